@@ -0,0 +1,713 @@
+//! Local automation API
+//!
+//! When enabled in settings, WindowLasso takes JSON-RPC 2.0 requests on a
+//! local socket (a named pipe on Windows), one JSON object per line. The app
+//! carries them out with the same message handlers as its hotkeys, so a
+//! request behaves exactly like the matching shortcut.
+//!
+//! ```text
+//! --> {"jsonrpc":"2.0","id":1,"method":"move_window","params":{"window":"notepad","monitor":2}}
+//! <-- {"jsonrpc":"2.0","id":1,"result":null}
+//! ```
+//!
+//! A connection that calls `subscribe` gets event notifications from then on
+//! and takes no further requests.
+
+use crate::cli::monitor_json;
+use crate::ipc::transport::{self, Stream};
+use crate::ipc::{read_line, write_message};
+use crate::matcher::{parse_handle, WindowMatcher};
+use crate::topology::TopologyChange;
+use crate::types::{MonitorInfo, WindowInfo};
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufReader, ErrorKind};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Endpoint automation clients connect to
+const API_ENDPOINT: &str = "window-lasso-api";
+
+/// How long a request may wait for the app to answer
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a connection may sit without sending a request
+#[cfg(unix)]
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The request was understood but couldn't be carried out
+const REQUEST_FAILED: i64 = -32000;
+
+/// Something a client asks the app to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiMethod {
+    /// Windows matching a query, or all of them
+    ListWindows {
+        query: Option<String>,
+    },
+    ListMonitors,
+    /// Move a window to a monitor given by position, ID or name
+    MoveWindow {
+        window: String,
+        monitor: String,
+    },
+    CenterWindow {
+        window: String,
+    },
+    /// Move every off-screen window to the primary monitor
+    RescueAll,
+    ListLayouts,
+    /// Save where every window is as a named layout
+    SaveLayout {
+        name: String,
+    },
+    /// Move windows back to where a saved layout has them
+    RestoreLayout {
+        name: String,
+    },
+    /// Re-read the settings file
+    ReloadSettings,
+}
+
+impl ApiMethod {
+    fn parse(method: &str, params: Value) -> Result<Self, RpcError> {
+        Ok(match method {
+            "list_windows" => {
+                let params: ListParams = parse_params(params)?;
+                ApiMethod::ListWindows {
+                    query: params.query,
+                }
+            }
+            "list_monitors" => ApiMethod::ListMonitors,
+            "move_window" => {
+                let params: MoveParams = parse_params(params)?;
+                ApiMethod::MoveWindow {
+                    window: params.window,
+                    monitor: params.monitor,
+                }
+            }
+            "center_window" => {
+                let params: WindowParams = parse_params(params)?;
+                ApiMethod::CenterWindow {
+                    window: params.window,
+                }
+            }
+            "rescue_all" => ApiMethod::RescueAll,
+            "list_layouts" => ApiMethod::ListLayouts,
+            "save_layout" => ApiMethod::SaveLayout {
+                name: parse_params::<LayoutParams>(params)?.name,
+            },
+            "restore_layout" => ApiMethod::RestoreLayout {
+                name: parse_params::<LayoutParams>(params)?.name,
+            },
+            "reload_settings" => ApiMethod::ReloadSettings,
+            _ => {
+                return Err(RpcError {
+                    code: METHOD_NOT_FOUND,
+                    message: format!("Unknown method: {}", method),
+                })
+            }
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ListParams {
+    #[serde(default)]
+    query: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WindowParams {
+    #[serde(deserialize_with = "text_or_number")]
+    window: String,
+}
+
+#[derive(Deserialize)]
+struct MoveParams {
+    #[serde(deserialize_with = "text_or_number")]
+    window: String,
+    #[serde(deserialize_with = "text_or_number")]
+    monitor: String,
+}
+
+#[derive(Deserialize)]
+struct LayoutParams {
+    name: String,
+}
+
+/// Accept window handles and monitor positions as numbers too
+fn text_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(text) => Ok(text),
+        Value::Number(number) => Ok(number.to_string()),
+        other => Err(de::Error::custom(format!(
+            "expected text or a number, got {}",
+            other
+        ))),
+    }
+}
+
+/// Read a method's params; leaving them out is the same as `{}`
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = match params {
+        Value::Null => json!({}),
+        params => params,
+    };
+    serde_json::from_value(params).map_err(|e| RpcError {
+        code: INVALID_PARAMS,
+        message: format!("Invalid params: {}", e),
+    })
+}
+
+/// The one window a request refers to, by handle or window query
+///
+/// Something that reads as a handle but isn't one is taken as a query, so
+/// "2048" still finds a window with that in its title.
+pub fn find_window<'a>(
+    query: &str,
+    windows: &'a [WindowInfo],
+    monitors: &[MonitorInfo],
+) -> Result<&'a WindowInfo, String> {
+    if let Some(hwnd) = parse_handle(query) {
+        if let Some(window) = windows.iter().find(|window| window.hwnd == hwnd) {
+            return Ok(window);
+        }
+    }
+
+    let matcher: WindowMatcher = query.parse()?;
+    match matcher.filter(windows, monitors).as_slice() {
+        [window] => Ok(*window),
+        [] => Err(format!("No window matches \"{}\"", query)),
+        matches => Err(format!(
+            "{} windows match \"{}\"; narrow the query or pass a handle",
+            matches.len(),
+            query
+        )),
+    }
+}
+
+/// A request waiting for the app
+#[derive(Debug)]
+pub struct ApiCall {
+    pub method: ApiMethod,
+    pub reply: ApiReply,
+}
+
+/// Where the answer to a request goes
+#[derive(Debug)]
+pub struct ApiReply(Sender<Result<Value, String>>);
+
+impl ApiReply {
+    pub fn send(&self, result: Result<Value, String>) {
+        // The client may have given up already
+        let _ = self.0.send(result);
+    }
+}
+
+/// Something that happened, sent to subscribed clients
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ApiEvent {
+    /// A move finished; `error` says why it failed
+    WindowMoved {
+        error: Option<String>,
+    },
+    /// Auto-rescue moved a window; `error` says why it failed
+    WindowRescued {
+        handle: isize,
+        title: String,
+        process: String,
+        monitor: String,
+        error: Option<String>,
+    },
+    /// A layout was restored; `failed` lists the windows that couldn't be
+    /// moved, with why
+    LayoutRestored {
+        name: String,
+        moved: usize,
+        skipped: Vec<String>,
+        failed: Vec<String>,
+    },
+    /// Windows went off-screen or came back; lists the ones off-screen now
+    OffscreenChanged {
+        windows: Value,
+    },
+    /// Monitors were connected, disconnected or rearranged
+    MonitorsChanged {
+        /// Fingerprint of the new arrangement
+        topology: String,
+        added: Vec<Value>,
+        removed: Vec<Value>,
+        /// Each as `{"before": ..., "after": ...}`
+        changed: Vec<Value>,
+        primary_changed: bool,
+    },
+    SettingsReloaded,
+}
+
+impl ApiEvent {
+    pub fn monitors_changed(topology: String, change: &TopologyChange) -> Self {
+        ApiEvent::MonitorsChanged {
+            topology,
+            added: change.added.iter().map(monitor_json).collect(),
+            removed: change.removed.iter().map(monitor_json).collect(),
+            changed: change
+                .changed
+                .iter()
+                .map(|(before, after)| {
+                    json!({ "before": monitor_json(before), "after": monitor_json(after) })
+                })
+                .collect(),
+            primary_changed: change.primary_changed(),
+        }
+    }
+}
+
+/// A JSON-RPC request
+#[derive(Deserialize)]
+struct RpcRequest {
+    /// Missing for notifications, which get no response; a `null` id is
+    /// still answered
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Keep a field that is there but `null` apart from a missing one
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn failed(message: impl Into<String>) -> Self {
+        Self {
+            code: REQUEST_FAILED,
+            message: message.into(),
+        }
+    }
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+/// Clients subscribed to events
+type Subscribers = Arc<Mutex<Vec<Sender<ApiEvent>>>>;
+
+/// Listens for automation clients, one thread per connection
+pub struct ApiServer {
+    calls: Receiver<ApiCall>,
+    subscribers: Subscribers,
+    stopped: Arc<AtomicBool>,
+}
+
+impl ApiServer {
+    /// Start listening
+    pub fn start() -> Result<Self, String> {
+        let listener = transport::Listener::bind(API_ENDPOINT).map_err(|e| e.to_string())?;
+        let (app, calls) = mpsc::channel();
+        let subscribers = Subscribers::default();
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let connection = Connection {
+            app,
+            subscribers: subscribers.clone(),
+        };
+        let stop = stopped.clone();
+        thread::Builder::new()
+            .name("api-server".to_string())
+            .spawn(move || {
+                listener.run(|stream| {
+                    if stop.load(Ordering::SeqCst) {
+                        return false;
+                    }
+                    let connection = connection.clone();
+                    if let Err(e) = thread::Builder::new()
+                        .name("api-connection".to_string())
+                        .spawn(move || connection.serve(stream))
+                    {
+                        warn!("Failed to serve API connection: {}", e);
+                    }
+                    true
+                })
+            })
+            .map_err(|e| format!("Failed to start API server: {}", e))?;
+
+        info!("Automation API is listening");
+        Ok(Self {
+            calls,
+            subscribers,
+            stopped,
+        })
+    }
+
+    /// Next request for the app, if any
+    pub fn poll(&self) -> Option<ApiCall> {
+        self.calls.try_recv().ok()
+    }
+
+    /// Send an event to every subscribed client
+    pub fn publish(&self, event: ApiEvent) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Ends the subscriptions
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.clear();
+        }
+        // Wake the listener so it sees it has stopped
+        let _ = transport::connect(API_ENDPOINT);
+    }
+}
+
+/// What a connection thread needs from the server
+#[derive(Clone)]
+struct Connection {
+    app: Sender<ApiCall>,
+    subscribers: Subscribers,
+}
+
+impl Connection {
+    /// Answer requests until the client disconnects or subscribes
+    fn serve(&self, stream: Stream) {
+        #[cfg(unix)]
+        let _ = stream.set_read_timeout(Some(IDLE_TIMEOUT));
+        let mut reader = BufReader::new(&stream);
+        let mut writer = &stream;
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            match read_line(&mut reader, &mut line) {
+                Ok(0) => return,
+                Ok(_) if line.trim().is_empty() => continue,
+                Ok(_) => {}
+                // Too long, or not text; there's no telling where the next
+                // request starts, so answer and hang up
+                Err(e) if e.kind() == ErrorKind::InvalidData => {
+                    let error = RpcError {
+                        code: PARSE_ERROR,
+                        message: format!("Invalid request: {}", e),
+                    };
+                    let _ = write_message(&mut writer, &response(Value::Null, Err(error)));
+                    return;
+                }
+                Err(_) => return,
+            }
+
+            let (id, result) = match serde_json::from_str::<RpcRequest>(&line) {
+                Err(e) => (
+                    Some(Value::Null),
+                    Err(RpcError {
+                        code: PARSE_ERROR,
+                        message: format!("Invalid request: {}", e),
+                    }),
+                ),
+                Ok(request) if request.method == "subscribe" => {
+                    let events = self.subscribe();
+                    if let Some(id) = request.id {
+                        if write_message(&mut writer, &response(id, Ok(Value::Bool(true)))).is_err()
+                        {
+                            return;
+                        }
+                    }
+                    self.stream_events(writer, events);
+                    return;
+                }
+                Ok(request) => (request.id.clone(), self.call(request)),
+            };
+
+            let Some(id) = id else {
+                continue;
+            };
+            if write_message(&mut writer, &response(id, result)).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Hand a request to the app and wait for its answer
+    fn call(&self, request: RpcRequest) -> Result<Value, RpcError> {
+        let method = ApiMethod::parse(&request.method, request.params)?;
+        info!("API request: {:?}", method);
+
+        let (reply, answer) = mpsc::channel();
+        self.app
+            .send(ApiCall {
+                method,
+                reply: ApiReply(reply),
+            })
+            .map_err(|_| RpcError::failed("The automation API was turned off"))?;
+
+        match answer.recv_timeout(REPLY_TIMEOUT) {
+            Ok(result) => result.map_err(RpcError::failed),
+            Err(RecvTimeoutError::Timeout) => Err(RpcError::failed("WindowLasso didn't answer")),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err(RpcError::failed("The request couldn't be carried out"))
+            }
+        }
+    }
+
+    fn subscribe(&self) -> Receiver<ApiEvent> {
+        let (sender, events) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        events
+    }
+
+    /// Forward events as notifications until the client goes away
+    fn stream_events(&self, mut writer: &Stream, events: Receiver<ApiEvent>) {
+        for event in events {
+            let notification = json!({ "jsonrpc": "2.0", "method": "event", "params": event });
+            if write_message(&mut writer, &notification).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::{monitor, rect, window};
+
+    fn windows() -> Vec<WindowInfo> {
+        vec![
+            window(
+                101,
+                "notepad.exe",
+                "notes.txt - Notepad",
+                rect(0, 0, 800, 600),
+            ),
+            window(102, "game.exe", "2048", rect(100, 100, 500, 500)),
+            window(
+                103,
+                "notepad.exe",
+                "todo.txt - Notepad",
+                rect(0, 0, 800, 600),
+            ),
+        ]
+    }
+
+    fn found(query: &str) -> Result<isize, String> {
+        let monitors = [monitor(0, rect(0, 0, 1920, 1080), 96)];
+        find_window(query, &windows(), &monitors).map(|w| w.hwnd)
+    }
+
+    #[test]
+    fn handles_come_first() {
+        assert_eq!(found("101"), Ok(101));
+        assert_eq!(found("0x66"), Ok(102));
+    }
+
+    #[test]
+    fn numbers_that_are_no_handle_are_queries() {
+        assert_eq!(found("2048"), Ok(102));
+        assert_eq!(found("1234"), Err("No window matches \"1234\"".to_string()));
+    }
+
+    #[test]
+    fn queries_must_pick_one_window() {
+        assert_eq!(found("todo"), Ok(103));
+        assert!(found("process:notepad")
+            .unwrap_err()
+            .starts_with("2 windows match"));
+    }
+
+    /// `Connection::serve` over a socket pair, with a fake app on the
+    /// other end of the call channel
+    #[cfg(unix)]
+    mod protocol {
+        use super::*;
+        use crate::ipc::{read_message, MAX_MESSAGE_LEN};
+        use std::io::Write;
+        use std::os::unix::net::UnixStream;
+        use std::thread::JoinHandle;
+
+        struct Client {
+            stream: UnixStream,
+            reader: BufReader<UnixStream>,
+            subscribers: Subscribers,
+            serving: JoinHandle<()>,
+        }
+
+        impl Client {
+            /// Serve a connection whose calls the app answers with their
+            /// method name
+            fn connect() -> Self {
+                let (app, calls) = mpsc::channel::<ApiCall>();
+                thread::spawn(move || {
+                    for call in calls {
+                        call.reply.send(Ok(json!(format!("{:?}", call.method))));
+                    }
+                });
+
+                let (stream, server) = UnixStream::pair().unwrap();
+                let subscribers = Subscribers::default();
+                let connection = Connection {
+                    app,
+                    subscribers: subscribers.clone(),
+                };
+                let serving = thread::spawn(move || connection.serve(server));
+                Self {
+                    reader: BufReader::new(stream.try_clone().unwrap()),
+                    stream,
+                    subscribers,
+                    serving,
+                }
+            }
+
+            fn send(&mut self, line: &str) {
+                self.stream.write_all(line.as_bytes()).unwrap();
+                self.stream.write_all(b"\n").unwrap();
+            }
+
+            fn receive(&mut self) -> Value {
+                read_message(&mut self.reader).unwrap()
+            }
+
+            /// Send a request and read its response
+            fn request(&mut self, line: &str) -> Value {
+                self.send(line);
+                self.receive()
+            }
+
+            /// Whether the server hung up
+            fn closed(mut self) -> bool {
+                self.serving.join().unwrap();
+                let mut line = String::new();
+                read_line(&mut self.reader, &mut line).unwrap() == 0
+            }
+        }
+
+        #[test]
+        fn requests_are_answered_by_the_app() {
+            let mut client = Client::connect();
+            let response =
+                client.request(r#"{"jsonrpc":"2.0","id":1,"method":"list_monitors"}"#);
+            assert_eq!(
+                response,
+                json!({ "jsonrpc": "2.0", "id": 1, "result": "ListMonitors" })
+            );
+        }
+
+        #[test]
+        fn invalid_json_is_a_parse_error() {
+            let mut client = Client::connect();
+            let response = client.request("{not json");
+            assert_eq!(response["id"], Value::Null);
+            assert_eq!(response["error"]["code"], PARSE_ERROR);
+        }
+
+        #[test]
+        fn unknown_methods_are_reported() {
+            let mut client = Client::connect();
+            let response = client.request(r#"{"jsonrpc":"2.0","id":2,"method":"explode"}"#);
+            assert_eq!(response["id"], 2);
+            assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        }
+
+        #[test]
+        fn bad_params_are_reported() {
+            let mut client = Client::connect();
+            for request in [
+                r#"{"jsonrpc":"2.0","id":3,"method":"move_window","params":{"window":true,"monitor":1}}"#,
+                r#"{"jsonrpc":"2.0","id":3,"method":"center_window"}"#,
+                r#"{"jsonrpc":"2.0","id":3,"method":"save_layout","params":[]}"#,
+            ] {
+                let response = client.request(request);
+                assert_eq!(response["id"], 3, "{}", request);
+                assert_eq!(response["error"]["code"], INVALID_PARAMS, "{}", request);
+            }
+        }
+
+        #[test]
+        fn notifications_get_no_response() {
+            let mut client = Client::connect();
+            client.send(r#"{"jsonrpc":"2.0","method":"reload_settings"}"#);
+            client.send(r#"{"jsonrpc":"2.0","method":"explode"}"#);
+            let response =
+                client.request(r#"{"jsonrpc":"2.0","id":4,"method":"list_layouts"}"#);
+            assert_eq!(response["id"], 4);
+            assert_eq!(response["result"], "ListLayouts");
+        }
+
+        #[test]
+        fn a_null_id_is_answered() {
+            let mut client = Client::connect();
+            let response =
+                client.request(r#"{"jsonrpc":"2.0","id":null,"method":"rescue_all"}"#);
+            assert_eq!(
+                response,
+                json!({ "jsonrpc": "2.0", "id": null, "result": "RescueAll" })
+            );
+        }
+
+        #[test]
+        fn subscribers_receive_events() {
+            let mut client = Client::connect();
+            let response = client.request(r#"{"jsonrpc":"2.0","id":5,"method":"subscribe"}"#);
+            assert_eq!(
+                response,
+                json!({ "jsonrpc": "2.0", "id": 5, "result": true })
+            );
+
+            // Subscribed before the response was written
+            for subscriber in client.subscribers.lock().unwrap().iter() {
+                subscriber.send(ApiEvent::SettingsReloaded).unwrap();
+            }
+            assert_eq!(
+                client.receive(),
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "event",
+                    "params": { "event": "settings_reloaded" },
+                })
+            );
+
+            client.subscribers.lock().unwrap().clear();
+            assert!(client.closed());
+        }
+
+        #[test]
+        fn overlong_lines_are_answered_before_hanging_up() {
+            let mut client = Client::connect();
+            client
+                .stream
+                .write_all(&vec![b'x'; MAX_MESSAGE_LEN])
+                .unwrap();
+            let response = client.receive();
+            assert_eq!(response["id"], Value::Null);
+            assert_eq!(response["error"]["code"], PARSE_ERROR);
+            assert!(client.closed());
+        }
+    }
+}
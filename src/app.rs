@@ -1,22 +1,53 @@
 //! Main application state and message handling
 
-use crate::hotkeys::{self, HotkeyManager};
+use crate::api::{find_window, ApiCall, ApiEvent, ApiMethod, ApiReply, ApiServer};
+use crate::backend::{self, monitor_for_rect, WindowBackend};
+use crate::cli;
+use crate::hotkeys::{self, duplicate_conflicts, HotkeyConflicts, HotkeyEvent, HotkeyManager};
+use crate::ipc::{InstanceServer, IpcRequest};
+use crate::keycodes;
+use crate::layouts::{self, layout_for_topology, Layout};
 use crate::localization::Localization;
+use crate::matcher::WindowMatcher;
+use crate::placement::{PlacementMode, PlacementPolicy, PlacementTarget, ScalingMode};
+use crate::rescue::{is_excluded, rescue_monitor, RecoveryCheck, RescueTracker};
 use crate::settings::{load_settings, save_settings};
+use crate::topology::{
+    diff_monitors, find_monitor, topology_fingerprint, Direction, MonitorTarget, TopologyChange,
+};
 use crate::tray::{self, SystemTray, TrayMenuAction};
-use crate::types::{AppSettings, HotkeyAction, HotkeyBinding, MonitorInfo, Screen, WindowInfo};
-use crate::views::{main_view, monitor_picker, settings_view, tray_dialog};
-use crate::windows_api;
+use crate::types::{
+    AppSettings, HotkeyAction, HotkeyBinding, MonitorInfo, Screen, TopologyAction, WindowInfo,
+};
+use crate::views::{leader_hint, main_view, monitor_picker, settings_view, tray_dialog};
+use crate::zones::resolve_zone;
+use iced::keyboard::key::Physical;
 use iced::keyboard::{self, Key, Modifiers};
 use iced::time::{self, Duration};
 use iced::{event, Element, Event, Subscription, Task, Theme};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// How long to wait after monitors change before rescuing windows, so the
+/// system can move windows itself first
+const TOPOLOGY_SETTLE_TIME: Duration = Duration::from_secs(2);
 
 /// Application state
 pub struct App {
+    /// Platform window management backend
+    backend: Arc<dyn WindowBackend>,
     /// List of open windows
     windows: Vec<WindowInfo>,
     /// List of connected monitors
     monitors: Vec<MonitorInfo>,
+    /// Fingerprint of the current monitor arrangement
+    topology: Option<String>,
+    /// Query typed into the window list's search box
+    window_filter: String,
+    /// The parsed query, or why it couldn't be parsed
+    window_matcher: Result<WindowMatcher, String>,
     /// Current screen/view
     screen: Screen,
     /// Application settings
@@ -27,17 +58,42 @@ pub struct App {
     status_message: Option<String>,
     /// Whether we're showing the tray dialog
     show_tray_dialog: bool,
+    /// The main application window
+    main_window: iced::window::Id,
     /// The window ID that requested close (for tray dialog)
     pending_close_window: Option<iced::window::Id>,
-    /// Whether we're editing a hotkey
-    editing_hotkey: Option<HotkeyAction>,
+    /// Hotkey binding being recorded (action, binding index; `None` adds one)
+    editing_hotkey: Option<(HotkeyAction, Option<usize>)>,
+    /// Monitor being renamed (stable ID, draft nickname)
+    editing_nickname: Option<(String, String)>,
     /// System tray (kept alive)
     #[allow(dead_code)]
     tray: Option<SystemTray>,
     /// Hotkey manager
     hotkey_manager: Option<HotkeyManager>,
-    /// Whether to check for close-after-recovery on next WindowsLoaded
-    pending_recovery_check: bool,
+    /// Bindings that aren't active, shown in settings
+    hotkey_conflicts: HotkeyConflicts,
+    /// Receives commands from later launches
+    instance_server: Option<InstanceServer>,
+    /// Automation API, when enabled in settings
+    api_server: Option<ApiServer>,
+    /// API requests waiting for a fresh window list
+    pending_api_calls: Vec<ApiCall>,
+    /// Windows going off-screen, for auto-rescue
+    rescue_tracker: RescueTracker,
+    /// What to do once the window list is fresh after monitors changed
+    pending_topology_action: Option<TopologyAction>,
+    /// Names of the saved layouts
+    layouts: Vec<String>,
+    /// Layout name typed into the layouts bar
+    layout_name: String,
+    /// Popup listing the leader keys, while the leader chord waits for a key
+    leader_hint: Option<iced::window::Id>,
+    /// Window hotkey handlers act on instead of the focused one: the window
+    /// focused when the leader chord was pressed, or one named by an API call
+    target_window: Option<isize>,
+    /// Whether the next window list should close the app after a recovery
+    recovery_check: RecoveryCheck,
 }
 
 /// Application messages
@@ -46,10 +102,39 @@ pub enum Message {
     // Window operations
     RefreshWindows,
     WindowsLoaded(Vec<WindowInfo>, Vec<MonitorInfo>),
+    FilterWindows(String),
     SelectWindow(WindowInfo),
     MoveToMonitor(MonitorInfo),
+    PlaceOnMonitor(MonitorInfo, PlacementTarget),
     CancelSelection,
     WindowMoved(Result<(), String>),
+    /// Off-screen windows were moved to the primary monitor, each with how
+    /// its move went
+    AllMovedToPrimary(Vec<(WindowInfo, Result<(), String>)>),
+    /// Monitors were listed without the windows, to notice changes while
+    /// the window list isn't being refreshed
+    MonitorsPolled(Vec<MonitorInfo>),
+    /// Monitors were connected, disconnected or rearranged
+    MonitorsChanged(TopologyChange),
+    /// Monitors changed a while ago, so the system is done moving windows
+    ApplyTopologyAction(TopologyAction),
+    /// Windows moved by auto-rescue, with the monitor they went to
+    AutoRescued(Vec<(WindowInfo, String, Result<(), String>)>),
+
+    // Layouts
+    LayoutNameInput(String),
+    SaveLayout,
+    RestoreLayout(String),
+    DeleteLayout(String),
+    /// A layout was restored: its name, the saved windows left out, and each
+    /// moved window with how its move went
+    LayoutRestored(String, Vec<String>, Vec<(WindowInfo, Result<(), String>)>),
+
+    // Monitor nicknames
+    EditMonitorNickname(MonitorInfo),
+    MonitorNicknameInput(String),
+    SaveMonitorNickname,
+    CancelNicknameEdit,
 
     // Settings
     OpenSettings,
@@ -58,16 +143,23 @@ pub enum Message {
     SetMinimizeToTray(Option<bool>),
     SetAutoFocusAfterLasso(bool),
     SetCloseAfterRecovery(bool),
-    EditHotkey(HotkeyAction),
+    SetPlacementMode(PlacementMode),
+    SetScalingMode(ScalingMode),
+    SetApiEnabled(bool),
+    SetAutoRescue(bool),
+    SetTopologyAction(TopologyAction),
+    ReloadSettings,
+    EditHotkey(HotkeyAction, Option<usize>),
     CancelHotkeyEdit,
-    UpdateHotkey(HotkeyAction, HotkeyBinding),
-    ToggleHotkey(HotkeyAction, bool),
+    UpdateHotkey(HotkeyAction, Option<usize>, HotkeyBinding),
+    ToggleHotkey(HotkeyAction, usize, bool),
+    RemoveHotkey(HotkeyAction, usize),
 
     // External links
     OpenUrl(String),
 
     // Keyboard input (for hotkey recording)
-    KeyPressed(Key, Modifiers),
+    KeyPressed(Key, Physical, Modifiers),
 
     // Tray dialog
     TrayDialogResponse(bool),
@@ -83,6 +175,10 @@ pub enum Message {
     HotkeyMoveAllToPrimary,
     HotkeyCenterWindow,
     HotkeyNextMonitor,
+    HotkeyPreviousMonitor,
+    HotkeySendToMonitor(MonitorTarget),
+    HotkeyMoveInDirection(Direction),
+    HotkeySnapToZone(String),
 
     // Tray events
     TrayMenuEvent(TrayMenuAction),
@@ -90,6 +186,7 @@ pub enum Message {
 
     // Timer/polling
     Tick,
+    PollMonitors,
     PollEvents,
 
     // Status
@@ -97,8 +194,12 @@ pub enum Message {
 }
 
 impl App {
-    pub fn new() -> (Self, Task<Message>) {
+    pub fn new(
+        main_window: iced::window::Settings,
+        instance_server: Option<InstanceServer>,
+    ) -> (Self, Task<Message>) {
         let settings = load_settings();
+        let backend = backend::default_backend();
         let loc = Localization::new(&settings.language);
 
         // Initialize system tray
@@ -106,39 +207,64 @@ impl App {
 
         // Initialize hotkey manager
         let mut hotkey_manager = HotkeyManager::new().ok();
-        if let Some(ref mut manager) = hotkey_manager {
-            manager.register_from_settings(&settings.hotkeys);
-        }
+        let hotkey_conflicts = match hotkey_manager {
+            Some(ref mut manager) => manager.register_from_settings(&settings.hotkeys),
+            None => duplicate_conflicts(&settings.hotkeys),
+        };
+
+        let api_server = if settings.api_enabled {
+            start_api_server()
+        } else {
+            None
+        };
 
-        let app = Self {
+        let (main_window, open_main_window) = iced::window::open(main_window);
+
+        let mut app = Self {
+            backend,
             windows: Vec::new(),
             monitors: Vec::new(),
+            topology: None,
+            window_filter: String::new(),
+            window_matcher: Ok(WindowMatcher::default()),
             screen: Screen::Main,
             settings,
             loc,
             status_message: None,
             show_tray_dialog: false,
+            main_window,
             pending_close_window: None,
             editing_hotkey: None,
+            editing_nickname: None,
             tray,
             hotkey_manager,
-            pending_recovery_check: false,
+            hotkey_conflicts,
+            instance_server,
+            api_server,
+            pending_api_calls: Vec::new(),
+            rescue_tracker: RescueTracker::default(),
+            pending_topology_action: None,
+            layouts: Vec::new(),
+            layout_name: String::new(),
+            leader_hint: None,
+            target_window: None,
+            recovery_check: RecoveryCheck::default(),
         };
 
         // Load windows on startup
-        (
-            app,
-            Task::perform(load_windows_and_monitors(), |(w, m)| {
-                Message::WindowsLoaded(w, m)
-            }),
-        )
+        app.load_layout_names();
+        let task = Task::batch([open_main_window.discard(), app.reload()]);
+        (app, task)
     }
 
-    pub fn title(&self) -> String {
+    pub fn title(&self, window: iced::window::Id) -> String {
+        if Some(window) == self.leader_hint {
+            return self.loc.get(crate::localization::keys::HOTKEY_LEADER);
+        }
         self.loc.get(crate::localization::keys::APP_TITLE)
     }
 
-    pub fn theme(&self) -> Theme {
+    pub fn theme(&self, _window: iced::window::Id) -> Theme {
         if self.settings.theme.dark_mode {
             Theme::Dark
         } else {
@@ -146,15 +272,376 @@ impl App {
         }
     }
 
+    /// Task that re-enumerates windows and monitors
+    fn reload(&self) -> Task<Message> {
+        Task::perform(
+            load_windows_and_monitors(
+                self.backend.clone(),
+                self.settings.monitor_nicknames.clone(),
+            ),
+            |(w, m)| Message::WindowsLoaded(w, m),
+        )
+    }
+
+    /// Re-register hotkeys from settings and record the ones that failed
+    fn register_hotkeys(&mut self) {
+        self.hotkey_conflicts = match self.hotkey_manager {
+            Some(ref mut manager) => manager.register_from_settings(&self.settings.hotkeys),
+            None => duplicate_conflicts(&self.settings.hotkeys),
+        };
+    }
+
+    /// Window a hotkey acts on
+    ///
+    /// After the leader chord this is the window focused when it was pressed,
+    /// since the hint popup takes focus.
+    fn hotkey_target(&self) -> Option<isize> {
+        self.target_window
+            .or_else(|| self.backend.get_foreground_window())
+    }
+
+    /// Run a hotkey handler on a given window rather than the focused one
+    fn update_for_window(&mut self, hwnd: isize, message: Message) -> Task<Message> {
+        let target_window = self.target_window.replace(hwnd);
+        let task = self.update(message);
+        self.target_window = target_window;
+        task
+    }
+
+    /// Start or stop the automation API to match settings
+    fn update_api_server(&mut self) {
+        if self.settings.api_enabled == self.api_server.is_some() {
+            return;
+        }
+        self.api_server = if self.settings.api_enabled {
+            start_api_server()
+        } else {
+            None
+        };
+    }
+
+    /// Move windows that have stayed off-screen for the grace period
+    fn auto_rescue(&mut self) -> Task<Message> {
+        let settings = &self.settings.auto_rescue;
+        if !settings.enabled {
+            return Task::none();
+        }
+
+        let due: Vec<WindowInfo> = self
+            .rescue_tracker
+            .update(&self.windows, settings, Instant::now())
+            .into_iter()
+            .cloned()
+            .collect();
+        self.rescue_windows(due)
+    }
+
+    /// Move every off-screen window that isn't excluded
+    fn rescue_offscreen(&self) -> Task<Message> {
+        let settings = &self.settings.auto_rescue;
+        let windows = self
+            .windows
+            .iter()
+            .filter(|w| w.is_offscreen && !is_excluded(w, settings))
+            .cloned()
+            .collect();
+        self.rescue_windows(windows)
+    }
+
+    /// Move windows to the auto-rescue fallback monitor
+    fn rescue_windows(&self, windows: Vec<WindowInfo>) -> Task<Message> {
+        if windows.is_empty() {
+            return Task::none();
+        }
+        let Some(monitor) = rescue_monitor(&self.settings.auto_rescue, &self.monitors).cloned()
+        else {
+            tracing::warn!("No monitor to rescue {} window(s) to", windows.len());
+            return Task::none();
+        };
+
+        let backend = self.backend.clone();
+        let policy = self.placement_policy(false);
+        Task::perform(
+            async move {
+                windows
+                    .into_iter()
+                    .map(|window| {
+                        // Off-screen windows have no source monitor, so they
+                        // are centered without scaling
+                        let result = backend.move_window_to_monitor_with_options(
+                            window.hwnd,
+                            &monitor,
+                            None,
+                            &policy,
+                            false,
+                        );
+                        (window, monitor.display_name().to_string(), result)
+                    })
+                    .collect()
+            },
+            Message::AutoRescued,
+        )
+    }
+
+    /// Re-read the names of the saved layouts
+    fn load_layout_names(&mut self) {
+        self.layouts = layouts::list_layouts()
+            .into_iter()
+            .map(|layout| layout.name)
+            .collect();
+    }
+
+    /// Move windows back to where a layout has them
+    fn restore_layout(&self, layout: Layout) -> Task<Message> {
+        let plan = layout.plan_restore(&self.windows, &self.monitors);
+        let backend = self.backend.clone();
+        let name = layout.name;
+        Task::perform(
+            async move {
+                let skipped = plan.skipped.clone();
+                (name, skipped, plan.apply(backend.as_ref()))
+            },
+            |(name, skipped, results)| Message::LayoutRestored(name, skipped, results),
+        )
+    }
+
+    /// Restore the layout most recently saved on the monitors connected now
+    fn restore_layout_for_topology(&self) -> Task<Message> {
+        let topology = self.topology.as_deref().unwrap_or_default();
+        let layouts = layouts::list_layouts();
+        match layout_for_topology(&layouts, topology) {
+            Some(layout) => {
+                tracing::info!(
+                    "Restoring layout \"{}\" for monitor topology {}",
+                    layout.name,
+                    topology
+                );
+                self.restore_layout(layout.clone())
+            }
+            None => {
+                tracing::info!("No layout saved for monitor topology {}", topology);
+                Task::none()
+            }
+        }
+    }
+
+    /// Tell automation clients about an event
+    fn publish(&self, event: ApiEvent) {
+        if let Some(server) = &self.api_server {
+            server.publish(event);
+        }
+    }
+
+    /// Carry out a request from the automation API
+    ///
+    /// Moves go through the hotkey handlers and are answered once they finish.
+    fn handle_api_call(&mut self, call: ApiCall) -> Task<Message> {
+        let ApiCall { method, reply } = call;
+        match method {
+            ApiMethod::ListWindows { query } => {
+                let matcher = query.as_deref().unwrap_or_default().parse::<WindowMatcher>();
+                reply.send(matcher.map(|matcher| {
+                    cli::windows_json(matcher.filter(&self.windows, &self.monitors))
+                }));
+                Task::none()
+            }
+            ApiMethod::ListMonitors => {
+                reply.send(Ok(cli::monitors_json(&self.monitors)));
+                Task::none()
+            }
+            ApiMethod::MoveWindow { window, monitor } => {
+                let target = find_window(&window, &self.windows, &self.monitors).and_then(|w| {
+                    find_monitor(&monitor, &self.monitors)
+                        .map(|m| (w.hwnd, m.stable_id.clone()))
+                        .ok_or_else(|| format!("No monitor matches \"{}\"", monitor))
+                });
+                match target {
+                    Ok((hwnd, id)) => {
+                        let message = Message::HotkeySendToMonitor(MonitorTarget::Id(id));
+                        reply_when_moved(self.update_for_window(hwnd, message), reply)
+                    }
+                    Err(e) => {
+                        reply.send(Err(e));
+                        Task::none()
+                    }
+                }
+            }
+            ApiMethod::CenterWindow { window } => {
+                match find_window(&window, &self.windows, &self.monitors).map(|w| w.hwnd) {
+                    Ok(hwnd) => {
+                        let task = self.update_for_window(hwnd, Message::HotkeyCenterWindow);
+                        reply_when_moved(task, reply)
+                    }
+                    Err(e) => {
+                        reply.send(Err(e));
+                        Task::none()
+                    }
+                }
+            }
+            ApiMethod::RescueAll => {
+                if !self.monitors.iter().any(|m| m.is_primary) {
+                    reply.send(Err("No primary monitor found".to_string()));
+                    return Task::none();
+                }
+                if !self.windows.iter().any(|w| w.is_offscreen) {
+                    reply.send(Ok(json!({ "rescued": 0, "failed": [] })));
+                    return Task::none();
+                }
+                self.update(Message::HotkeyMoveAllToPrimary).map(move |message| {
+                    if let Message::AllMovedToPrimary(results) = &message {
+                        let (rescued, failed) = move_summary(results);
+                        reply.send(Ok(json!({ "rescued": rescued, "failed": failed })));
+                    }
+                    message
+                })
+            }
+            ApiMethod::ListLayouts => {
+                let topology = self.topology.as_deref().unwrap_or_default();
+                reply.send(Ok(cli::layouts_json(&layouts::list_layouts(), topology)));
+                Task::none()
+            }
+            ApiMethod::SaveLayout { name } => {
+                let layout = Layout::capture(&name, &self.windows, &self.monitors);
+                let result = layouts::save_layout(&layout);
+                reply.send(result.map(|()| json!({ "windows": layout.windows.len() })));
+                self.load_layout_names();
+                Task::none()
+            }
+            ApiMethod::RestoreLayout { name } => match layouts::load_layout(&name) {
+                Ok(layout) => self.restore_layout(layout).map(move |message| {
+                    if let Message::LayoutRestored(_, skipped, results) = &message {
+                        let (moved, failed) = move_summary(results);
+                        reply.send(Ok(json!({
+                            "moved": moved,
+                            "skipped": skipped,
+                            "failed": failed,
+                        })));
+                    }
+                    message
+                }),
+                Err(e) => {
+                    reply.send(Err(e));
+                    Task::none()
+                }
+            },
+            ApiMethod::ReloadSettings => {
+                reply.send(Ok(Value::Null));
+                self.update(Message::ReloadSettings)
+            }
+        }
+    }
+
+    /// Respond to a global hotkey
+    fn handle_hotkey_event(&mut self, event: HotkeyEvent) -> Task<Message> {
+        let action = match event {
+            HotkeyEvent::LeaderStarted => return self.show_leader_hint(),
+            HotkeyEvent::LeaderCancelled => return self.hide_leader_hint(),
+            HotkeyEvent::Action(action) => action,
+        };
+
+        let task = match action {
+            HotkeyAction::LassoWindow => self.update(Message::HotkeyLasso),
+            HotkeyAction::RefreshWindows => self.update(Message::HotkeyRefresh),
+            HotkeyAction::MoveToPrimary => self.update(Message::HotkeyMoveToPrimary),
+            HotkeyAction::MoveAllToPrimary => self.update(Message::HotkeyMoveAllToPrimary),
+            HotkeyAction::CenterWindow => self.update(Message::HotkeyCenterWindow),
+            HotkeyAction::NextMonitor => self.update(Message::HotkeyNextMonitor),
+            HotkeyAction::PreviousMonitor => self.update(Message::HotkeyPreviousMonitor),
+            HotkeyAction::SendToMonitor(target) => {
+                self.update(Message::HotkeySendToMonitor(target))
+            }
+            HotkeyAction::MoveInDirection(direction) => {
+                self.update(Message::HotkeyMoveInDirection(direction))
+            }
+            HotkeyAction::SnapToZone(zone) => self.update(Message::HotkeySnapToZone(zone)),
+            // The manager turns the leader chord into leader events
+            HotkeyAction::Leader => Task::none(),
+        };
+        Task::batch([task, self.hide_leader_hint()])
+    }
+
+    /// Remember the focused window and open the leader key popup
+    fn show_leader_hint(&mut self) -> Task<Message> {
+        self.target_window = self.backend.get_foreground_window();
+        if self.leader_hint.is_some() {
+            return Task::none();
+        }
+
+        let (id, open) = iced::window::open(iced::window::Settings {
+            size: leader_hint::window_size(&self.settings),
+            position: iced::window::Position::Centered,
+            resizable: false,
+            decorations: false,
+            level: iced::window::Level::AlwaysOnTop,
+            exit_on_close_request: false,
+            #[cfg(windows)]
+            platform_specific: iced::window::settings::PlatformSpecific {
+                skip_taskbar: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        self.leader_hint = Some(id);
+        open.discard()
+    }
+
+    /// Close the leader key popup, if it is open
+    fn hide_leader_hint(&mut self) -> Task<Message> {
+        self.target_window = None;
+        match self.leader_hint.take() {
+            Some(id) => iced::window::close(id),
+            None => Task::none(),
+        }
+    }
+
+    /// Placement policy for moves, based on the user's settings
+    fn placement_policy(&self, maximize: bool) -> PlacementPolicy {
+        self.settings.placement_policy(maximize)
+    }
+
+    /// Move the window selected in the monitor picker and return to the main screen
+    fn move_selected_window(
+        &mut self,
+        monitor: MonitorInfo,
+        policy: PlacementPolicy,
+    ) -> Task<Message> {
+        let Screen::MonitorPicker { selected_window } = &self.screen else {
+            return Task::none();
+        };
+
+        let hwnd = selected_window.hwnd;
+        // Off-screen windows have no source monitor, so they are
+        // centered without scaling
+        let source_monitor = policy
+            .needs_source_monitor()
+            .then(|| monitor_for_rect(&selected_window.rect, &self.monitors).cloned())
+            .flatten();
+        let auto_focus = self.settings.auto_focus_after_lasso;
+        let backend = self.backend.clone();
+        self.screen = Screen::Main;
+
+        Task::perform(
+            async move {
+                backend.move_window_to_monitor_with_options(
+                    hwnd,
+                    &monitor,
+                    source_monitor.as_ref(),
+                    &policy,
+                    auto_focus,
+                )
+            },
+            Message::WindowMoved,
+        )
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::RefreshWindows => {
+                self.load_layout_names();
                 self.status_message =
                     Some(self.loc.get(crate::localization::keys::STATUS_REFRESHED));
                 Task::batch([
-                    Task::perform(load_windows_and_monitors(), |(w, m)| {
-                        Message::WindowsLoaded(w, m)
-                    }),
+                    self.reload(),
                     Task::perform(
                         async { tokio::time::sleep(tokio::time::Duration::from_secs(2)).await },
                         |_| Message::ClearStatus,
@@ -163,21 +650,60 @@ impl App {
             }
 
             Message::WindowsLoaded(windows, monitors) => {
-                let had_offscreen_before = self.windows.iter().any(|w| w.is_offscreen);
-                self.windows = windows;
-                self.monitors = monitors;
-                
-                // Check if we should close after recovery
-                if self.pending_recovery_check {
-                    self.pending_recovery_check = false;
-                    let has_offscreen_now = self.windows.iter().any(|w| w.is_offscreen);
-                    
-                    // Close if close_after_recovery is enabled and no more off-screen windows
-                    if self.settings.close_after_recovery && had_offscreen_before && !has_offscreen_now {
-                        return iced::exit();
-                    }
+                let offscreen_before = offscreen_handles(&self.windows);
+                let previous_windows = std::mem::replace(&mut self.windows, windows);
+                let previous_monitors = std::mem::replace(&mut self.monitors, monitors);
+
+                // The first list has nothing to compare with
+                let change = diff_monitors(&previous_monitors, &self.monitors);
+                let monitors_changed = if self.topology.is_some() && !change.is_empty() {
+                    Task::done(Message::MonitorsChanged(change))
+                } else {
+                    Task::none()
+                };
+
+                let topology = topology_fingerprint(&self.monitors);
+                if self.topology.as_ref() != Some(&topology) {
+                    tracing::info!("Monitor topology is now {}", topology);
+                    self.topology = Some(topology);
                 }
+
+                if offscreen_handles(&self.windows) != offscreen_before {
+                    self.publish(ApiEvent::OffscreenChanged {
+                        windows: cli::windows_json(self.windows.iter().filter(|w| w.is_offscreen)),
+                    });
+                }
+
+                let rescue = match self.pending_topology_action.take() {
+                    Some(TopologyAction::RescueOffscreen) => self.rescue_offscreen(),
+                    Some(TopologyAction::RestoreLayout) => self.restore_layout_for_topology(),
+                    Some(TopologyAction::Nothing) | None => self.auto_rescue(),
+                };
+
+                // API requests waited for this list
+                let api_tasks: Vec<_> = std::mem::take(&mut self.pending_api_calls)
+                    .into_iter()
+                    .map(|call| self.handle_api_call(call))
+                    .collect();
                 
+                // Close if a move brought back the last off-screen window
+                if self.recovery_check.windows_loaded(
+                    self.settings.close_after_recovery,
+                    &previous_windows,
+                    &self.windows,
+                ) {
+                    // Answer the API requests and finish reacting to the
+                    // monitor change before going
+                    return Task::batch(api_tasks.into_iter().chain([rescue, monitors_changed]))
+                        .chain(iced::exit());
+                }
+
+                Task::batch(api_tasks.into_iter().chain([rescue, monitors_changed]))
+            }
+
+            Message::FilterWindows(filter) => {
+                self.window_matcher = filter.parse();
+                self.window_filter = filter;
                 Task::none()
             }
 
@@ -189,41 +715,76 @@ impl App {
             }
 
             Message::MoveToMonitor(monitor) => {
-                if let Screen::MonitorPicker { selected_window } = &self.screen {
-                    let hwnd = selected_window.hwnd;
-                    let monitor_clone = monitor.clone();
-                    let auto_focus = self.settings.auto_focus_after_lasso;
-                    self.screen = Screen::Main;
+                let policy = self.placement_policy(true);
+                self.move_selected_window(monitor, policy)
+            }
 
-                    Task::perform(
-                        async move {
-                            windows_api::move_window_to_monitor_with_options(
-                                hwnd,
-                                &monitor_clone,
-                                None,
-                                true,
-                                auto_focus,
-                            )
-                        },
-                        Message::WindowMoved,
-                    )
-                } else {
-                    Task::none()
-                }
+            Message::PlaceOnMonitor(monitor, target) => {
+                let policy = PlacementPolicy {
+                    target: Some(target),
+                    ..self.placement_policy(false)
+                };
+                self.move_selected_window(monitor, policy)
             }
 
             Message::CancelSelection => {
                 self.screen = Screen::Main;
+                self.editing_nickname = None;
+                Task::none()
+            }
+
+            Message::EditMonitorNickname(monitor) => {
+                let draft = monitor.nickname.clone().unwrap_or_default();
+                self.editing_nickname = Some((monitor.stable_id, draft));
+                Task::none()
+            }
+
+            Message::MonitorNicknameInput(value) => {
+                if let Some((_, draft)) = &mut self.editing_nickname {
+                    *draft = value;
+                }
+                Task::none()
+            }
+
+            Message::SaveMonitorNickname => {
+                let Some((stable_id, draft)) = self.editing_nickname.take() else {
+                    return Task::none();
+                };
+
+                // An empty nickname restores the default name
+                let nickname = draft.trim().to_string();
+                if nickname.is_empty() {
+                    self.settings.monitor_nicknames.remove(&stable_id);
+                } else {
+                    self.settings
+                        .monitor_nicknames
+                        .insert(stable_id.clone(), nickname.clone());
+                }
+                let _ = save_settings(&self.settings);
+
+                for monitor in self.monitors.iter_mut().filter(|m| m.stable_id == stable_id) {
+                    monitor.nickname = (!nickname.is_empty()).then(|| nickname.clone());
+                }
+
+                // Reload so window subtitles pick up the new name
+                self.reload()
+            }
+
+            Message::CancelNicknameEdit => {
+                self.editing_nickname = None;
                 Task::none()
             }
 
             Message::WindowMoved(result) => {
+                self.publish(ApiEvent::WindowMoved {
+                    error: result.as_ref().err().cloned(),
+                });
+                // Check for close-after-recovery once the windows reload
+                self.recovery_check.moved(&result);
                 match result {
                     Ok(()) => {
                         self.status_message =
                             Some(self.loc.get(crate::localization::keys::STATUS_MOVED));
-                        // Set flag to check for close-after-recovery after windows reload
-                        self.pending_recovery_check = true;
                     }
                     Err(e) => {
                         self.status_message = Some(self.loc.get_with_arg(
@@ -236,9 +797,143 @@ impl App {
 
                 // Refresh windows after move and clear status after delay
                 Task::batch([
-                    Task::perform(load_windows_and_monitors(), |(w, m)| {
-                        Message::WindowsLoaded(w, m)
+                    self.reload(),
+                    Task::perform(
+                        async { tokio::time::sleep(tokio::time::Duration::from_secs(3)).await },
+                        |_| Message::ClearStatus,
+                    ),
+                ])
+            }
+
+            Message::MonitorsPolled(monitors) => {
+                // The full reload diffs the monitors and reports the change
+                if self.topology.is_some() && !diff_monitors(&self.monitors, &monitors).is_empty() {
+                    self.reload()
+                } else {
+                    Task::none()
+                }
+            }
+
+            Message::MonitorsChanged(change) => {
+                tracing::info!(
+                    "Monitors changed: {} added, {} removed, {} changed{}",
+                    change.added.len(),
+                    change.removed.len(),
+                    change.changed.len(),
+                    if change.primary_changed() { ", new primary" } else { "" }
+                );
+                let topology = self.topology.clone().unwrap_or_default();
+                self.publish(ApiEvent::monitors_changed(topology, &change));
+
+                match self.settings.on_monitors_changed {
+                    TopologyAction::Nothing => Task::none(),
+                    action => Task::perform(tokio::time::sleep(TOPOLOGY_SETTLE_TIME), move |_| {
+                        Message::ApplyTopologyAction(action)
                     }),
+                }
+            }
+
+            Message::ApplyTopologyAction(action) => {
+                // Wait for a fresh window list
+                self.pending_topology_action = Some(action);
+                self.reload()
+            }
+
+            Message::LayoutNameInput(name) => {
+                self.layout_name = name;
+                Task::none()
+            }
+
+            Message::SaveLayout => {
+                let layout = Layout::capture(&self.layout_name, &self.windows, &self.monitors);
+                match layouts::save_layout(&layout) {
+                    Ok(()) => {
+                        tracing::info!(
+                            "Saved {} windows as layout \"{}\"",
+                            layout.windows.len(),
+                            layout.name
+                        );
+                        self.status_message = Some(self.loc.get_with_arg(
+                            crate::localization::keys::STATUS_LAYOUT_SAVED,
+                            "name",
+                            &layout.name,
+                        ));
+                        self.layout_name = layout.name;
+                        self.load_layout_names();
+                    }
+                    Err(e) => {
+                        self.status_message = Some(self.loc.get_with_arg(
+                            crate::localization::keys::STATUS_ERROR,
+                            "message",
+                            &e,
+                        ));
+                    }
+                }
+                Task::perform(
+                    async { tokio::time::sleep(tokio::time::Duration::from_secs(3)).await },
+                    |_| Message::ClearStatus,
+                )
+            }
+
+            Message::RestoreLayout(name) => match layouts::load_layout(&name) {
+                Ok(layout) => self.restore_layout(layout),
+                Err(e) => {
+                    self.status_message = Some(self.loc.get_with_arg(
+                        crate::localization::keys::STATUS_ERROR,
+                        "message",
+                        &e,
+                    ));
+                    Task::none()
+                }
+            },
+
+            Message::DeleteLayout(name) => {
+                if let Err(e) = layouts::delete_layout(&name) {
+                    self.status_message = Some(self.loc.get_with_arg(
+                        crate::localization::keys::STATUS_ERROR,
+                        "message",
+                        &e,
+                    ));
+                }
+                self.layout_name.clear();
+                self.load_layout_names();
+                Task::none()
+            }
+
+            Message::LayoutRestored(name, skipped, results) => {
+                for reason in &skipped {
+                    tracing::info!("Layout \"{}\": skipped {}", name, reason);
+                }
+                for (window, result) in &results {
+                    if let Err(e) = result {
+                        tracing::warn!(
+                            "Layout \"{}\": failed to move \"{}\" ({}): {}",
+                            name,
+                            window.title,
+                            window.process_name,
+                            e
+                        );
+                    }
+                }
+
+                let (moved, failed) = move_summary(&results);
+                let mut args = fluent::FluentArgs::new();
+                args.set("name", name.clone());
+                args.set("moved", moved);
+                args.set("total", results.len() + skipped.len());
+                self.status_message = Some(self.loc.get_with_args(
+                    crate::localization::keys::STATUS_LAYOUT_RESTORED,
+                    Some(&args),
+                ));
+                self.publish(ApiEvent::LayoutRestored {
+                    name,
+                    moved,
+                    skipped,
+                    failed,
+                });
+
+                Task::batch([
+                    self.reload(),
                     Task::perform(
                         async { tokio::time::sleep(tokio::time::Duration::from_secs(3)).await },
                         |_| Message::ClearStatus,
@@ -246,6 +941,38 @@ impl App {
                 ])
             }
 
+            Message::AllMovedToPrimary(results) => {
+                // Reported like a single move
+                self.update(Message::WindowMoved(backend::combined_result(&results)))
+            }
+
+            Message::AutoRescued(results) => {
+                for (window, monitor, result) in results {
+                    match &result {
+                        Ok(()) => tracing::info!(
+                            "Auto-rescued \"{}\" ({}) to {}",
+                            window.title,
+                            window.process_name,
+                            monitor
+                        ),
+                        Err(e) => tracing::warn!(
+                            "Failed to auto-rescue \"{}\" ({}): {}",
+                            window.title,
+                            window.process_name,
+                            e
+                        ),
+                    }
+                    self.publish(ApiEvent::WindowRescued {
+                        handle: window.hwnd,
+                        title: window.title,
+                        process: window.process_name,
+                        monitor,
+                        error: result.err(),
+                    });
+                }
+                self.reload()
+            }
+
             Message::OpenSettings => {
                 self.screen = Screen::Settings;
                 Task::none()
@@ -283,13 +1010,56 @@ impl App {
                 Task::none()
             }
 
+            Message::SetPlacementMode(mode) => {
+                self.settings.placement_mode = mode;
+                let _ = save_settings(&self.settings);
+                Task::none()
+            }
+
+            Message::SetScalingMode(mode) => {
+                self.settings.scaling_mode = mode;
+                let _ = save_settings(&self.settings);
+                Task::none()
+            }
+
+            Message::SetAutoRescue(enabled) => {
+                self.settings.auto_rescue.enabled = enabled;
+                // Start over, so windows off-screen right now are left alone
+                self.rescue_tracker = RescueTracker::default();
+                let _ = save_settings(&self.settings);
+                Task::none()
+            }
+
+            Message::SetTopologyAction(action) => {
+                self.settings.on_monitors_changed = action;
+                let _ = save_settings(&self.settings);
+                Task::none()
+            }
+
+            Message::SetApiEnabled(enabled) => {
+                self.settings.api_enabled = enabled;
+                self.update_api_server();
+                let _ = save_settings(&self.settings);
+                Task::none()
+            }
+
+            Message::ReloadSettings => {
+                // Pick up changes made to the settings file by hand
+                self.settings = load_settings();
+                self.loc.set_language(&self.settings.language);
+                self.register_hotkeys();
+                self.update_api_server();
+                self.publish(ApiEvent::SettingsReloaded);
+                self.reload()
+            }
+
             Message::OpenUrl(url) => {
                 let _ = open::that(&url);
                 Task::none()
             }
 
-            Message::EditHotkey(action) => {
-                self.editing_hotkey = Some(action);
+            Message::EditHotkey(action, index) => {
+                self.editing_hotkey = Some((action, index));
                 Task::none()
             }
 
@@ -298,83 +1068,49 @@ impl App {
                 Task::none()
             }
 
-            Message::UpdateHotkey(action, binding) => {
-                match action {
-                    HotkeyAction::LassoWindow => {
-                        self.settings.hotkeys.lasso_window = binding;
-                    }
-                    HotkeyAction::RefreshWindows => {
-                        self.settings.hotkeys.refresh_windows = binding;
-                    }
-                    HotkeyAction::MoveToPrimary => {
-                        self.settings.hotkeys.move_to_primary = binding;
-                    }
-                    HotkeyAction::MoveAllToPrimary => {
-                        self.settings.hotkeys.move_all_to_primary = binding;
-                    }
-                    HotkeyAction::CenterWindow => {
-                        self.settings.hotkeys.center_window = binding;
-                    }
-                    HotkeyAction::NextMonitor => {
-                        self.settings.hotkeys.next_monitor = binding;
-                    }
-                }
+            Message::UpdateHotkey(action, index, binding) => {
+                self.settings.hotkeys.set_binding(action, index, binding);
                 self.editing_hotkey = None;
                 // Re-register hotkeys with updated settings
-                if let Some(ref mut manager) = self.hotkey_manager {
-                    manager.register_from_settings(&self.settings.hotkeys);
-                }
+                self.register_hotkeys();
                 let _ = save_settings(&self.settings);
                 Task::none()
             }
 
-            Message::ToggleHotkey(action, enabled) => {
-                match action {
-                    HotkeyAction::LassoWindow => {
-                        self.settings.hotkeys.lasso_window.enabled = enabled;
-                    }
-                    HotkeyAction::RefreshWindows => {
-                        self.settings.hotkeys.refresh_windows.enabled = enabled;
-                    }
-                    HotkeyAction::MoveToPrimary => {
-                        self.settings.hotkeys.move_to_primary.enabled = enabled;
-                    }
-                    HotkeyAction::MoveAllToPrimary => {
-                        self.settings.hotkeys.move_all_to_primary.enabled = enabled;
-                    }
-                    HotkeyAction::CenterWindow => {
-                        self.settings.hotkeys.center_window.enabled = enabled;
-                    }
-                    HotkeyAction::NextMonitor => {
-                        self.settings.hotkeys.next_monitor.enabled = enabled;
-                    }
-                }
+            Message::ToggleHotkey(action, index, enabled) => {
+                self.settings.hotkeys.set_enabled(&action, index, enabled);
                 // Re-register hotkeys with updated settings
-                if let Some(ref mut manager) = self.hotkey_manager {
-                    manager.register_from_settings(&self.settings.hotkeys);
-                }
+                self.register_hotkeys();
+                let _ = save_settings(&self.settings);
+                Task::none()
+            }
+
+            Message::RemoveHotkey(action, index) => {
+                self.settings.hotkeys.remove_binding(&action, index);
+                // Re-register hotkeys with updated settings
+                self.register_hotkeys();
                 let _ = save_settings(&self.settings);
                 Task::none()
             }
 
-            Message::KeyPressed(key, modifiers) => {
+            Message::KeyPressed(key, physical, modifiers) => {
                 // Only process if we're in hotkey editing mode
-                if let Some(action) = self.editing_hotkey {
+                if let Some((action, index)) = self.editing_hotkey.clone() {
                     // Check for Escape to cancel
                     if matches!(key, Key::Named(keyboard::key::Named::Escape)) {
                         return self.update(Message::CancelHotkeyEdit);
                     }
-                    // Convert key to string (skip modifier-only presses)
-                    if let Some(key_str) = key_to_string(&key) {
+                    // Look up the key in the key table (modifier-only presses have none)
+                    if let Some(key_info) = keycodes::key_for_press(&key, &physical) {
                         // Require at least one modifier for safety
                         let mods = modifiers_to_strings(&modifiers);
                         if !mods.is_empty() {
                             let binding = HotkeyBinding {
                                 modifiers: mods,
-                                key: key_str,
+                                key: key_info.name.to_string(),
                                 enabled: true,
                             };
-                            return self.update(Message::UpdateHotkey(action, binding));
+                            return self.update(Message::UpdateHotkey(action, index, binding));
                         }
                     }
                 }
@@ -384,10 +1120,8 @@ impl App {
             Message::BringToFront => {
                 // Restore window from hidden mode and bring to foreground
                 Task::batch([
-                    iced::window::oldest().and_then(|id| {
-                        iced::window::set_mode(id, iced::window::Mode::Windowed)
-                    }),
-                    iced::window::oldest().and_then(iced::window::gain_focus),
+                    iced::window::set_mode(self.main_window, iced::window::Mode::Windowed),
+                    iced::window::gain_focus(self.main_window),
                 ])
             }
 
@@ -411,6 +1145,10 @@ impl App {
             }
 
             Message::RequestClose(id) => {
+                if Some(id) == self.leader_hint {
+                    return self.hide_leader_hint();
+                }
+
                 // Check if we should show the tray dialog
                 if self.settings.minimize_to_tray.is_none() {
                     self.show_tray_dialog = true;
@@ -433,13 +1171,15 @@ impl App {
                     };
                 }
                 // Also bring the app to front
-                windows_api::focus_self();
+                self.backend.focus_self();
                 Task::none()
             }
 
-            Message::HotkeyRefresh => Task::perform(load_windows_and_monitors(), |(w, m)| {
-                Message::WindowsLoaded(w, m)
-            }),
+            Message::HotkeyRefresh => {
+                // A command from another launch may have saved a layout
+                self.load_layout_names();
+                self.reload()
+            }
 
             Message::HotkeyMoveToPrimary => {
                 // Move first off-screen window to primary monitor
@@ -447,8 +1187,9 @@ impl App {
                     if let Some(primary) = self.monitors.iter().find(|m| m.is_primary) {
                         let hwnd = window.hwnd;
                         let monitor = primary.clone();
+                        let backend = self.backend.clone();
                         return Task::perform(
-                            async move { windows_api::move_window_to_monitor(hwnd, &monitor) },
+                            async move { backend.move_window_to_monitor(hwnd, &monitor) },
                             Message::WindowMoved,
                         );
                     }
@@ -458,23 +1199,79 @@ impl App {
 
             Message::HotkeyMoveAllToPrimary => {
                 // Move ALL off-screen windows to primary monitor
-                let offscreen_windows: Vec<_> = self.windows.iter()
-                    .filter(|w| w.is_offscreen)
-                    .map(|w| w.hwnd)
-                    .collect();
-                
-                if offscreen_windows.is_empty() {
+                if !self.windows.iter().any(|w| w.is_offscreen)
+                    || !self.monitors.iter().any(|m| m.is_primary)
+                {
                     return Task::none();
                 }
-                
-                if let Some(primary) = self.monitors.iter().find(|m| m.is_primary).cloned() {
+
+                let windows = self.windows.clone();
+                let monitors = self.monitors.clone();
+                let backend = self.backend.clone();
+                Task::perform(
+                    async move {
+                        backend::move_offscreen_to_primary(backend.as_ref(), &windows, &monitors)
+                            .unwrap_or_default()
+                    },
+                    Message::AllMovedToPrimary,
+                )
+            }
+
+            Message::HotkeyCenterWindow => {
+                // Center the currently focused window
+                if let Some(hwnd) = self.hotkey_target() {
+                    let monitors = self.monitors.clone();
+                    let backend = self.backend.clone();
+                    return Task::perform(
+                        async move { backend.center_window(hwnd, &monitors) },
+                        Message::WindowMoved,
+                    );
+                }
+                Task::none()
+            }
+
+            Message::HotkeyPreviousMonitor => {
+                // Move the focused window to the previous monitor
+                if let Some(hwnd) = self.hotkey_target() {
+                    let monitors = self.monitors.clone();
+                    let backend = self.backend.clone();
+                    let policy = self.placement_policy(false);
+                    return Task::perform(
+                        async move { backend.move_to_previous_monitor(hwnd, &monitors, &policy) },
+                        Message::WindowMoved,
+                    );
+                }
+                Task::none()
+            }
+
+            Message::HotkeySendToMonitor(target) => {
+                // Move the focused window to a specific monitor, if it is connected
+                let Some(monitor) = target.resolve(&self.monitors).cloned() else {
+                    return Task::none();
+                };
+                if let Some(hwnd) = self.hotkey_target() {
+                    let monitors = self.monitors.clone();
+                    let backend = self.backend.clone();
+                    let policy = self.placement_policy(false);
+                    return Task::perform(
+                        async move { backend.send_to_monitor(hwnd, &monitors, &monitor, &policy) },
+                        Message::WindowMoved,
+                    );
+                }
+                Task::none()
+            }
+
+            Message::HotkeyMoveInDirection(direction) => {
+                // Move the focused window to the neighbouring monitor
+                if let Some(hwnd) = self.hotkey_target() {
+                    let monitors = self.monitors.clone();
+                    let backend = self.backend.clone();
+                    let policy = self.placement_policy(false);
                     return Task::perform(
                         async move {
-                            let mut last_result = Ok(());
-                            for hwnd in offscreen_windows {
-                                last_result = windows_api::move_window_to_monitor(hwnd, &primary);
-                            }
-                            last_result
+                            backend.move_to_monitor_in_direction(
+                                hwnd, &monitors, direction, &policy,
+                            )
                         },
                         Message::WindowMoved,
                     );
@@ -482,12 +1279,21 @@ impl App {
                 Task::none()
             }
 
-            Message::HotkeyCenterWindow => {
-                // Center the currently focused window
-                if let Some(hwnd) = windows_api::get_foreground_window() {
+            Message::HotkeySnapToZone(zone) => {
+                // Snap the focused window into a zone of its monitor
+                let Some(target) = resolve_zone(&zone, &self.settings.custom_zones) else {
+                    tracing::warn!("Unknown snap zone: {}", zone);
+                    return Task::none();
+                };
+                if let Some(hwnd) = self.hotkey_target() {
                     let monitors = self.monitors.clone();
+                    let backend = self.backend.clone();
+                    let policy = PlacementPolicy {
+                        target: Some(target),
+                        ..self.placement_policy(false)
+                    };
                     return Task::perform(
-                        async move { windows_api::center_window(hwnd, &monitors) },
+                        async move { backend.snap_window(hwnd, &monitors, &policy) },
                         Message::WindowMoved,
                     );
                 }
@@ -496,10 +1302,12 @@ impl App {
 
             Message::HotkeyNextMonitor => {
                 // Move the focused window to the next monitor
-                if let Some(hwnd) = windows_api::get_foreground_window() {
+                if let Some(hwnd) = self.hotkey_target() {
                     let monitors = self.monitors.clone();
+                    let backend = self.backend.clone();
+                    let policy = self.placement_policy(false);
                     return Task::perform(
-                        async move { windows_api::move_to_next_monitor(hwnd, &monitors) },
+                        async move { backend.move_to_next_monitor(hwnd, &monitors, &policy) },
                         Message::WindowMoved,
                     );
                 }
@@ -513,40 +1321,59 @@ impl App {
 
             Message::Tick => {
                 // Auto-refresh window list
-                Task::perform(load_windows_and_monitors(), |(w, m)| {
-                    Message::WindowsLoaded(w, m)
-                })
+                self.reload()
+            }
+
+            Message::PollMonitors => {
+                let backend = self.backend.clone();
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || backend.enumerate_monitors())
+                            .await
+                            .unwrap_or_default()
+                    },
+                    Message::MonitorsPolled,
+                )
             }
 
             Message::PollEvents => {
-                // Poll for hotkey events
-                if let Some(ref manager) = self.hotkey_manager {
-                    if let Some(id) = hotkeys::poll_hotkey_event() {
-                        if let Some(action) = manager.get_action(id) {
-                            return match action {
-                                HotkeyAction::LassoWindow => {
-                                    self.update(Message::HotkeyLasso)
-                                }
-                                HotkeyAction::RefreshWindows => {
-                                    self.update(Message::HotkeyRefresh)
-                                }
-                                HotkeyAction::MoveToPrimary => {
-                                    self.update(Message::HotkeyMoveToPrimary)
-                                }
-                                HotkeyAction::MoveAllToPrimary => {
-                                    self.update(Message::HotkeyMoveAllToPrimary)
-                                }
-                                HotkeyAction::CenterWindow => {
-                                    self.update(Message::HotkeyCenterWindow)
-                                }
-                                HotkeyAction::NextMonitor => {
-                                    self.update(Message::HotkeyNextMonitor)
-                                }
-                            };
-                        }
+                // Poll for hotkey events, and time out the leader chord
+                if let Some(ref mut manager) = self.hotkey_manager {
+                    let now = Instant::now();
+                    let event = match hotkeys::poll_hotkey_event() {
+                        Some(id) => manager.handle_press(id, now),
+                        None => manager.tick(now),
+                    };
+                    if let Some(event) = event {
+                        return self.handle_hotkey_event(event);
                     }
                 }
 
+                // Poll for requests from later launches
+                let request = self.instance_server.as_ref().and_then(InstanceServer::poll);
+                if let Some(request) = request {
+                    return match request {
+                        IpcRequest::Show => self.update(Message::BringToFront),
+                        IpcRequest::Refresh => self.update(Message::HotkeyRefresh),
+                        IpcRequest::Lasso => Task::batch([
+                            self.update(Message::HotkeyLasso),
+                            self.update(Message::BringToFront),
+                        ]),
+                        // Already carried out by the server thread
+                        IpcRequest::Run { .. } => Task::none(),
+                    };
+                }
+
+                // Queue automation requests and refresh the window list first,
+                // so they act on windows as they are now
+                let waiting = self.pending_api_calls.len();
+                while let Some(call) = self.api_server.as_ref().and_then(ApiServer::poll) {
+                    self.pending_api_calls.push(call);
+                }
+                if self.pending_api_calls.len() > waiting {
+                    return self.reload();
+                }
+
                 // Poll for tray menu events
                 if let Some(action) = tray::poll_menu_event() {
                     return self.update(Message::TrayMenuEvent(action));
@@ -568,7 +1395,7 @@ impl App {
                     self.update(Message::RefreshWindows)
                 }
                 TrayMenuAction::Settings => {
-                    windows_api::focus_self();
+                    self.backend.focus_self();
                     self.update(Message::OpenSettings)
                 }
                 TrayMenuAction::Exit => {
@@ -582,24 +1409,47 @@ impl App {
         }
     }
 
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view(&self, window: iced::window::Id) -> Element<'_, Message> {
+        if Some(window) == self.leader_hint {
+            return leader_hint::view(&self.settings, &self.loc);
+        }
+
         // Main content based on screen
         let content: Element<Message> = match &self.screen {
             Screen::Main => {
-                main_view::view(&self.windows, &self.loc, self.status_message.as_deref())
+                // An unfinished query shows every window until it parses
+                let windows = match &self.window_matcher {
+                    Ok(matcher) => matcher.filter(&self.windows, &self.monitors),
+                    Err(_) => self.windows.iter().collect(),
+                };
+                main_view::view(
+                    windows,
+                    &self.window_filter,
+                    self.window_matcher.as_ref().err().map(String::as_str),
+                    &self.layouts,
+                    &self.layout_name,
+                    &self.loc,
+                    self.status_message.as_deref(),
+                )
             }
             Screen::MonitorPicker { selected_window } => {
-                monitor_picker::view(selected_window, &self.monitors, &self.loc)
+                monitor_picker::view(
+                    selected_window,
+                    &self.monitors,
+                    self.editing_nickname.as_ref(),
+                    self.placement_policy(false),
+                    &self.loc,
+                )
             }
-            Screen::Settings => settings_view::view(&self.settings, &self.loc),
+            Screen::Settings => settings_view::view(&self.settings, &self.hotkey_conflicts, &self.loc),
         };
 
         // Show tray dialog overlay if needed
         if self.show_tray_dialog {
             let overlay = tray_dialog::view(&self.loc);
             iced::widget::stack![content, overlay].into()
-        } else if let Some(action) = self.editing_hotkey {
-            let overlay = settings_view::hotkey_edit_view(action, &self.loc);
+        } else if let Some((action, _)) = &self.editing_hotkey {
+            let overlay = settings_view::hotkey_edit_view(action, &self.settings, &self.loc);
             iced::widget::stack![content, overlay].into()
         } else {
             content
@@ -610,17 +1460,31 @@ impl App {
         // Combine subscriptions
         let poll_events = time::every(Duration::from_millis(50)).map(|_| Message::PollEvents);
 
-        // Auto-refresh every 1 second when on main screen
-        let auto_refresh = match self.screen {
-            Screen::Main => time::every(Duration::from_secs(1)).map(|_| Message::Tick),
-            _ => Subscription::none(),
+        // Auto-refresh every 1 second when on main screen, and all the time
+        // while auto-rescue watches for windows going off-screen
+        // Otherwise only the monitors are watched, even from the tray, when
+        // a monitor change triggers an action or is sent to API subscribers
+        let auto_refresh = if self.screen == Screen::Main || self.settings.auto_rescue.enabled {
+            time::every(Duration::from_secs(1)).map(|_| Message::Tick)
+        } else if self.settings.on_monitors_changed != TopologyAction::Nothing
+            || self.settings.api_enabled
+        {
+            time::every(Duration::from_secs(1)).map(|_| Message::PollMonitors)
+        } else {
+            Subscription::none()
         };
 
         // Keyboard events for hotkey recording
         let keyboard = if self.editing_hotkey.is_some() {
             event::listen_with(|event, _status, _id| {
-                if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event {
-                    Some(Message::KeyPressed(key, modifiers))
+                if let Event::Keyboard(keyboard::Event::KeyPressed {
+                    key,
+                    physical_key,
+                    modifiers,
+                    ..
+                }) = event
+                {
+                    Some(Message::KeyPressed(key, physical_key, modifiers))
                 } else {
                     None
                 }
@@ -636,56 +1500,6 @@ impl App {
     }
 }
 
-/// Convert iced Key to a string representation
-fn key_to_string(key: &Key) -> Option<String> {
-    match key {
-        Key::Character(c) => {
-            let s = c.to_string().to_uppercase();
-            // Only allow single characters (letters, digits)
-            if s.len() == 1 {
-                Some(s)
-            } else {
-                None
-            }
-        }
-        Key::Named(named) => {
-            use iced::keyboard::key::Named;
-            match named {
-                Named::F1 => Some("F1".to_string()),
-                Named::F2 => Some("F2".to_string()),
-                Named::F3 => Some("F3".to_string()),
-                Named::F4 => Some("F4".to_string()),
-                Named::F5 => Some("F5".to_string()),
-                Named::F6 => Some("F6".to_string()),
-                Named::F7 => Some("F7".to_string()),
-                Named::F8 => Some("F8".to_string()),
-                Named::F9 => Some("F9".to_string()),
-                Named::F10 => Some("F10".to_string()),
-                Named::F11 => Some("F11".to_string()),
-                Named::F12 => Some("F12".to_string()),
-                Named::Space => Some("Space".to_string()),
-                Named::Enter => Some("Enter".to_string()),
-                Named::Tab => Some("Tab".to_string()),
-                Named::Escape => None, // Escape cancels recording
-                Named::Home => Some("Home".to_string()),
-                Named::End => Some("End".to_string()),
-                Named::PageUp => Some("PageUp".to_string()),
-                Named::PageDown => Some("PageDown".to_string()),
-                Named::Insert => Some("Insert".to_string()),
-                Named::Delete => Some("Delete".to_string()),
-                Named::ArrowUp => Some("Up".to_string()),
-                Named::ArrowDown => Some("Down".to_string()),
-                Named::ArrowLeft => Some("Left".to_string()),
-                Named::ArrowRight => Some("Right".to_string()),
-                // Modifier keys alone don't count as valid hotkeys
-                Named::Control | Named::Shift | Named::Alt | Named::Super => None,
-                _ => None,
-            }
-        }
-        Key::Unidentified => None,
-    }
-}
-
 /// Convert iced Modifiers to a list of modifier strings
 fn modifiers_to_strings(modifiers: &Modifiers) -> Vec<String> {
     let mut result = Vec::new();
@@ -705,12 +1519,49 @@ fn modifiers_to_strings(modifiers: &Modifiers) -> Vec<String> {
 }
 
 /// Load windows and monitors asynchronously
-async fn load_windows_and_monitors() -> (Vec<WindowInfo>, Vec<MonitorInfo>) {
-    tokio::task::spawn_blocking(|| {
-        let monitors = windows_api::enumerate_monitors();
-        let windows = windows_api::enumerate_windows(&monitors);
-        (windows, monitors)
-    })
+async fn load_windows_and_monitors(
+    backend: Arc<dyn WindowBackend>,
+    nicknames: BTreeMap<String, String>,
+) -> (Vec<WindowInfo>, Vec<MonitorInfo>) {
+    tokio::task::spawn_blocking(move || backend::enumerate_desktop(backend.as_ref(), &nicknames))
     .await
     .unwrap_or_default()
 }
+
+/// Start the automation API, logging why if it can't
+fn start_api_server() -> Option<ApiServer> {
+    ApiServer::start()
+        .map_err(|e| tracing::warn!("Automation API is unavailable: {}", e))
+        .ok()
+}
+
+/// Answer an API call once the move started by `task` finishes
+fn reply_when_moved(task: Task<Message>, reply: ApiReply) -> Task<Message> {
+    task.map(move |message| {
+        if let Message::WindowMoved(moved) = &message {
+            reply.send(moved.clone().map(|()| Value::Null));
+        }
+        message
+    })
+}
+
+/// How many windows moved, and why the others failed
+fn move_summary(results: &[(WindowInfo, Result<(), String>)]) -> (usize, Vec<String>) {
+    let failed: Vec<String> = results
+        .iter()
+        .filter_map(|(window, result)| {
+            let error = result.as_ref().err()?;
+            Some(format!("{}: {}: {}", window.process_name, window.title, error))
+        })
+        .collect();
+    (results.len() - failed.len(), failed)
+}
+
+/// Handles of the windows that are off-screen
+fn offscreen_handles(windows: &[WindowInfo]) -> Vec<isize> {
+    windows
+        .iter()
+        .filter(|w| w.is_offscreen)
+        .map(|w| w.hwnd)
+        .collect()
+}
@@ -0,0 +1,481 @@
+//! Platform-neutral window management backend
+
+pub mod simulated;
+#[cfg(all(unix, not(target_os = "macos")))]
+pub mod x11;
+
+use crate::placement::{self, PlacementPolicy};
+use crate::topology::{monitor_in_direction, Direction};
+use crate::types::{MonitorInfo, WindowInfo, WindowRect};
+use simulated::SimulatedDesktop;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Environment variable naming a scenario file to run against a simulated desktop
+pub const SCENARIO_ENV: &str = "WINDOW_LASSO_SCENARIO";
+
+/// Window and monitor operations provided by a platform backend
+///
+/// Window handles are opaque `isize` values owned by the backend (an `HWND`
+/// on Windows).
+pub trait WindowBackend: Send + Sync {
+    /// Enumerate all connected monitors
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo>;
+
+    /// Enumerate all visible application windows
+    fn enumerate_windows(&self, monitors: &[MonitorInfo]) -> Vec<WindowInfo>;
+
+    /// Get the current bounds of a window
+    fn window_rect(&self, hwnd: isize) -> Result<WindowRect, String>;
+
+    /// Set a window's restored bounds without changing its monitor-independent state
+    fn set_window_rect(&self, hwnd: isize, rect: &WindowRect) -> Result<(), String>;
+
+    /// Minimize a window
+    fn minimize_window(&self, hwnd: isize) -> Result<(), String>;
+
+    /// Move a window to a specific monitor with configurable options
+    /// - source_monitor: If provided, window size will be scaled proportionally
+    /// - policy: Where the window lands and whether it is maximized
+    /// - auto_focus: If true, the window will be brought to the foreground
+    fn move_window_to_monitor_with_options(
+        &self,
+        hwnd: isize,
+        target_monitor: &MonitorInfo,
+        source_monitor: Option<&MonitorInfo>,
+        policy: &PlacementPolicy,
+        auto_focus: bool,
+    ) -> Result<(), String>;
+
+    /// Focus this application's window (bring to foreground)
+    fn focus_self(&self);
+
+    /// Get the currently focused (foreground) window handle
+    fn get_foreground_window(&self) -> Option<isize>;
+
+    /// Move a window to a specific monitor, maximizing and focusing it
+    fn move_window_to_monitor(&self, hwnd: isize, monitor: &MonitorInfo) -> Result<(), String> {
+        self.move_window_to_monitor_with_options(
+            hwnd,
+            monitor,
+            None,
+            &PlacementPolicy::maximized(),
+            true,
+        )
+    }
+
+    /// Center a window on its current monitor
+    fn center_window(&self, hwnd: isize, monitors: &[MonitorInfo]) -> Result<(), String> {
+        let window_rect = self.window_rect(hwnd)?;
+
+        // Find which monitor the window is on
+        let monitor = monitor_for_rect(&window_rect, monitors)
+            .or_else(|| monitors.iter().find(|m| m.is_primary))
+            .ok_or_else(|| "No monitor found".to_string())?;
+
+        self.set_window_rect(hwnd, &placement::center(&window_rect, monitor))
+    }
+
+    /// Snap a window into a zone of its current monitor
+    ///
+    /// `policy.target` names the zone; the window keeps its monitor.
+    fn snap_window(
+        &self,
+        hwnd: isize,
+        monitors: &[MonitorInfo],
+        policy: &PlacementPolicy,
+    ) -> Result<(), String> {
+        let window_rect = self.window_rect(hwnd)?;
+
+        let monitor = monitor_for_rect(&window_rect, monitors)
+            .or_else(|| monitors.iter().find(|m| m.is_primary))
+            .ok_or_else(|| "No monitor found".to_string())?;
+
+        self.move_window_to_monitor_with_options(hwnd, monitor, Some(monitor), policy, false)
+    }
+
+    /// Move a window to the nearest monitor in a direction
+    ///
+    /// Does nothing if there is no monitor that way.
+    fn move_to_monitor_in_direction(
+        &self,
+        hwnd: isize,
+        monitors: &[MonitorInfo],
+        direction: Direction,
+        policy: &PlacementPolicy,
+    ) -> Result<(), String> {
+        let window_rect = self.window_rect(hwnd)?;
+
+        // Off-screen windows are treated as if they were on the primary monitor
+        let current = monitor_for_rect(&window_rect, monitors)
+            .or_else(|| monitors.iter().find(|m| m.is_primary))
+            .ok_or_else(|| "No monitor found".to_string())?;
+
+        match monitor_in_direction(current, monitors, direction) {
+            Some(target) => {
+                self.move_window_to_monitor_with_options(hwnd, target, Some(current), policy, true)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Move a window to a given monitor, scaling from the monitor it is on
+    fn send_to_monitor(
+        &self,
+        hwnd: isize,
+        monitors: &[MonitorInfo],
+        target: &MonitorInfo,
+        policy: &PlacementPolicy,
+    ) -> Result<(), String> {
+        let window_rect = self.window_rect(hwnd)?;
+        let source = monitor_for_rect(&window_rect, monitors);
+
+        self.move_window_to_monitor_with_options(hwnd, target, source, policy, true)
+    }
+
+    /// Move a window to the next monitor in the list
+    fn move_to_next_monitor(
+        &self,
+        hwnd: isize,
+        monitors: &[MonitorInfo],
+        policy: &PlacementPolicy,
+    ) -> Result<(), String> {
+        self.move_by_monitor_offset(hwnd, monitors, 1, policy)
+    }
+
+    /// Move a window to the previous monitor in the list
+    fn move_to_previous_monitor(
+        &self,
+        hwnd: isize,
+        monitors: &[MonitorInfo],
+        policy: &PlacementPolicy,
+    ) -> Result<(), String> {
+        self.move_by_monitor_offset(hwnd, monitors, -1, policy)
+    }
+
+    /// Move a window by `offset` places in the monitor list, wrapping around
+    fn move_by_monitor_offset(
+        &self,
+        hwnd: isize,
+        monitors: &[MonitorInfo],
+        offset: isize,
+        policy: &PlacementPolicy,
+    ) -> Result<(), String> {
+        if monitors.is_empty() {
+            return Err("No monitors available".to_string());
+        }
+
+        if monitors.len() == 1 {
+            return Ok(()); // Only one monitor, nothing to do
+        }
+
+        let window_rect = self.window_rect(hwnd)?;
+
+        // Find current monitor index
+        let current_idx = monitor_for_rect(&window_rect, monitors)
+            .and_then(|current| monitors.iter().position(|m| m == current))
+            .unwrap_or(0);
+
+        // Get the target monitor (cycle around)
+        let target_idx =
+            (current_idx as isize + offset).rem_euclid(monitors.len() as isize) as usize;
+
+        self.move_window_to_monitor_with_options(
+            hwnd,
+            &monitors[target_idx],
+            Some(&monitors[current_idx]),
+            policy,
+            true,
+        )
+    }
+}
+
+/// Enumerate monitors and windows, with monitor nicknames from settings applied
+pub fn enumerate_desktop(
+    backend: &dyn WindowBackend,
+    nicknames: &BTreeMap<String, String>,
+) -> (Vec<WindowInfo>, Vec<MonitorInfo>) {
+    let mut monitors = backend.enumerate_monitors();
+    // Apply nicknames before windows are matched to monitors
+    for monitor in &mut monitors {
+        monitor.nickname = nicknames.get(&monitor.stable_id).cloned();
+    }
+    let windows = backend.enumerate_windows(&monitors);
+    (windows, monitors)
+}
+
+/// Create the backend for the current platform
+///
+/// If `WINDOW_LASSO_SCENARIO` points to a scenario file, a simulated desktop is
+/// used instead.
+pub fn default_backend() -> Arc<dyn WindowBackend> {
+    if let Some(path) = std::env::var_os(SCENARIO_ENV) {
+        match SimulatedDesktop::load_scenario(Path::new(&path)) {
+            Ok(desktop) => {
+                info!(
+                    "Using simulated desktop from {}",
+                    Path::new(&path).display()
+                );
+                return Arc::new(desktop);
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+
+    platform_backend()
+}
+
+/// Create the native backend for the current platform
+fn platform_backend() -> Arc<dyn WindowBackend> {
+    #[cfg(windows)]
+    {
+        Arc::new(crate::windows_api::Win32Backend)
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        match x11::X11Backend::connect() {
+            Ok(backend) => Arc::new(backend),
+            Err(e) => {
+                warn!("Window management is unavailable: {}", e);
+                Arc::new(UnsupportedBackend)
+            }
+        }
+    }
+
+    #[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+    {
+        Arc::new(UnsupportedBackend)
+    }
+}
+
+/// Backend for platforms without native window management support
+///
+/// Reports no windows or monitors, so the UI still runs but has nothing to manage.
+#[cfg(not(windows))]
+pub struct UnsupportedBackend;
+
+#[cfg(not(windows))]
+impl WindowBackend for UnsupportedBackend {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        Vec::new()
+    }
+
+    fn enumerate_windows(&self, _monitors: &[MonitorInfo]) -> Vec<WindowInfo> {
+        Vec::new()
+    }
+
+    fn window_rect(&self, _hwnd: isize) -> Result<WindowRect, String> {
+        Err(unsupported())
+    }
+
+    fn set_window_rect(&self, _hwnd: isize, _rect: &WindowRect) -> Result<(), String> {
+        Err(unsupported())
+    }
+
+    fn minimize_window(&self, _hwnd: isize) -> Result<(), String> {
+        Err(unsupported())
+    }
+
+    fn move_window_to_monitor_with_options(
+        &self,
+        _hwnd: isize,
+        _target_monitor: &MonitorInfo,
+        _source_monitor: Option<&MonitorInfo>,
+        _policy: &PlacementPolicy,
+        _auto_focus: bool,
+    ) -> Result<(), String> {
+        Err(unsupported())
+    }
+
+    fn focus_self(&self) {}
+
+    fn get_foreground_window(&self) -> Option<isize> {
+        None
+    }
+}
+
+#[cfg(not(windows))]
+fn unsupported() -> String {
+    "Window management is not supported on this platform".to_string()
+}
+
+/// Move every off-screen window to the primary monitor, with how each move
+/// went
+///
+/// Returns `None` if no window is off-screen or there is no primary monitor.
+pub fn move_offscreen_to_primary(
+    backend: &dyn WindowBackend,
+    windows: &[WindowInfo],
+    monitors: &[MonitorInfo],
+) -> Option<Vec<(WindowInfo, Result<(), String>)>> {
+    let primary = monitors.iter().find(|m| m.is_primary)?;
+    let results: Vec<_> = windows
+        .iter()
+        .filter(|w| w.is_offscreen)
+        .map(|window| {
+            let result = backend.move_window_to_monitor(window.hwnd, primary);
+            (window.clone(), result)
+        })
+        .collect();
+    (!results.is_empty()).then_some(results)
+}
+
+/// Several moves reported as one, failing if any window didn't move
+pub fn combined_result(results: &[(WindowInfo, Result<(), String>)]) -> Result<(), String> {
+    results
+        .iter()
+        .find_map(|(_, result)| result.clone().err())
+        .map_or(Ok(()), Err)
+}
+
+/// Fill in off-screen status and monitor names, then sort for display
+pub fn finish_window_list(windows: &mut [WindowInfo], monitors: &[MonitorInfo]) {
+    // Update off-screen status based on monitors
+    for window in windows.iter_mut() {
+        window.is_offscreen = !is_window_on_any_monitor(&window.rect, monitors);
+        window.monitor_name =
+            monitor_for_rect(&window.rect, monitors).map(|m| m.display_name().to_string());
+    }
+
+    // Sort: off-screen windows first, then by title
+    windows.sort_by(|a, b| match (a.is_offscreen, b.is_offscreen) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+    });
+}
+
+/// Check if a window rect intersects with any monitor
+fn is_window_on_any_monitor(rect: &WindowRect, monitors: &[MonitorInfo]) -> bool {
+    monitors
+        .iter()
+        .any(|monitor| rect.intersects(&monitor.bounds))
+}
+
+/// Find the monitor a window is primarily on
+///
+/// That is the monitor containing the window's center, or failing that the one
+/// it overlaps most.
+pub fn monitor_for_rect<'a>(
+    rect: &WindowRect,
+    monitors: &'a [MonitorInfo],
+) -> Option<&'a MonitorInfo> {
+    let window_center = rect.center();
+
+    // Find monitor that contains the window center
+    let containing = monitors.iter().find(|monitor| {
+        window_center.0 >= monitor.bounds.left
+            && window_center.0 < monitor.bounds.right
+            && window_center.1 >= monitor.bounds.top
+            && window_center.1 < monitor.bounds.bottom
+    });
+    if containing.is_some() {
+        return containing;
+    }
+
+    // If center isn't on any monitor, find the one with most overlap
+    monitors
+        .iter()
+        .filter(|m| rect.intersects(&m.bounds))
+        .max_by_key(|m| {
+            let overlap_left = rect.left.max(m.bounds.left);
+            let overlap_right = rect.right.min(m.bounds.right);
+            let overlap_top = rect.top.max(m.bounds.top);
+            let overlap_bottom = rect.bottom.min(m.bounds.bottom);
+            (overlap_right - overlap_left) * (overlap_bottom - overlap_top)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::{monitor, rect};
+
+    fn nicknamed_desktop() -> (SimulatedDesktop, Vec<WindowInfo>, Vec<MonitorInfo>) {
+        let scenario =
+            serde_json::from_str(include_str!("../../scenarios/stranded-windows.json")).unwrap();
+        let desktop = SimulatedDesktop::from_scenario(scenario);
+        let nicknames = desktop
+            .enumerate_monitors()
+            .iter()
+            .map(|m| (m.stable_id.clone(), format!("Nick {}", m.display_index + 1)))
+            .collect();
+        let (windows, monitors) = enumerate_desktop(&desktop, &nicknames);
+        (desktop, windows, monitors)
+    }
+
+    #[test]
+    fn monitor_for_rect_prefers_the_center() {
+        let monitors = [
+            monitor(0, rect(0, 0, 1920, 1080), 96),
+            monitor(1, rect(1920, 0, 3840, 1080), 96),
+        ];
+
+        let on_second = monitor_for_rect(&rect(1800, 0, 2800, 600), &monitors);
+        assert_eq!(on_second.map(|m| m.display_index), Some(1));
+    }
+
+    #[test]
+    fn monitor_for_rect_falls_back_to_most_overlap() {
+        let monitors = [
+            monitor(0, rect(0, 0, 1920, 1080), 96),
+            monitor(1, rect(1920, 0, 3840, 1080), 96),
+        ];
+
+        // Center is below both monitors
+        let mostly_second = monitor_for_rect(&rect(1800, 900, 2800, 1500), &monitors);
+        assert_eq!(mostly_second.map(|m| m.display_index), Some(1));
+        assert!(monitor_for_rect(&rect(-900, 0, -100, 600), &monitors).is_none());
+    }
+
+    #[test]
+    fn nicknamed_monitors_are_found() {
+        let mut monitors = [
+            monitor(0, rect(0, 0, 1920, 1080), 96),
+            monitor(1, rect(1920, 0, 3840, 1080), 96),
+        ];
+        monitors[1].nickname = Some("Display 1".to_string());
+
+        let found = monitor_for_rect(&rect(2000, 100, 2800, 700), &monitors);
+        assert_eq!(found.map(|m| m.stable_id.as_str()), Some("MON2"));
+    }
+
+    #[test]
+    fn window_list_shows_monitor_nicknames() {
+        let (_, windows, _) = nicknamed_desktop();
+
+        let chat = windows.iter().find(|w| w.process_name == "chat").unwrap();
+        assert_eq!(chat.monitor_name.as_deref(), Some("Nick 2"));
+        let excel = windows.iter().find(|w| w.process_name == "EXCEL").unwrap();
+        assert_eq!(excel.monitor_name, None);
+    }
+
+    #[test]
+    fn center_stays_on_a_nicknamed_monitor() {
+        let (desktop, windows, monitors) = nicknamed_desktop();
+        let chat = windows.iter().find(|w| w.process_name == "chat").unwrap();
+
+        desktop.center_window(chat.hwnd, &monitors).unwrap();
+
+        let centered = desktop.window_rect(chat.hwnd).unwrap();
+        assert_eq!(centered.center(), monitors[1].center());
+        assert_eq!(centered.width(), chat.rect.width());
+    }
+
+    #[test]
+    fn next_monitor_starts_from_a_nicknamed_monitor() {
+        let (desktop, windows, monitors) = nicknamed_desktop();
+        let chat = windows.iter().find(|w| w.process_name == "chat").unwrap();
+
+        desktop
+            .move_to_next_monitor(chat.hwnd, &monitors, &PlacementPolicy::default())
+            .unwrap();
+
+        let moved = desktop.window_rect(chat.hwnd).unwrap();
+        let landed = monitor_for_rect(&moved, &monitors).unwrap();
+        assert_eq!(landed.stable_id, monitors[0].stable_id);
+    }
+}
@@ -0,0 +1,534 @@
+//! In-memory simulated desktop backend
+//!
+//! Keeps a virtual list of windows and monitors and applies moves, maximizes,
+//! restores and focus changes to that state. Useful for demos and for exercising
+//! app logic without a real desktop session.
+
+use super::{finish_window_list, monitor_for_rect, WindowBackend};
+use crate::placement::{place, PlacementPolicy, ShowState, BASE_DPI};
+use crate::topology::stable_monitor_id;
+use crate::types::{MonitorInfo, WindowInfo, WindowRect};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// First handle assigned to scenario windows without an explicit one
+const FIRST_HANDLE: isize = 0x1000;
+
+/// A scripted desktop loaded from JSON
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    pub monitors: Vec<ScenarioMonitor>,
+    pub windows: Vec<ScenarioWindow>,
+    /// Title of the window that starts in the foreground
+    pub foreground: Option<String>,
+}
+
+/// A monitor in a scenario file
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioMonitor {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub bounds: WindowRect,
+    /// Defaults to the full bounds (no taskbar)
+    #[serde(default)]
+    pub work_area: Option<WindowRect>,
+    #[serde(default)]
+    pub is_primary: bool,
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// Hardware path used for the stable ID; defaults to the device name
+    #[serde(default)]
+    pub device_path: Option<String>,
+}
+
+fn default_dpi() -> u32 {
+    BASE_DPI
+}
+
+/// A window in a scenario file
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScenarioWindow {
+    #[serde(default)]
+    pub hwnd: Option<isize>,
+    pub title: String,
+    #[serde(default = "default_process_name")]
+    pub process_name: String,
+    #[serde(default)]
+    pub process_id: u32,
+    pub rect: WindowRect,
+    #[serde(default)]
+    pub minimized: bool,
+    #[serde(default)]
+    pub maximized: bool,
+    /// Refuse to be moved, like a window of an elevated process
+    #[serde(default)]
+    pub unmovable: bool,
+}
+
+fn default_process_name() -> String {
+    "simulated".to_string()
+}
+
+/// Show state of a simulated window
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SimShow {
+    Normal,
+    Maximized,
+    Minimized,
+}
+
+/// A window living on the simulated desktop
+#[derive(Debug, Clone)]
+struct SimWindow {
+    hwnd: isize,
+    title: String,
+    process_name: String,
+    process_id: u32,
+    /// Restored (non-maximized) bounds
+    normal_rect: WindowRect,
+    /// Bounds while maximized
+    maximized_rect: Option<WindowRect>,
+    show: SimShow,
+    unmovable: bool,
+}
+
+impl SimWindow {
+    /// Bounds as reported by the window manager in the current show state
+    fn current_rect(&self) -> WindowRect {
+        match self.show {
+            SimShow::Maximized => self.maximized_rect.unwrap_or(self.normal_rect),
+            SimShow::Normal | SimShow::Minimized => self.normal_rect,
+        }
+    }
+
+    fn check_movable(&self) -> Result<(), String> {
+        if self.unmovable {
+            return Err(format!("Access is denied moving \"{}\"", self.title));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct SimState {
+    monitors: Vec<MonitorInfo>,
+    windows: Vec<SimWindow>,
+    foreground: Option<isize>,
+}
+
+/// Window backend backed by an in-memory virtual desktop
+pub struct SimulatedDesktop {
+    state: Mutex<SimState>,
+}
+
+impl SimulatedDesktop {
+    /// Build a desktop from a scenario
+    pub fn from_scenario(scenario: Scenario) -> Self {
+        let monitors = scenario
+            .monitors
+            .into_iter()
+            .enumerate()
+            .map(|(index, m)| {
+                let device_name = format!("SIM{}", index + 1);
+                let device_path = m.device_path.unwrap_or_else(|| device_name.clone());
+                let stable_id =
+                    stable_monitor_id(&device_path, None, m.bounds.width(), m.bounds.height());
+
+                MonitorInfo {
+                    handle: index as isize + 1,
+                    name: m.name.unwrap_or_else(|| {
+                        if m.is_primary {
+                            format!("Display {} (Primary)", index + 1)
+                        } else {
+                            format!("Display {}", index + 1)
+                        }
+                    }),
+                    device_name,
+                    device_path,
+                    bounds: m.bounds,
+                    work_area: m.work_area.unwrap_or(m.bounds),
+                    is_primary: m.is_primary,
+                    display_index: index,
+                    stable_id,
+                    nickname: None,
+                    dpi: m.dpi,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut next_handle = FIRST_HANDLE;
+        let mut windows = Vec::with_capacity(scenario.windows.len());
+        for w in scenario.windows {
+            let hwnd = w.hwnd.unwrap_or_else(|| {
+                next_handle += 1;
+                next_handle
+            });
+            let show = if w.minimized {
+                SimShow::Minimized
+            } else if w.maximized {
+                SimShow::Maximized
+            } else {
+                SimShow::Normal
+            };
+            let maximized_rect = w.maximized.then(|| work_area_for(&w.rect, &monitors));
+            windows.push(SimWindow {
+                hwnd,
+                title: w.title,
+                process_name: w.process_name,
+                process_id: w.process_id,
+                normal_rect: w.rect,
+                maximized_rect: maximized_rect.flatten(),
+                show,
+                unmovable: w.unmovable,
+            });
+        }
+
+        let foreground = scenario
+            .foreground
+            .and_then(|title| windows.iter().find(|w| w.title == title))
+            .map(|w| w.hwnd);
+
+        Self {
+            state: Mutex::new(SimState {
+                monitors,
+                windows,
+                foreground,
+            }),
+        }
+    }
+
+    /// Load a scenario from a JSON file
+    pub fn load_scenario(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read scenario {}: {}", path.display(), e))?;
+        let scenario: Scenario = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse scenario {}: {}", path.display(), e))?;
+
+        Ok(Self::from_scenario(scenario))
+    }
+}
+
+/// Work area of the monitor a rect is on, if any
+fn work_area_for(rect: &WindowRect, monitors: &[MonitorInfo]) -> Option<WindowRect> {
+    monitor_for_rect(rect, monitors).map(|m| m.work_area)
+}
+
+impl WindowBackend for SimulatedDesktop {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        self.state
+            .lock()
+            .map(|state| state.monitors.clone())
+            .unwrap_or_default()
+    }
+
+    fn enumerate_windows(&self, monitors: &[MonitorInfo]) -> Vec<WindowInfo> {
+        let Ok(state) = self.state.lock() else {
+            return Vec::new();
+        };
+
+        let mut result: Vec<WindowInfo> = state
+            .windows
+            .iter()
+            .map(|w| WindowInfo {
+                hwnd: w.hwnd,
+                title: w.title.clone(),
+                process_name: w.process_name.clone(),
+                process_id: w.process_id,
+                rect: w.current_rect(),
+                is_visible: w.show != SimShow::Minimized,
+                is_offscreen: false,
+                is_minimized: w.show == SimShow::Minimized,
+                is_maximized: w.show == SimShow::Maximized,
+                monitor_name: None,
+                icon_rgba: None,
+                icon_size: 32,
+            })
+            .collect();
+
+        finish_window_list(&mut result, monitors);
+        result
+    }
+
+    fn window_rect(&self, hwnd: isize) -> Result<WindowRect, String> {
+        let state = self.state.lock().map_err(|e| e.to_string())?;
+        state
+            .windows
+            .iter()
+            .find(|w| w.hwnd == hwnd)
+            .map(|w| w.current_rect())
+            .ok_or_else(|| format!("No such window: {:#x}", hwnd))
+    }
+
+    fn set_window_rect(&self, hwnd: isize, rect: &WindowRect) -> Result<(), String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        let window = state
+            .windows
+            .iter_mut()
+            .find(|w| w.hwnd == hwnd)
+            .ok_or_else(|| format!("No such window: {:#x}", hwnd))?;
+        window.check_movable()?;
+
+        window.normal_rect = *rect;
+        window.show = SimShow::Normal;
+        window.maximized_rect = None;
+
+        Ok(())
+    }
+
+    fn minimize_window(&self, hwnd: isize) -> Result<(), String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        let window = state
+            .windows
+            .iter_mut()
+            .find(|w| w.hwnd == hwnd)
+            .ok_or_else(|| format!("No such window: {:#x}", hwnd))?;
+
+        window.show = SimShow::Minimized;
+        window.maximized_rect = None;
+        if state.foreground == Some(hwnd) {
+            state.foreground = None;
+        }
+
+        Ok(())
+    }
+
+    fn move_window_to_monitor_with_options(
+        &self,
+        hwnd: isize,
+        target_monitor: &MonitorInfo,
+        source_monitor: Option<&MonitorInfo>,
+        policy: &PlacementPolicy,
+        auto_focus: bool,
+    ) -> Result<(), String> {
+        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+        let window = state
+            .windows
+            .iter_mut()
+            .find(|w| w.hwnd == hwnd)
+            .ok_or_else(|| format!("No such window: {:#x}", hwnd))?;
+        window.check_movable()?;
+
+        let target = place(&window.normal_rect, source_monitor, target_monitor, policy);
+        window.normal_rect = target.rect;
+
+        match target.show {
+            ShowState::Maximized => {
+                window.show = SimShow::Maximized;
+                window.maximized_rect = Some(target_monitor.work_area);
+            }
+            ShowState::Normal => {
+                window.show = SimShow::Normal;
+                window.maximized_rect = None;
+            }
+        }
+
+        if auto_focus {
+            state.foreground = Some(hwnd);
+        }
+
+        Ok(())
+    }
+
+    fn focus_self(&self) {
+        // The app itself is not part of the simulated desktop
+        if let Ok(mut state) = self.state.lock() {
+            state.foreground = None;
+        }
+    }
+
+    fn get_foreground_window(&self) -> Option<isize> {
+        self.state.lock().ok().and_then(|state| state.foreground)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{combined_result, enumerate_desktop, move_offscreen_to_primary};
+    use crate::rescue::RecoveryCheck;
+
+    fn stranded_scenario() -> Scenario {
+        serde_json::from_str(include_str!("../../scenarios/stranded-windows.json")).unwrap()
+    }
+
+    fn stranded_windows() -> SimulatedDesktop {
+        SimulatedDesktop::from_scenario(stranded_scenario())
+    }
+
+    #[test]
+    fn scenario_loads_monitors_and_windows() {
+        let desktop = stranded_windows();
+        let (windows, monitors) = enumerate_desktop(&desktop, &Default::default());
+
+        assert_eq!(monitors.len(), 2);
+        assert!(monitors[0].is_primary);
+        assert_eq!(monitors[1].dpi, 144);
+        assert_eq!(windows.len(), 5);
+
+        // Off-screen windows sort first
+        let offscreen: Vec<&str> = windows
+            .iter()
+            .filter(|w| w.is_offscreen)
+            .map(|w| w.process_name.as_str())
+            .collect();
+        assert_eq!(offscreen, ["OUTLOOK", "notepad", "EXCEL"]);
+        assert!(windows[..3].iter().all(|w| w.is_offscreen));
+
+        let word = windows
+            .iter()
+            .find(|w| w.process_name == "WINWORD")
+            .unwrap();
+        assert_eq!(desktop.get_foreground_window(), Some(word.hwnd));
+    }
+
+    #[test]
+    fn move_all_offscreen_to_primary() {
+        let desktop = stranded_windows();
+        let (windows, monitors) = enumerate_desktop(&desktop, &Default::default());
+        let primary = monitors.iter().find(|m| m.is_primary).unwrap();
+
+        let results = move_offscreen_to_primary(&desktop, &windows, &monitors).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(combined_result(&results), Ok(()));
+
+        let (after, _) = enumerate_desktop(&desktop, &Default::default());
+        assert!(after.iter().all(|w| !w.is_offscreen));
+        for window in windows.iter().filter(|w| w.is_offscreen) {
+            let moved = after.iter().find(|w| w.hwnd == window.hwnd).unwrap();
+            assert!(moved.is_maximized);
+            assert!(!moved.is_minimized);
+            assert_eq!(moved.rect, primary.work_area);
+            assert_eq!(moved.monitor_name.as_deref(), Some(primary.name.as_str()));
+        }
+
+        // Windows that were on-screen are left alone
+        for window in windows.iter().filter(|w| !w.is_offscreen) {
+            let same = after.iter().find(|w| w.hwnd == window.hwnd).unwrap();
+            assert_eq!(same.rect, window.rect);
+        }
+    }
+
+    #[test]
+    fn nothing_to_move_without_offscreen_windows() {
+        let desktop = stranded_windows();
+        let (windows, monitors) = enumerate_desktop(&desktop, &Default::default());
+        move_offscreen_to_primary(&desktop, &windows, &monitors).unwrap();
+
+        let (after, _) = enumerate_desktop(&desktop, &Default::default());
+        assert!(move_offscreen_to_primary(&desktop, &after, &monitors).is_none());
+    }
+
+    #[test]
+    fn close_after_recovery_waits_for_the_last_offscreen_window() {
+        let desktop = stranded_windows();
+        let (before, monitors) = enumerate_desktop(&desktop, &Default::default());
+        let primary = monitors.iter().find(|m| m.is_primary).unwrap();
+        let mut check = RecoveryCheck::default();
+
+        // One window back, two still off-screen
+        check.moved(&desktop.move_window_to_monitor(before[0].hwnd, primary));
+        let (partial, _) = enumerate_desktop(&desktop, &Default::default());
+        assert!(!check.windows_loaded(true, &before, &partial));
+
+        let results = move_offscreen_to_primary(&desktop, &partial, &monitors).unwrap();
+        check.moved(&combined_result(&results));
+        let (recovered, _) = enumerate_desktop(&desktop, &Default::default());
+        assert!(check.windows_loaded(true, &partial, &recovered));
+
+        // Only the list after a move counts
+        assert!(!check.windows_loaded(true, &partial, &recovered));
+    }
+
+    #[test]
+    fn close_after_recovery_only_when_enabled() {
+        let desktop = stranded_windows();
+        let (before, monitors) = enumerate_desktop(&desktop, &Default::default());
+        let mut check = RecoveryCheck::default();
+
+        let results = move_offscreen_to_primary(&desktop, &before, &monitors).unwrap();
+        check.moved(&combined_result(&results));
+        let (after, _) = enumerate_desktop(&desktop, &Default::default());
+        assert!(!check.windows_loaded(false, &before, &after));
+    }
+
+    #[test]
+    fn failed_moves_keep_the_app_open() {
+        let mut scenario = stranded_scenario();
+        let excel = scenario
+            .windows
+            .iter_mut()
+            .find(|w| w.process_name == "EXCEL")
+            .unwrap();
+        excel.unmovable = true;
+        let desktop = SimulatedDesktop::from_scenario(scenario);
+        let (before, monitors) = enumerate_desktop(&desktop, &Default::default());
+        let mut check = RecoveryCheck::default();
+
+        let results = move_offscreen_to_primary(&desktop, &before, &monitors).unwrap();
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|(_, result)| result.is_err())
+            .map(|(window, _)| window.process_name.as_str())
+            .collect();
+        assert_eq!(failed, ["EXCEL"]);
+
+        let result = combined_result(&results);
+        assert!(result.unwrap_err().contains("Quarterly Report"));
+        check.moved(&combined_result(&results));
+
+        let (after, _) = enumerate_desktop(&desktop, &Default::default());
+        let offscreen: Vec<&str> = after
+            .iter()
+            .filter(|w| w.is_offscreen)
+            .map(|w| w.process_name.as_str())
+            .collect();
+        assert_eq!(offscreen, ["EXCEL"]);
+        assert!(!check.windows_loaded(true, &before, &after));
+    }
+
+    #[test]
+    fn minimize_and_restore() {
+        let desktop = stranded_windows();
+        let (windows, monitors) = enumerate_desktop(&desktop, &Default::default());
+        let word = windows
+            .iter()
+            .find(|w| w.process_name == "WINWORD")
+            .unwrap();
+
+        desktop.minimize_window(word.hwnd).unwrap();
+        assert_eq!(desktop.get_foreground_window(), None);
+        let (after, _) = enumerate_desktop(&desktop, &Default::default());
+        let minimized = after.iter().find(|w| w.hwnd == word.hwnd).unwrap();
+        assert!(minimized.is_minimized);
+        assert_eq!(minimized.rect, word.rect);
+
+        desktop
+            .move_window_to_monitor_with_options(
+                word.hwnd,
+                &monitors[0],
+                Some(&monitors[0]),
+                &PlacementPolicy::default(),
+                true,
+            )
+            .unwrap();
+        assert_eq!(desktop.get_foreground_window(), Some(word.hwnd));
+        let (after, _) = enumerate_desktop(&desktop, &Default::default());
+        assert!(
+            !after
+                .iter()
+                .find(|w| w.hwnd == word.hwnd)
+                .unwrap()
+                .is_minimized
+        );
+    }
+
+    #[test]
+    fn unknown_window_is_an_error() {
+        let desktop = stranded_windows();
+        assert!(desktop.window_rect(1).is_err());
+        assert!(desktop.minimize_window(1).is_err());
+    }
+}
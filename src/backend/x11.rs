@@ -0,0 +1,643 @@
+//! Window enumeration and manipulation on X11 using EWMH
+
+use super::{finish_window_list, WindowBackend};
+use crate::placement::{place, PlacementPolicy, ShowState, BASE_DPI};
+use crate::topology::{parse_edid, stable_monitor_id};
+use crate::types::{MonitorInfo, WindowInfo, WindowRect};
+use std::fmt::Display;
+use std::fs;
+use std::ops::RangeInclusive;
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::{ConnectionExt as _, Output};
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, Window,
+};
+use x11rb::rust_connection::RustConnection;
+
+/// Size of the icons extracted from `_NET_WM_ICON`
+const ICON_SIZE: u32 = 32;
+
+/// `_NET_WM_STATE` client message actions
+const NET_WM_STATE_REMOVE: u32 = 0;
+const NET_WM_STATE_ADD: u32 = 1;
+
+/// ICCCM `WM_STATE` value for a minimized window
+const ICONIC_STATE: u32 = 3;
+
+/// Source indication for EWMH requests: "pager", so the WM always honours them
+const SOURCE_PAGER: u32 = 2;
+
+x11rb::atom_manager! {
+    /// EWMH atoms used by the backend
+    Atoms: AtomsCookie {
+        _NET_CLIENT_LIST,
+        _NET_ACTIVE_WINDOW,
+        _NET_CURRENT_DESKTOP,
+        _NET_WORKAREA,
+        _NET_WM_NAME,
+        _NET_WM_PID,
+        _NET_WM_ICON,
+        _NET_WM_STATE,
+        _NET_WM_STATE_HIDDEN,
+        _NET_WM_STATE_MAXIMIZED_VERT,
+        _NET_WM_STATE_MAXIMIZED_HORZ,
+        _NET_WM_STATE_SKIP_TASKBAR,
+        _NET_MOVERESIZE_WINDOW,
+        WM_CHANGE_STATE,
+        UTF8_STRING,
+        EDID,
+    }
+}
+
+/// X11 implementation of the window backend
+pub struct X11Backend {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+fn x11_err(e: impl Display) -> String {
+    format!("X11 error: {}", e)
+}
+
+impl X11Backend {
+    /// Connect to the X server named by `$DISPLAY`
+    pub fn connect() -> Result<Self, String> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(x11_err)?;
+        let root = conn.setup().roots[screen_num].root;
+        let atoms = Atoms::new(&conn)
+            .map_err(x11_err)?
+            .reply()
+            .map_err(x11_err)?;
+
+        // RandR 1.5 is needed for monitor enumeration
+        let version = conn
+            .randr_query_version(1, 5)
+            .map_err(x11_err)?
+            .reply()
+            .map_err(x11_err)?;
+        if (version.major_version, version.minor_version) < (1, 5) {
+            return Err(format!(
+                "The X server supports RandR {}.{}, but listing monitors needs 1.5",
+                version.major_version, version.minor_version
+            ));
+        }
+
+        Ok(Self { conn, root, atoms })
+    }
+
+    /// Read a property as a list of 32-bit values
+    fn property32(&self, window: Window, property: Atom, type_: impl Into<Atom>) -> Vec<u32> {
+        self.conn
+            .get_property(false, window, property, type_, 0, u32::MAX / 4)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default()
+    }
+
+    /// Read a property as raw bytes
+    fn property8(
+        &self,
+        window: Window,
+        property: impl Into<Atom>,
+        type_: impl Into<Atom>,
+    ) -> Vec<u8> {
+        self.conn
+            .get_property(false, window, property, type_, 0, u32::MAX / 4)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.value)
+            .unwrap_or_default()
+    }
+
+    /// Get the window title, preferring the UTF-8 `_NET_WM_NAME`
+    fn window_title(&self, window: Window) -> String {
+        let name = self.property8(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING);
+        if !name.is_empty() {
+            return String::from_utf8_lossy(&name).into_owned();
+        }
+
+        // Fall back to the legacy Latin-1 WM_NAME
+        self.property8(window, AtomEnum::WM_NAME, AtomEnum::STRING)
+            .into_iter()
+            .map(char::from)
+            .collect()
+    }
+
+    fn window_state(&self, window: Window) -> Vec<Atom> {
+        self.property32(window, self.atoms._NET_WM_STATE, AtomEnum::ATOM)
+    }
+
+    fn client_list(&self) -> Vec<Window> {
+        self.property32(self.root, self.atoms._NET_CLIENT_LIST, AtomEnum::WINDOW)
+    }
+
+    /// Get the window's client area in root coordinates
+    fn client_rect(&self, window: Window) -> Result<WindowRect, String> {
+        let geometry = self
+            .conn
+            .get_geometry(window)
+            .map_err(x11_err)?
+            .reply()
+            .map_err(x11_err)?;
+        let origin = self
+            .conn
+            .translate_coordinates(window, self.root, 0, 0)
+            .map_err(x11_err)?
+            .reply()
+            .map_err(x11_err)?;
+
+        Ok(WindowRect {
+            left: origin.dst_x as i32,
+            top: origin.dst_y as i32,
+            right: origin.dst_x as i32 + geometry.width as i32,
+            bottom: origin.dst_y as i32 + geometry.height as i32,
+        })
+    }
+
+    /// Send an EWMH client message to the root window
+    fn send_root_message(
+        &self,
+        window: Window,
+        message_type: Atom,
+        data: [u32; 5],
+    ) -> Result<(), String> {
+        let event = ClientMessageEvent::new(32, window, message_type, data);
+        self.conn
+            .send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )
+            .map_err(x11_err)?;
+        Ok(())
+    }
+
+    fn set_maximized(&self, window: Window, maximized: bool) -> Result<(), String> {
+        let action = if maximized {
+            NET_WM_STATE_ADD
+        } else {
+            NET_WM_STATE_REMOVE
+        };
+        self.send_root_message(
+            window,
+            self.atoms._NET_WM_STATE,
+            [
+                action,
+                self.atoms._NET_WM_STATE_MAXIMIZED_VERT,
+                self.atoms._NET_WM_STATE_MAXIMIZED_HORZ,
+                SOURCE_PAGER,
+                0,
+            ],
+        )
+    }
+
+    /// Ask the window manager to activate a window (also un-minimizes it)
+    fn activate(&self, window: Window) -> Result<(), String> {
+        self.send_root_message(
+            window,
+            self.atoms._NET_ACTIVE_WINDOW,
+            [SOURCE_PAGER, x11rb::CURRENT_TIME, 0, 0, 0],
+        )
+    }
+
+    /// Move and resize a window's client area through the window manager
+    fn move_resize(&self, window: Window, rect: &WindowRect) -> Result<(), String> {
+        // Static gravity: coordinates refer to the client area, matching client_rect
+        const STATIC_GRAVITY: u32 = 10;
+        const FLAGS_XYWH: u32 = 0b1111 << 8;
+
+        self.send_root_message(
+            window,
+            self.atoms._NET_MOVERESIZE_WINDOW,
+            [
+                STATIC_GRAVITY | FLAGS_XYWH | (SOURCE_PAGER << 12),
+                rect.left as u32,
+                rect.top as u32,
+                rect.width().max(1) as u32,
+                rect.height().max(1) as u32,
+            ],
+        )
+    }
+
+    fn flush(&self) -> Result<(), String> {
+        self.conn.flush().map_err(x11_err)
+    }
+
+    /// Work area of the current desktop (spans all monitors)
+    fn desktop_work_area(&self) -> Option<WindowRect> {
+        let desktop = self
+            .property32(
+                self.root,
+                self.atoms._NET_CURRENT_DESKTOP,
+                AtomEnum::CARDINAL,
+            )
+            .first()
+            .copied()
+            .unwrap_or(0) as usize;
+        let areas = self.property32(self.root, self.atoms._NET_WORKAREA, AtomEnum::CARDINAL);
+        let area = areas.get(desktop * 4..desktop * 4 + 4)?;
+
+        Some(WindowRect {
+            left: area[0] as i32,
+            top: area[1] as i32,
+            right: (area[0] + area[2]) as i32,
+            bottom: (area[1] + area[3]) as i32,
+        })
+    }
+
+    /// Raw EDID of a RandR output
+    fn output_edid(&self, output: Output) -> Option<Vec<u8>> {
+        // 256 bytes covers the base block and one extension block
+        let reply = self
+            .conn
+            .randr_get_output_property(output, self.atoms.EDID, AtomEnum::ANY, 0, 64, false, false)
+            .ok()?
+            .reply()
+            .ok()?;
+        (!reply.data.is_empty()).then_some(reply.data)
+    }
+
+    fn window_info(&self, window: Window) -> Option<WindowInfo> {
+        let state = self.window_state(window);
+        if state.contains(&self.atoms._NET_WM_STATE_SKIP_TASKBAR) {
+            return None;
+        }
+
+        let title = self.window_title(window);
+        if title.is_empty() {
+            return None;
+        }
+
+        let process_id = self
+            .property32(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL)
+            .first()
+            .copied()
+            .unwrap_or(0);
+
+        // Skip our own windows
+        if process_id == std::process::id() {
+            return None;
+        }
+
+        let process_name = process_name(process_id).unwrap_or_else(|| "Unknown".to_string());
+        let rect = self.client_rect(window).ok()?;
+        let is_minimized = state.contains(&self.atoms._NET_WM_STATE_HIDDEN);
+        let is_maximized = state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_VERT)
+            && state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_HORZ);
+        let icon_rgba = self.window_icon(window);
+
+        Some(WindowInfo {
+            hwnd: window as isize,
+            title,
+            process_name,
+            process_id,
+            rect,
+            is_visible: !is_minimized,
+            is_offscreen: false,
+            is_minimized,
+            is_maximized,
+            monitor_name: None,
+            icon_rgba,
+            icon_size: ICON_SIZE,
+        })
+    }
+
+    /// Get the window icon from `_NET_WM_ICON` as RGBA pixel data
+    fn window_icon(&self, window: Window) -> Option<Vec<u8>> {
+        let data = self.property32(window, self.atoms._NET_WM_ICON, AtomEnum::CARDINAL);
+        icon_to_rgba(&data, ICON_SIZE)
+    }
+}
+
+/// Pick the best icon from a `_NET_WM_ICON` array and scale it to `size`
+///
+/// The array holds consecutive `width, height, ARGB pixels...` entries.
+fn icon_to_rgba(data: &[u32], size: u32) -> Option<Vec<u8>> {
+    let mut icons: Vec<(u32, u32, &[u32])> = Vec::new();
+    let mut rest = data;
+    while rest.len() >= 2 {
+        let (w, h) = (rest[0], rest[1]);
+        let len = (w as usize).checked_mul(h as usize)?;
+        if w == 0 || h == 0 || rest.len() < 2 + len {
+            break;
+        }
+        icons.push((w, h, &rest[2..2 + len]));
+        rest = &rest[2 + len..];
+    }
+
+    // Prefer the smallest icon at least `size` wide, otherwise the largest one
+    let (w, h, pixels) = icons
+        .iter()
+        .filter(|(w, _, _)| *w >= size)
+        .min_by_key(|(w, _, _)| *w)
+        .or_else(|| icons.iter().max_by_key(|(w, _, _)| *w))
+        .copied()?;
+
+    // Nearest-neighbour scale and convert ARGB to RGBA. The maths is in u64,
+    // since a malformed icon can be as wide as u32 allows.
+    let (w, h, size) = (w as u64, h as u64, size as u64);
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let src_x = x * w / size;
+            let src_y = y * h / size;
+            let argb = pixels[(src_y * w + src_x) as usize];
+            rgba.extend_from_slice(&[
+                (argb >> 16) as u8,
+                (argb >> 8) as u8,
+                argb as u8,
+                (argb >> 24) as u8,
+            ]);
+        }
+    }
+
+    Some(rgba)
+}
+
+/// DPIs a real monitor can have; outside them the reported size is made up
+const PLAUSIBLE_DPI: RangeInclusive<u32> = 48..=480;
+
+/// Estimate DPI from a monitor's pixel width and physical width
+///
+/// Projectors, TVs and virtual outputs often report sizes that aren't real,
+/// so a DPI no monitor has falls back to `BASE_DPI`.
+fn dpi_from_physical_size(width_px: u32, width_mm: u32) -> u32 {
+    if width_mm == 0 {
+        return BASE_DPI;
+    }
+
+    let dpi = (width_px as f64 * 25.4 / width_mm as f64).round() as u32;
+    if PLAUSIBLE_DPI.contains(&dpi) {
+        dpi
+    } else {
+        BASE_DPI
+    }
+}
+
+/// Get the process name from a process ID
+fn process_name(process_id: u32) -> Option<String> {
+    if process_id == 0 {
+        return None;
+    }
+
+    fs::read_to_string(format!("/proc/{}/comm", process_id))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+impl WindowBackend for X11Backend {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        let Some(reply) = self
+            .conn
+            .randr_get_monitors(self.root, true)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+        else {
+            return Vec::new();
+        };
+
+        let desktop_work_area = self.desktop_work_area();
+
+        reply
+            .monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| {
+                let device_name = self
+                    .conn
+                    .get_atom_name(monitor.name)
+                    .ok()
+                    .and_then(|cookie| cookie.reply().ok())
+                    .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+                    .unwrap_or_default();
+
+                let bounds = WindowRect {
+                    left: monitor.x as i32,
+                    top: monitor.y as i32,
+                    right: monitor.x as i32 + monitor.width as i32,
+                    bottom: monitor.y as i32 + monitor.height as i32,
+                };
+
+                // _NET_WORKAREA covers the whole desktop, so clip it to this monitor
+                let work_area = desktop_work_area
+                    .filter(|area| area.intersects(&bounds))
+                    .map(|area| WindowRect {
+                        left: area.left.max(bounds.left),
+                        top: area.top.max(bounds.top),
+                        right: area.right.min(bounds.right),
+                        bottom: area.bottom.min(bounds.bottom),
+                    })
+                    .unwrap_or(bounds);
+
+                let name = if monitor.primary {
+                    format!("Display {} (Primary)", index + 1)
+                } else {
+                    format!("Display {}", index + 1)
+                };
+
+                // Connector names (e.g. "DP-1") are the closest thing to a device path
+                let edid = monitor
+                    .outputs
+                    .first()
+                    .and_then(|&output| self.output_edid(output))
+                    .and_then(|data| parse_edid(&data));
+                let stable_id =
+                    stable_monitor_id(&device_name, edid.as_ref(), bounds.width(), bounds.height());
+
+                MonitorInfo {
+                    handle: monitor.name as isize,
+                    name,
+                    device_path: device_name.clone(),
+                    device_name,
+                    bounds,
+                    work_area,
+                    is_primary: monitor.primary,
+                    display_index: index,
+                    stable_id,
+                    nickname: None,
+                    // X11 has no per-monitor scaling setting, so use the physical DPI
+                    dpi: dpi_from_physical_size(monitor.width as u32, monitor.width_in_millimeters),
+                }
+            })
+            .collect()
+    }
+
+    fn enumerate_windows(&self, monitors: &[MonitorInfo]) -> Vec<WindowInfo> {
+        let mut result: Vec<WindowInfo> = self
+            .client_list()
+            .into_iter()
+            .filter_map(|window| self.window_info(window))
+            .collect();
+
+        finish_window_list(&mut result, monitors);
+        result
+    }
+
+    fn window_rect(&self, hwnd: isize) -> Result<WindowRect, String> {
+        self.client_rect(hwnd as Window)
+    }
+
+    fn move_window_to_monitor_with_options(
+        &self,
+        hwnd: isize,
+        target_monitor: &MonitorInfo,
+        source_monitor: Option<&MonitorInfo>,
+        policy: &PlacementPolicy,
+        auto_focus: bool,
+    ) -> Result<(), String> {
+        let window = hwnd as Window;
+        let state = self.window_state(window);
+        let was_minimized = state.contains(&self.atoms._NET_WM_STATE_HIDDEN);
+        let was_maximized = state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_VERT)
+            || state.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_HORZ);
+
+        let current_rect = self.client_rect(window)?;
+        let target = place(&current_rect, source_monitor, target_monitor, policy);
+
+        // Maximized windows are tied to their monitor, so restore them before moving
+        if was_maximized {
+            self.set_maximized(window, false)?;
+        }
+
+        self.move_resize(window, &target.rect)?;
+
+        if target.show == ShowState::Maximized {
+            self.set_maximized(window, true)?;
+        }
+
+        // Activating a hidden window restores it
+        if auto_focus || was_minimized {
+            self.activate(window)?;
+        }
+
+        self.flush()
+    }
+
+    fn set_window_rect(&self, hwnd: isize, rect: &WindowRect) -> Result<(), String> {
+        self.move_resize(hwnd as Window, rect)?;
+        self.flush()
+    }
+
+    fn minimize_window(&self, hwnd: isize) -> Result<(), String> {
+        // ICCCM: ask the window manager to iconify the window
+        self.send_root_message(
+            hwnd as Window,
+            self.atoms.WM_CHANGE_STATE,
+            [ICONIC_STATE, 0, 0, 0, 0],
+        )?;
+        self.flush()
+    }
+
+    fn focus_self(&self) {
+        let own_pid = std::process::id();
+        let own_window = self.client_list().into_iter().find(|&window| {
+            self.property32(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL)
+                .first()
+                == Some(&own_pid)
+        });
+
+        if let Some(window) = own_window {
+            let _ = self.activate(window).and_then(|_| self.flush());
+        }
+    }
+
+    fn get_foreground_window(&self) -> Option<isize> {
+        self.property32(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW)
+            .first()
+            .copied()
+            .filter(|&window| window != x11rb::NONE)
+            .map(|window| window as isize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `_NET_WM_ICON` data for one icon filled with a colour
+    fn icon(w: u32, h: u32, argb: u32) -> Vec<u32> {
+        let mut data = vec![w, h];
+        data.extend(std::iter::repeat_n(argb, (w * h) as usize));
+        data
+    }
+
+    fn first_pixel(rgba: &[u8]) -> [u8; 4] {
+        rgba[..4].try_into().unwrap()
+    }
+
+    #[test]
+    fn icons_are_converted_to_rgba() {
+        let rgba = icon_to_rgba(&icon(32, 32, 0x80112233), 32).unwrap();
+        assert_eq!(rgba.len(), 32 * 32 * 4);
+        assert_eq!(first_pixel(&rgba), [0x11, 0x22, 0x33, 0x80]);
+    }
+
+    #[test]
+    fn icons_are_scaled_to_size() {
+        // Left half red, right half blue
+        let mut data = vec![4, 2];
+        data.extend([0xFFFF0000, 0xFFFF0000, 0xFF0000FF, 0xFF0000FF].repeat(2));
+
+        let rgba = icon_to_rgba(&data, 8).unwrap();
+        assert_eq!(rgba.len(), 8 * 8 * 4);
+        assert_eq!(first_pixel(&rgba), [0xFF, 0, 0, 0xFF]);
+        assert_eq!(first_pixel(&rgba[7 * 4..]), [0, 0, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn the_smallest_icon_that_is_big_enough_wins() {
+        let mut data = icon(16, 16, 0xFF000001);
+        data.extend(icon(64, 64, 0xFF000003));
+        data.extend(icon(48, 48, 0xFF000002));
+        let rgba = icon_to_rgba(&data, 32).unwrap();
+        assert_eq!(first_pixel(&rgba), [0, 0, 2, 0xFF]);
+
+        // None big enough: the largest one
+        let mut data = icon(16, 16, 0xFF000001);
+        data.extend(icon(24, 24, 0xFF000002));
+        let rgba = icon_to_rgba(&data, 32).unwrap();
+        assert_eq!(first_pixel(&rgba), [0, 0, 2, 0xFF]);
+    }
+
+    #[test]
+    fn malformed_icons_are_handled() {
+        assert_eq!(icon_to_rgba(&[], 32), None);
+        assert_eq!(icon_to_rgba(&[0, 0], 32), None);
+        // Claims more pixels than there are
+        assert_eq!(icon_to_rgba(&[32, 32, 0, 0, 0], 32), None);
+        // A valid icon followed by a truncated one
+        let mut data = icon(16, 16, 0xFF000001);
+        data.extend([64, 64, 0]);
+        assert!(icon_to_rgba(&data, 32).is_some());
+    }
+
+    #[test]
+    fn very_wide_icons_do_not_overflow() {
+        // Wide enough that column times width no longer fits in a u32
+        let (width, size) = (4_200_000, 1024);
+        let mut data = vec![width, 1];
+        data.resize(2 + width as usize, 0xFF00FF00);
+        let rgba = icon_to_rgba(&data, size).unwrap();
+        assert_eq!(rgba.len(), (size * size * 4) as usize);
+        assert_eq!(first_pixel(&rgba[rgba.len() - 4..]), [0, 0xFF, 0, 0xFF]);
+    }
+
+    #[test]
+    fn dpi_follows_the_physical_size() {
+        // 24" 1920x1080 is about 92 DPI, 27" 4K about 163
+        assert_eq!(dpi_from_physical_size(1920, 531), 92);
+        assert_eq!(dpi_from_physical_size(3840, 597), 163);
+        assert_eq!(dpi_from_physical_size(1920, 508), 96);
+        // Projectors and some drivers report no size
+        assert_eq!(dpi_from_physical_size(1920, 0), BASE_DPI);
+        // or one that can't be right: 1600 mm wide, or 10 mm
+        assert_eq!(dpi_from_physical_size(1920, 1600), BASE_DPI);
+        assert_eq!(dpi_from_physical_size(1920, 10), BASE_DPI);
+        // A large TV is still plausible
+        assert_eq!(dpi_from_physical_size(3840, 1650), 59);
+    }
+}
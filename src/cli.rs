@@ -0,0 +1,757 @@
+//! Command-line interface
+//!
+//! Subcommands act on the desktop directly and exit without starting the UI,
+//! so they can run from login scripts and docking event handlers.
+
+use crate::backend::{self, WindowBackend};
+use crate::layouts::{self, Layout};
+use crate::matcher::WindowMatcher;
+use crate::placement::PlacementPolicy;
+use crate::settings::load_settings;
+use crate::topology::{find_monitor, monitors_by_position, topology_fingerprint};
+use crate::types::{AppSettings, MonitorInfo, WindowInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::process::ExitCode;
+
+/// Usage text printed by `help` and on argument errors
+pub const USAGE: &str = "\
+Usage: window-lasso [COMMAND]
+
+Without a command, the WindowLasso window opens.
+
+Commands:
+  list [--json]                            List monitors and windows
+  move --window <MATCH> --monitor <MONITOR>
+                                           Move matching windows to a monitor
+  rescue-all                               Move every off-screen window to the primary monitor
+  center [--window <MATCH>]                Center windows on their monitor
+  next-monitor [--window <MATCH>]          Move windows to the next monitor
+  save-layout <NAME>                       Save where every window is as a layout
+  restore-layout <NAME>                    Move windows back to where a layout has them
+  layouts [--json]                         List saved layouts
+  delete-layout <NAME>                     Delete a saved layout
+  show                                     Open the WindowLasso window
+  refresh                                  Refresh the window list
+  lasso                                    Pick the first off-screen window to move
+  help                                     Show this help
+
+When WindowLasso is already running, commands are carried out by it.
+
+<MATCH> is a window query, such as a process name, part of a title or
+`process:chrome title~\"Meet\" offscreen`. Without --window, the focused
+window is used.
+<MONITOR> is a position counted left to right (1, 2, ...), a monitor ID
+from `list`, or a monitor name.";
+
+/// A subcommand given on the command line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum Command {
+    List {
+        json: bool,
+    },
+    Move {
+        window: String,
+        monitor: String,
+    },
+    RescueAll,
+    Center {
+        window: Option<String>,
+    },
+    NextMonitor {
+        window: Option<String>,
+    },
+    SaveLayout {
+        name: String,
+    },
+    RestoreLayout {
+        name: String,
+    },
+    Layouts {
+        json: bool,
+    },
+    DeleteLayout {
+        name: String,
+    },
+    /// Open the window (the default without a command)
+    Show,
+    Refresh,
+    Lasso,
+    Help,
+}
+
+/// What a command printed and whether it succeeded
+///
+/// Collected instead of printed directly, so a running instance can carry out
+/// a command and send the result back to the process that was launched.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub success: bool,
+}
+
+impl CommandOutput {
+    /// Output of a command that succeeded without printing anything
+    pub fn succeeded() -> Self {
+        Self {
+            success: true,
+            ..Self::default()
+        }
+    }
+
+    /// Output of a command that failed with an error
+    pub fn failed(error: impl fmt::Display) -> Self {
+        let mut output = Self::default();
+        output.err(format!("error: {}", error));
+        output
+    }
+
+    fn out(&mut self, line: impl fmt::Display) {
+        self.stdout.push_str(&format!("{}\n", line));
+    }
+
+    fn err(&mut self, line: impl fmt::Display) {
+        self.stderr.push_str(&format!("{}\n", line));
+    }
+
+    /// Print the output and turn the result into an exit code
+    pub fn finish(self) -> ExitCode {
+        print!("{}", self.stdout);
+        eprint!("{}", self.stderr);
+        if self.success {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parse the arguments after the program name
+///
+/// Returns `None` when there is no subcommand and the UI should start.
+pub fn parse_args<I>(args: I) -> Result<Option<Command>, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let Some(name) = args.next() else {
+        return Ok(None);
+    };
+
+    let mut json = false;
+    let mut window = None;
+    let mut monitor = None;
+    let mut name_arg = None;
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+
+        match flag.as_str() {
+            "--json" => json = true,
+            "--window" | "-w" => window = Some(value()?),
+            "--monitor" | "-m" => monitor = Some(value()?),
+            _ if !arg.starts_with('-') && name_arg.is_none() => name_arg = Some(arg),
+            _ if !arg.starts_with('-') => return Err(format!("Unexpected argument: {}", arg)),
+            _ => return Err(format!("Unknown option: {}", arg)),
+        }
+    }
+
+    let options_given = [
+        ("--json", json),
+        ("--window", window.is_some()),
+        ("--monitor", monitor.is_some()),
+    ];
+
+    let mut layout_name = || {
+        name_arg
+            .take()
+            .ok_or_else(|| format!("{} needs a layout name", name))
+    };
+    let command = match name.as_str() {
+        "list" => Command::List { json },
+        "move" => Command::Move {
+            window: window.ok_or("move needs --window")?,
+            monitor: monitor.ok_or("move needs --monitor")?,
+        },
+        "rescue-all" => Command::RescueAll,
+        "center" => Command::Center { window },
+        "next-monitor" => Command::NextMonitor { window },
+        "save-layout" => Command::SaveLayout {
+            name: layout_name()?,
+        },
+        "restore-layout" => Command::RestoreLayout {
+            name: layout_name()?,
+        },
+        "layouts" => Command::Layouts { json },
+        "delete-layout" => Command::DeleteLayout {
+            name: layout_name()?,
+        },
+        "show" => Command::Show,
+        "refresh" => Command::Refresh,
+        "lasso" => Command::Lasso,
+        "help" | "--help" | "-h" => Command::Help,
+        _ => return Err(format!("Unknown command: {}", name)),
+    };
+    if let Some(arg) = name_arg {
+        return Err(format!("Unexpected argument: {}", arg));
+    }
+    // Options the command would ignore are mistakes, not no-ops
+    for (option, given) in options_given {
+        let takes = match option {
+            "--json" => matches!(command, Command::List { .. } | Command::Layouts { .. }),
+            "--window" => matches!(
+                command,
+                Command::Move { .. } | Command::Center { .. } | Command::NextMonitor { .. }
+            ),
+            _ => matches!(command, Command::Move { .. }),
+        };
+        if given && !takes {
+            return Err(format!("{} doesn't take {}", name, option));
+        }
+    }
+    Ok(Some(command))
+}
+
+/// Run a subcommand in this process and report how it went
+pub fn run(command: Command) -> ExitCode {
+    execute(&command).finish()
+}
+
+/// Carry out a subcommand and collect what it prints
+pub fn execute(command: &Command) -> CommandOutput {
+    let mut output = CommandOutput::default();
+    if *command == Command::Help {
+        output.out(USAGE);
+        output.success = true;
+        return output;
+    }
+
+    let settings = load_settings();
+    let backend = backend::default_backend();
+    let desktop = Desktop::load(backend.as_ref(), &settings);
+
+    let result = match command {
+        Command::List { json } => {
+            desktop.list(*json, &mut output);
+            Ok(())
+        }
+        Command::Move { window, monitor } => desktop.move_windows(window, monitor, &mut output),
+        Command::RescueAll => desktop.rescue_all(&mut output),
+        Command::Center { window } => {
+            desktop.for_each_window(window.as_deref(), &mut output, |hwnd| {
+                desktop.backend.center_window(hwnd, &desktop.monitors)
+            })
+        }
+        Command::NextMonitor { window } => {
+            desktop.for_each_window(window.as_deref(), &mut output, |hwnd| {
+                desktop
+                    .backend
+                    .move_to_next_monitor(hwnd, &desktop.monitors, &desktop.policy)
+            })
+        }
+        Command::SaveLayout { name } => desktop.save_layout(name, &mut output),
+        Command::RestoreLayout { name } => desktop.restore_layout(name, &mut output),
+        Command::Layouts { json } => {
+            desktop.list_layouts(*json, &mut output);
+            Ok(())
+        }
+        Command::DeleteLayout { name } => layouts::delete_layout(name),
+        // These only make sense for the running app
+        Command::Show | Command::Refresh | Command::Lasso => {
+            Err("WindowLasso isn't running".to_string())
+        }
+        Command::Help => Ok(()),
+    };
+
+    match result {
+        Ok(()) => output.success = true,
+        Err(e) => output.err(format!("error: {}", e)),
+    }
+    output
+}
+
+/// Monitors as listed by `list --json`, numbered left to right
+pub fn monitors_json(monitors: &[MonitorInfo]) -> Value {
+    monitors_by_position(monitors)
+        .iter()
+        .enumerate()
+        .map(|(index, m)| {
+            let mut monitor = monitor_json(m);
+            monitor["position"] = json!(index + 1);
+            monitor
+        })
+        .collect()
+}
+
+/// A monitor as listed by `list --json`, without its position
+pub fn monitor_json(m: &MonitorInfo) -> Value {
+    json!({
+        "id": m.stable_id,
+        "name": m.display_name(),
+        "device": m.device_name,
+        "bounds": m.bounds,
+        "work_area": m.work_area,
+        "primary": m.is_primary,
+        "scale_percent": m.scale_percent(),
+    })
+}
+
+/// Windows as listed by `list --json`
+pub fn windows_json<'a>(windows: impl IntoIterator<Item = &'a WindowInfo>) -> Value {
+    windows
+        .into_iter()
+        .map(|w| {
+            json!({
+                "handle": w.hwnd,
+                "title": w.title,
+                "process": w.process_name,
+                "pid": w.process_id,
+                "rect": w.rect,
+                "offscreen": w.is_offscreen,
+                "minimized": w.is_minimized,
+                "maximized": w.is_maximized,
+                "monitor": w.monitor_name,
+            })
+        })
+        .collect()
+}
+
+/// Saved layouts as listed by `layouts --json`
+///
+/// `current_monitors` says whether a layout was saved on the monitor
+/// arrangement with fingerprint `topology`.
+pub fn layouts_json(layouts: &[Layout], topology: &str) -> Value {
+    layouts
+        .iter()
+        .map(|layout| {
+            json!({
+                "name": layout.name,
+                "windows": layout.windows.len(),
+                "current_monitors": layout.topology == topology,
+                "saved_at": layout.saved_at,
+            })
+        })
+        .collect()
+}
+
+/// Windows and monitors as they are when the command starts
+struct Desktop<'a> {
+    backend: &'a dyn WindowBackend,
+    windows: Vec<WindowInfo>,
+    monitors: Vec<MonitorInfo>,
+    policy: PlacementPolicy,
+}
+
+impl<'a> Desktop<'a> {
+    fn load(backend: &'a dyn WindowBackend, settings: &AppSettings) -> Self {
+        let (windows, monitors) = backend::enumerate_desktop(backend, &settings.monitor_nicknames);
+        Self {
+            backend,
+            windows,
+            monitors,
+            policy: settings.placement_policy(false),
+        }
+    }
+
+    fn list(&self, as_json: bool, output: &mut CommandOutput) {
+        let monitors = monitors_by_position(&self.monitors);
+
+        if as_json {
+            let list = json!({
+                "monitors": monitors_json(&self.monitors),
+                "windows": windows_json(&self.windows),
+            });
+            output.out(serde_json::to_string_pretty(&list).unwrap_or_default());
+            return;
+        }
+
+        output.out(format_table(
+            &["#", "ID", "NAME", "BOUNDS", "SCALE", "PRIMARY"],
+            monitors
+                .iter()
+                .enumerate()
+                .map(|(index, m)| {
+                    vec![
+                        (index + 1).to_string(),
+                        m.stable_id.clone(),
+                        m.display_name().to_string(),
+                        format!(
+                            "{}x{} at {},{}",
+                            m.bounds.width(),
+                            m.bounds.height(),
+                            m.bounds.left,
+                            m.bounds.top
+                        ),
+                        format!("{}%", m.scale_percent()),
+                        if m.is_primary { "yes" } else { "" }.to_string(),
+                    ]
+                })
+                .collect(),
+        ));
+        output.out("");
+        output.out(format_table(
+            &["HANDLE", "PID", "PROCESS", "STATE", "MONITOR", "TITLE"],
+            self.windows
+                .iter()
+                .map(|w| {
+                    vec![
+                        w.hwnd.to_string(),
+                        w.process_id.to_string(),
+                        w.process_name.clone(),
+                        window_state(w).to_string(),
+                        w.monitor_name.clone().unwrap_or_default(),
+                        w.title.clone(),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+
+    fn move_windows(
+        &self,
+        window: &str,
+        monitor: &str,
+        output: &mut CommandOutput,
+    ) -> Result<(), String> {
+        let target = find_monitor(monitor, &self.monitors)
+            .ok_or_else(|| format!("No monitor matches \"{}\"", monitor))?;
+        self.for_each_window(Some(window), output, |hwnd| {
+            self.backend
+                .send_to_monitor(hwnd, &self.monitors, target, &self.policy)
+        })
+    }
+
+    /// Move every off-screen window to the primary monitor
+    fn rescue_all(&self, output: &mut CommandOutput) -> Result<(), String> {
+        let primary = self
+            .monitors
+            .iter()
+            .find(|m| m.is_primary)
+            .ok_or("No primary monitor found")?;
+
+        let stranded: Vec<&WindowInfo> = self.windows.iter().filter(|w| w.is_offscreen).collect();
+        if stranded.is_empty() {
+            output.out("No off-screen windows");
+            return Ok(());
+        }
+
+        self.apply(&stranded, output, |hwnd| {
+            self.backend.move_window_to_monitor(hwnd, primary)
+        })
+    }
+
+    /// Save where every window is as a named layout
+    fn save_layout(&self, name: &str, output: &mut CommandOutput) -> Result<(), String> {
+        let layout = Layout::capture(name, &self.windows, &self.monitors);
+        layouts::save_layout(&layout)?;
+        output.out(format!(
+            "Saved {} windows as \"{}\"",
+            layout.windows.len(),
+            layout.name
+        ));
+        Ok(())
+    }
+
+    /// Move windows back to where a saved layout has them
+    fn restore_layout(&self, name: &str, output: &mut CommandOutput) -> Result<(), String> {
+        let layout = layouts::load_layout(name)?;
+        let plan = layout.plan_restore(&self.windows, &self.monitors);
+        for reason in &plan.skipped {
+            output.err(format!("skipped {}", reason));
+        }
+        if plan.moves.is_empty() {
+            return Err(format!(
+                "None of the windows in \"{}\" can be restored",
+                layout.name
+            ));
+        }
+
+        let windows: Vec<&WindowInfo> = plan.moves.iter().map(|m| &m.window).collect();
+        self.apply(&windows, output, |hwnd| {
+            plan.moves
+                .iter()
+                .find(|m| m.window.hwnd == hwnd)
+                .map_or(Ok(()), |m| m.apply(self.backend))
+        })
+    }
+
+    fn list_layouts(&self, as_json: bool, output: &mut CommandOutput) {
+        let layouts = layouts::list_layouts();
+        let topology = topology_fingerprint(&self.monitors);
+
+        if as_json {
+            let list = layouts_json(&layouts, &topology);
+            output.out(serde_json::to_string_pretty(&list).unwrap_or_default());
+            return;
+        }
+
+        if layouts.is_empty() {
+            output.out("No saved layouts");
+            return;
+        }
+        output.out(format_table(
+            &["NAME", "WINDOWS", "MONITORS"],
+            layouts
+                .iter()
+                .map(|layout| {
+                    vec![
+                        layout.name.clone(),
+                        layout.windows.len().to_string(),
+                        if layout.topology == topology {
+                            "current"
+                        } else {
+                            "other"
+                        }
+                        .to_string(),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+
+    /// Run an operation on the matching windows, or on the focused window
+    fn for_each_window<F>(
+        &self,
+        window: Option<&str>,
+        output: &mut CommandOutput,
+        operation: F,
+    ) -> Result<(), String>
+    where
+        F: Fn(isize) -> Result<(), String>,
+    {
+        match window {
+            Some(query) => {
+                let matcher: WindowMatcher = query.parse()?;
+                let matches = matcher.filter(&self.windows, &self.monitors);
+                if matches.is_empty() {
+                    return Err(format!("No window matches \"{}\"", query));
+                }
+                self.apply(&matches, output, operation)
+            }
+            None => {
+                let hwnd = self
+                    .backend
+                    .get_foreground_window()
+                    .ok_or("No focused window")?;
+                operation(hwnd)
+            }
+        }
+    }
+
+    /// Run an operation on each window, reporting every result
+    fn apply<F>(
+        &self,
+        windows: &[&WindowInfo],
+        output: &mut CommandOutput,
+        operation: F,
+    ) -> Result<(), String>
+    where
+        F: Fn(isize) -> Result<(), String>,
+    {
+        let mut failed = 0;
+        for window in windows {
+            match operation(window.hwnd) {
+                Ok(()) => output.out(format!("{}: {}", window.process_name, window.title)),
+                Err(e) => {
+                    output.err(format!("{}: {}: {}", window.process_name, window.title, e));
+                    failed += 1;
+                }
+            }
+        }
+
+        match failed {
+            0 => Ok(()),
+            _ => Err(format!("{} of {} windows failed", failed, windows.len())),
+        }
+    }
+}
+
+fn window_state(window: &WindowInfo) -> &'static str {
+    if window.is_offscreen {
+        "off-screen"
+    } else if window.is_minimized {
+        "minimized"
+    } else {
+        ""
+    }
+}
+
+/// Lay out rows in aligned columns; the last column isn't padded
+fn format_table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    let lines: Vec<String> = std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(index, (cell, width))| {
+                    if index + 1 == row.len() {
+                        cell.clone()
+                    } else {
+                        format!("{:<width$}", cell, width = width)
+                    }
+                })
+                .collect();
+            cells.join("  ")
+        })
+        .collect();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Command>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_start_the_ui() {
+        assert_eq!(parse(&[]), Ok(None));
+    }
+
+    #[test]
+    fn subcommands() {
+        assert_eq!(parse(&["list"]), Ok(Some(Command::List { json: false })));
+        assert_eq!(
+            parse(&["list", "--json"]),
+            Ok(Some(Command::List { json: true }))
+        );
+        assert_eq!(parse(&["rescue-all"]), Ok(Some(Command::RescueAll)));
+        assert_eq!(
+            parse(&["center"]),
+            Ok(Some(Command::Center { window: None }))
+        );
+        assert_eq!(
+            parse(&["next-monitor", "-w", "notepad"]),
+            Ok(Some(Command::NextMonitor {
+                window: Some("notepad".to_string())
+            }))
+        );
+        assert_eq!(
+            parse(&["save-layout", "Desk"]),
+            Ok(Some(Command::SaveLayout {
+                name: "Desk".to_string()
+            }))
+        );
+        assert_eq!(
+            parse(&["layouts", "--json"]),
+            Ok(Some(Command::Layouts { json: true }))
+        );
+        assert_eq!(parse(&["show"]), Ok(Some(Command::Show)));
+        assert_eq!(parse(&["-h"]), Ok(Some(Command::Help)));
+        assert_eq!(parse(&["--help"]), Ok(Some(Command::Help)));
+    }
+
+    #[test]
+    fn move_takes_values_in_either_form() {
+        let expected = Ok(Some(Command::Move {
+            window: "title~\"Meet\"".to_string(),
+            monitor: "2".to_string(),
+        }));
+
+        assert_eq!(
+            parse(&["move", "--window", "title~\"Meet\"", "--monitor", "2"]),
+            expected
+        );
+        assert_eq!(
+            parse(&["move", "--monitor=2", "--window=title~\"Meet\""]),
+            expected
+        );
+        assert_eq!(
+            parse(&["move", "-w", "title~\"Meet\"", "-m", "2"]),
+            expected
+        );
+    }
+
+    #[test]
+    fn inline_values_may_contain_equals_signs() {
+        assert_eq!(
+            parse(&["center", "--window=title:a=b"]),
+            Ok(Some(Command::Center {
+                window: Some("title:a=b".to_string())
+            }))
+        );
+    }
+
+    #[test]
+    fn missing_values_are_errors() {
+        assert_eq!(
+            parse(&["move", "--window"]),
+            Err("--window needs a value".to_string())
+        );
+        assert_eq!(
+            parse(&["move", "--window", "notepad"]),
+            Err("move needs --monitor".to_string())
+        );
+        assert_eq!(
+            parse(&["move", "--monitor", "1"]),
+            Err("move needs --window".to_string())
+        );
+        assert_eq!(
+            parse(&["restore-layout"]),
+            Err("restore-layout needs a layout name".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_commands_and_options_are_errors() {
+        assert_eq!(
+            parse(&["frobnicate"]),
+            Err("Unknown command: frobnicate".to_string())
+        );
+        assert_eq!(
+            parse(&["list", "--verbose"]),
+            Err("Unknown option: --verbose".to_string())
+        );
+        assert_eq!(
+            parse(&["list", "extra"]),
+            Err("Unexpected argument: extra".to_string())
+        );
+        assert_eq!(
+            parse(&["save-layout", "a", "b"]),
+            Err("Unexpected argument: b".to_string())
+        );
+    }
+
+    #[test]
+    fn options_a_command_ignores_are_errors() {
+        assert_eq!(
+            parse(&["rescue-all", "--json"]),
+            Err("rescue-all doesn't take --json".to_string())
+        );
+        assert_eq!(
+            parse(&["move", "--window", "x", "--monitor", "1", "--json"]),
+            Err("move doesn't take --json".to_string())
+        );
+        assert_eq!(
+            parse(&["list", "--window", "x"]),
+            Err("list doesn't take --window".to_string())
+        );
+        assert_eq!(
+            parse(&["center", "--monitor", "1"]),
+            Err("center doesn't take --monitor".to_string())
+        );
+    }
+}
@@ -1,204 +1,331 @@
 //! Global hotkey support using global-hotkey
 
+use crate::keycodes;
 use crate::types::{HotkeyAction, HotkeyBinding, HotkeySettings};
 use global_hotkey::hotkey::{Code, HotKey, Modifiers};
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Why an enabled binding isn't active
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyConflict {
+    /// Another binding in settings already uses the same shortcut
+    Duplicate(HotkeyAction),
+    /// The key can't be registered as a global hotkey
+    Unsupported,
+    /// The system refused the shortcut, usually because another app owns it
+    Unavailable,
+}
+
+/// Bindings that failed, keyed by (action, binding index)
+pub type HotkeyConflicts = HashMap<(HotkeyAction, usize), HotkeyConflict>;
+
+/// Conflicts that can be found from settings alone
+pub fn duplicate_conflicts(settings: &HotkeySettings) -> HotkeyConflicts {
+    settings
+        .duplicates()
+        .into_iter()
+        .map(|(slot, owner)| (slot, HotkeyConflict::Duplicate(owner)))
+        .collect()
+}
+
+/// What a hotkey press means for the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyEvent {
+    /// Run an action
+    Action(HotkeyAction),
+    /// The leader chord was pressed; the next key picks the action
+    LeaderStarted,
+    /// The leader chord timed out, was cancelled or pressed again
+    LeaderCancelled,
+}
+
+/// Something that can grab global shortcuts from the system
+///
+/// Implemented by `GlobalHotKeyManager`; other implementations let the
+/// registration logic run without an OS.
+pub trait HotkeyRegistrar {
+    fn register(&mut self, hotkey: HotKey) -> Result<(), global_hotkey::Error>;
+    fn unregister(&mut self, hotkey: HotKey) -> Result<(), global_hotkey::Error>;
+}
+
+impl HotkeyRegistrar for GlobalHotKeyManager {
+    fn register(&mut self, hotkey: HotKey) -> Result<(), global_hotkey::Error> {
+        GlobalHotKeyManager::register(self, hotkey)
+    }
+
+    fn unregister(&mut self, hotkey: HotKey) -> Result<(), global_hotkey::Error> {
+        GlobalHotKeyManager::unregister(self, hotkey)
+    }
+}
+
+/// A shortcut currently grabbed from the system
+struct Registration {
+    hotkey: HotKey,
+    action: HotkeyAction,
+}
+
+/// Waiting for the key that follows the leader chord
+struct LeaderState {
+    deadline: Instant,
+    /// Follow-up keys grabbed for the duration of the wait
+    grabbed: Vec<HotKey>,
+}
 
 /// Manages global hotkeys
-pub struct HotkeyManager {
-    manager: GlobalHotKeyManager,
-    registered: HashMap<u32, HotkeyAction>,
+pub struct HotkeyManager<R: HotkeyRegistrar = GlobalHotKeyManager> {
+    registrar: R,
+    /// Registered shortcuts, keyed by hotkey id
+    registered: HashMap<u32, Registration>,
+    /// Keys accepted after the leader chord, keyed by hotkey id
+    leader_keys: HashMap<u32, (HotKey, HotkeyAction)>,
+    leader_timeout: Duration,
+    /// Set while waiting for the key after the leader chord
+    leader: Option<LeaderState>,
 }
 
 impl HotkeyManager {
     /// Create a new hotkey manager
     pub fn new() -> Result<Self, global_hotkey::Error> {
-        let manager = GlobalHotKeyManager::new()?;
-        Ok(Self {
-            manager,
-            registered: HashMap::new(),
-        })
+        Ok(Self::with_registrar(GlobalHotKeyManager::new()?))
     }
+}
 
-    /// Register all enabled hotkeys from settings
-    pub fn register_from_settings(&mut self, settings: &HotkeySettings) {
-        // Unregister all existing
-        self.unregister_all();
+impl<R: HotkeyRegistrar> HotkeyManager<R> {
+    /// Create a hotkey manager on top of a registrar
+    pub fn with_registrar(registrar: R) -> Self {
+        Self {
+            registrar,
+            registered: HashMap::new(),
+            leader_keys: HashMap::new(),
+            leader_timeout: Duration::ZERO,
+            leader: None,
+        }
+    }
 
-        // Register lasso window hotkey
-        if settings.lasso_window.enabled {
-            if let Some(hotkey) = binding_to_hotkey(&settings.lasso_window) {
-                if self.manager.register(hotkey).is_ok() {
-                    self.registered.insert(hotkey.id(), HotkeyAction::LassoWindow);
+    /// Bring the registered hotkeys in line with settings
+    ///
+    /// Only shortcuts that were added or removed are registered or
+    /// unregistered; unchanged ones stay grabbed. Returns the bindings that
+    /// couldn't be registered. Duplicates are skipped so the binding that owns
+    /// the shortcut keeps working.
+    pub fn register_from_settings(&mut self, settings: &HotkeySettings) -> HotkeyConflicts {
+        self.end_leader();
+        self.leader_timeout = Duration::from_millis(settings.leader_timeout_ms);
+        self.leader_keys = settings
+            .leader_keys
+            .iter()
+            .filter(|(_, action)| **action != HotkeyAction::Leader)
+            .filter_map(|(key, action)| match keycodes::find_key(key) {
+                Some(info) => {
+                    let hotkey = HotKey::new(None, info.code);
+                    Some((hotkey.id(), (hotkey, action.clone())))
                 }
-            }
-        }
+                None => {
+                    warn!("Unknown leader key: {}", key);
+                    None
+                }
+            })
+            .collect();
 
-        // Register refresh windows hotkey
-        if settings.refresh_windows.enabled {
-            if let Some(hotkey) = binding_to_hotkey(&settings.refresh_windows) {
-                if self.manager.register(hotkey).is_ok() {
-                    self.registered
-                        .insert(hotkey.id(), HotkeyAction::RefreshWindows);
+        let mut conflicts = duplicate_conflicts(settings);
+
+        // Shortcuts wanted by settings, keyed by hotkey id
+        let mut wanted: HashMap<u32, (HotKey, &HotkeyAction, usize)> = HashMap::new();
+        for (action, bindings) in &settings.bindings {
+            for (index, binding) in bindings.iter().enumerate() {
+                let slot = (action.clone(), index);
+                if !binding.enabled || conflicts.contains_key(&slot) {
+                    continue;
                 }
-            }
-        }
 
-        // Register move to primary hotkey
-        if settings.move_to_primary.enabled {
-            if let Some(hotkey) = binding_to_hotkey(&settings.move_to_primary) {
-                if self.manager.register(hotkey).is_ok() {
-                    self.registered
-                        .insert(hotkey.id(), HotkeyAction::MoveToPrimary);
+                let Some(hotkey) = binding_to_hotkey(binding) else {
+                    conflicts.insert(slot, HotkeyConflict::Unsupported);
+                    continue;
+                };
+                // Different spellings of a key (e.g. Enter and Return) map to one hotkey
+                if let Some((_, owner, _)) = wanted.get(&hotkey.id()) {
+                    conflicts.insert(slot, HotkeyConflict::Duplicate((*owner).clone()));
+                    continue;
                 }
+                wanted.insert(hotkey.id(), (hotkey, action, index));
             }
         }
 
-        // Register move all to primary hotkey
-        if settings.move_all_to_primary.enabled {
-            if let Some(hotkey) = binding_to_hotkey(&settings.move_all_to_primary) {
-                if self.manager.register(hotkey).is_ok() {
-                    self.registered
-                        .insert(hotkey.id(), HotkeyAction::MoveAllToPrimary);
-                }
+        // Release shortcuts that are no longer wanted
+        let stale: Vec<u32> = self
+            .registered
+            .keys()
+            .filter(|id| !wanted.contains_key(id))
+            .copied()
+            .collect();
+        for id in stale {
+            if let Some(registration) = self.registered.remove(&id) {
+                self.release(registration.hotkey);
             }
         }
 
-        // Register center window hotkey
-        if settings.center_window.enabled {
-            if let Some(hotkey) = binding_to_hotkey(&settings.center_window) {
-                if self.manager.register(hotkey).is_ok() {
-                    self.registered
-                        .insert(hotkey.id(), HotkeyAction::CenterWindow);
-                }
+        // Grab new shortcuts and point existing ones at their current action
+        for (id, (hotkey, action, index)) in wanted {
+            if let Some(registration) = self.registered.get_mut(&id) {
+                registration.action = action.clone();
+                continue;
             }
-        }
 
-        // Register next monitor hotkey
-        if settings.next_monitor.enabled {
-            if let Some(hotkey) = binding_to_hotkey(&settings.next_monitor) {
-                if self.manager.register(hotkey).is_ok() {
-                    self.registered
-                        .insert(hotkey.id(), HotkeyAction::NextMonitor);
+            match self.registrar.register(hotkey) {
+                Ok(()) => {
+                    self.registered.insert(
+                        id,
+                        Registration {
+                            hotkey,
+                            action: action.clone(),
+                        },
+                    );
+                }
+                Err(e) => {
+                    warn!("Failed to register hotkey for {}: {}", action, e);
+                    // Raised for keys the platform has no mapping for
+                    let conflict = match e {
+                        global_hotkey::Error::FailedToRegister(_) => HotkeyConflict::Unsupported,
+                        _ => HotkeyConflict::Unavailable,
+                    };
+                    conflicts.insert((action.clone(), index), conflict);
                 }
             }
         }
+
+        conflicts
     }
 
     /// Unregister all hotkeys
     pub fn unregister_all(&mut self) {
-        // Note: The global-hotkey crate doesn't expose individual unregister by id,
-        // so we just clear our tracking map. Hotkeys will be re-registered when needed.
-        self.registered.clear();
+        self.end_leader();
+        let registrations: Vec<Registration> = self
+            .registered
+            .drain()
+            .map(|(_, registration)| registration)
+            .collect();
+        for registration in registrations {
+            self.release(registration.hotkey);
+        }
     }
 
     /// Get the action for a hotkey id
     pub fn get_action(&self, id: u32) -> Option<HotkeyAction> {
-        self.registered.get(&id).copied()
+        self.registered
+            .get(&id)
+            .map(|registration| registration.action.clone())
     }
-}
 
-impl Drop for HotkeyManager {
-    fn drop(&mut self) {
-        self.unregister_all();
+    /// Work out what a hotkey press means
+    ///
+    /// The leader chord grabs the follow-up keys until one of them is
+    /// pressed, Escape is pressed or the timeout passes. Any other hotkey
+    /// pressed meanwhile ends the wait and runs as usual; the leader chord
+    /// itself cancels it.
+    pub fn handle_press(&mut self, id: u32, now: Instant) -> Option<HotkeyEvent> {
+        if self.leader.is_some() {
+            let follow_up = self.leader_keys.get(&id).map(|(_, action)| action.clone());
+            let escape = HotKey::new(None, Code::Escape).id() == id;
+            self.end_leader();
+
+            if let Some(action) = follow_up {
+                return Some(HotkeyEvent::Action(action));
+            }
+            if escape {
+                return Some(HotkeyEvent::LeaderCancelled);
+            }
+            return match self.get_action(id)? {
+                HotkeyAction::Leader => Some(HotkeyEvent::LeaderCancelled),
+                action => Some(HotkeyEvent::Action(action)),
+            };
+        }
+
+        match self.get_action(id)? {
+            HotkeyAction::Leader => {
+                self.start_leader(now);
+                Some(HotkeyEvent::LeaderStarted)
+            }
+            action => Some(HotkeyEvent::Action(action)),
+        }
     }
-}
 
-/// Convert a HotkeyBinding to a global-hotkey HotKey
-fn binding_to_hotkey(binding: &HotkeyBinding) -> Option<HotKey> {
-    let code = key_to_code(&binding.key)?;
-    let modifiers = modifiers_to_flags(&binding.modifiers);
+    /// End the leader wait once its timeout has passed
+    pub fn tick(&mut self, now: Instant) -> Option<HotkeyEvent> {
+        let leader = self.leader.as_ref()?;
+        if now < leader.deadline {
+            return None;
+        }
 
-    Some(HotKey::new(modifiers, code))
-}
+        self.end_leader();
+        Some(HotkeyEvent::LeaderCancelled)
+    }
+
+    /// Grab the follow-up keys and Escape
+    ///
+    /// Keys that are already registered as hotkeys of their own are left
+    /// alone; presses of them still reach the leader through their id.
+    fn start_leader(&mut self, now: Instant) {
+        let candidates: Vec<HotKey> = self
+            .leader_keys
+            .values()
+            .map(|(hotkey, _)| *hotkey)
+            .chain(std::iter::once(HotKey::new(None, Code::Escape)))
+            .collect();
+
+        let mut grabbed = Vec::new();
+        for hotkey in candidates {
+            if self.registered.contains_key(&hotkey.id()) || grabbed.contains(&hotkey) {
+                continue;
+            }
+            match self.registrar.register(hotkey) {
+                Ok(()) => grabbed.push(hotkey),
+                Err(e) => warn!("Failed to grab leader key {}: {}", hotkey.id(), e),
+            }
+        }
 
-fn modifiers_to_flags(modifiers: &[String]) -> Option<Modifiers> {
-    let mut flags = Modifiers::empty();
+        self.leader = Some(LeaderState {
+            deadline: now + self.leader_timeout,
+            grabbed,
+        });
+    }
 
-    for m in modifiers {
-        match m.to_lowercase().as_str() {
-            "ctrl" | "control" => flags |= Modifiers::CONTROL,
-            "alt" => flags |= Modifiers::ALT,
-            "shift" => flags |= Modifiers::SHIFT,
-            "win" | "super" | "meta" => flags |= Modifiers::META,
-            _ => {}
+    /// Release the follow-up keys, if the leader is waiting
+    fn end_leader(&mut self) {
+        if let Some(leader) = self.leader.take() {
+            for hotkey in leader.grabbed {
+                self.release(hotkey);
+            }
         }
     }
 
-    if flags.is_empty() {
-        None
-    } else {
-        Some(flags)
+    fn release(&mut self, hotkey: HotKey) {
+        if let Err(e) = self.registrar.unregister(hotkey) {
+            warn!("Failed to unregister hotkey {}: {}", hotkey.id(), e);
+        }
+    }
+}
+
+impl<R: HotkeyRegistrar> Drop for HotkeyManager<R> {
+    fn drop(&mut self) {
+        self.unregister_all();
     }
 }
 
-fn key_to_code(key: &str) -> Option<Code> {
-    match key.to_uppercase().as_str() {
-        "A" => Some(Code::KeyA),
-        "B" => Some(Code::KeyB),
-        "C" => Some(Code::KeyC),
-        "D" => Some(Code::KeyD),
-        "E" => Some(Code::KeyE),
-        "F" => Some(Code::KeyF),
-        "G" => Some(Code::KeyG),
-        "H" => Some(Code::KeyH),
-        "I" => Some(Code::KeyI),
-        "J" => Some(Code::KeyJ),
-        "K" => Some(Code::KeyK),
-        "L" => Some(Code::KeyL),
-        "M" => Some(Code::KeyM),
-        "N" => Some(Code::KeyN),
-        "O" => Some(Code::KeyO),
-        "P" => Some(Code::KeyP),
-        "Q" => Some(Code::KeyQ),
-        "R" => Some(Code::KeyR),
-        "S" => Some(Code::KeyS),
-        "T" => Some(Code::KeyT),
-        "U" => Some(Code::KeyU),
-        "V" => Some(Code::KeyV),
-        "W" => Some(Code::KeyW),
-        "X" => Some(Code::KeyX),
-        "Y" => Some(Code::KeyY),
-        "Z" => Some(Code::KeyZ),
-        "0" => Some(Code::Digit0),
-        "1" => Some(Code::Digit1),
-        "2" => Some(Code::Digit2),
-        "3" => Some(Code::Digit3),
-        "4" => Some(Code::Digit4),
-        "5" => Some(Code::Digit5),
-        "6" => Some(Code::Digit6),
-        "7" => Some(Code::Digit7),
-        "8" => Some(Code::Digit8),
-        "9" => Some(Code::Digit9),
-        "F1" => Some(Code::F1),
-        "F2" => Some(Code::F2),
-        "F3" => Some(Code::F3),
-        "F4" => Some(Code::F4),
-        "F5" => Some(Code::F5),
-        "F6" => Some(Code::F6),
-        "F7" => Some(Code::F7),
-        "F8" => Some(Code::F8),
-        "F9" => Some(Code::F9),
-        "F10" => Some(Code::F10),
-        "F11" => Some(Code::F11),
-        "F12" => Some(Code::F12),
-        "SPACE" => Some(Code::Space),
-        "ENTER" | "RETURN" => Some(Code::Enter),
-        "TAB" => Some(Code::Tab),
-        "ESCAPE" | "ESC" => Some(Code::Escape),
-        "HOME" => Some(Code::Home),
-        "END" => Some(Code::End),
-        "PAGEUP" => Some(Code::PageUp),
-        "PAGEDOWN" => Some(Code::PageDown),
-        "INSERT" => Some(Code::Insert),
-        "DELETE" => Some(Code::Delete),
-        "UP" => Some(Code::ArrowUp),
-        "DOWN" => Some(Code::ArrowDown),
-        "LEFT" => Some(Code::ArrowLeft),
-        "RIGHT" => Some(Code::ArrowRight),
-        _ => None,
+/// Convert a HotkeyBinding to a global-hotkey HotKey
+fn binding_to_hotkey(binding: &HotkeyBinding) -> Option<HotKey> {
+    let code = keycodes::find_key(&binding.key)?.code;
+    let mut modifiers = Modifiers::empty();
+    for modifier in &binding.modifiers {
+        modifiers |= keycodes::find_modifier(modifier)?.flag;
     }
+
+    Some(HotKey::new(
+        (!modifiers.is_empty()).then_some(modifiers),
+        code,
+    ))
 }
 
 /// Poll for hotkey events (returns the hotkey ID if pressed)
@@ -210,3 +337,317 @@ pub fn poll_hotkey_event() -> Option<u32> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::MonitorTarget;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    enum Call {
+        Register(u32),
+        Unregister(u32),
+    }
+
+    /// What the fake registrar has been asked to do, shared with the test
+    #[derive(Default)]
+    struct Grabs {
+        calls: Vec<Call>,
+        grabbed: HashSet<u32>,
+        /// Shortcuts "owned by another app"
+        refused: HashSet<u32>,
+    }
+
+    #[derive(Clone, Default)]
+    struct FakeRegistrar(Rc<RefCell<Grabs>>);
+
+    impl FakeRegistrar {
+        fn take_calls(&self) -> Vec<Call> {
+            std::mem::take(&mut self.0.borrow_mut().calls)
+        }
+
+        fn grabbed(&self) -> HashSet<u32> {
+            self.0.borrow().grabbed.clone()
+        }
+    }
+
+    impl HotkeyRegistrar for FakeRegistrar {
+        fn register(&mut self, hotkey: HotKey) -> Result<(), global_hotkey::Error> {
+            let mut grabs = self.0.borrow_mut();
+            grabs.calls.push(Call::Register(hotkey.id()));
+            if grabs.refused.contains(&hotkey.id()) || !grabs.grabbed.insert(hotkey.id()) {
+                return Err(global_hotkey::Error::AlreadyRegistered(hotkey));
+            }
+            Ok(())
+        }
+
+        fn unregister(&mut self, hotkey: HotKey) -> Result<(), global_hotkey::Error> {
+            let mut grabs = self.0.borrow_mut();
+            grabs.calls.push(Call::Unregister(hotkey.id()));
+            match grabs.grabbed.remove(&hotkey.id()) {
+                true => Ok(()),
+                false => Err(global_hotkey::Error::FailedToUnRegister(hotkey)),
+            }
+        }
+    }
+
+    fn chord_id(modifiers: &[&str], key: &str) -> u32 {
+        binding_to_hotkey(&HotkeyBinding::new(modifiers, key))
+            .unwrap()
+            .id()
+    }
+
+    fn key_id(key: &str) -> u32 {
+        chord_id(&[], key)
+    }
+
+    fn manager() -> (HotkeyManager<FakeRegistrar>, FakeRegistrar) {
+        let registrar = FakeRegistrar::default();
+        (HotkeyManager::with_registrar(registrar.clone()), registrar)
+    }
+
+    fn enabled_bindings(settings: &HotkeySettings) -> usize {
+        settings
+            .bindings
+            .values()
+            .flatten()
+            .filter(|binding| binding.enabled)
+            .count()
+    }
+
+    #[test]
+    fn registers_every_enabled_binding() {
+        let (mut manager, registrar) = manager();
+        let settings = HotkeySettings::default();
+
+        let conflicts = manager.register_from_settings(&settings);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(registrar.grabbed().len(), enabled_bindings(&settings));
+        assert_eq!(
+            manager.get_action(chord_id(&["Ctrl", "Alt"], "L")),
+            Some(HotkeyAction::LassoWindow)
+        );
+        // Disabled bindings aren't grabbed
+        assert!(!registrar
+            .grabbed()
+            .contains(&chord_id(&["Ctrl", "Alt"], "1")));
+    }
+
+    #[test]
+    fn unchanged_bindings_are_not_registered_again() {
+        let (mut manager, registrar) = manager();
+        let settings = HotkeySettings::default();
+        manager.register_from_settings(&settings);
+        registrar.take_calls();
+
+        manager.register_from_settings(&settings);
+
+        assert_eq!(registrar.take_calls(), []);
+    }
+
+    #[test]
+    fn removed_bindings_are_unregistered() {
+        let (mut manager, registrar) = manager();
+        let mut settings = HotkeySettings::default();
+        manager.register_from_settings(&settings);
+        registrar.take_calls();
+
+        settings.remove_binding(&HotkeyAction::CenterWindow, 0);
+        settings.set_enabled(&HotkeyAction::NextMonitor, 0, false);
+        manager.register_from_settings(&settings);
+
+        let mut calls = registrar.take_calls();
+        calls.sort();
+        let mut expected = vec![
+            Call::Unregister(chord_id(&["Ctrl", "Alt"], "C")),
+            Call::Unregister(chord_id(&["Ctrl", "Alt"], "N")),
+        ];
+        expected.sort();
+        assert_eq!(calls, expected);
+        assert_eq!(manager.get_action(chord_id(&["Ctrl", "Alt"], "C")), None);
+    }
+
+    #[test]
+    fn repointed_chord_keeps_its_grab_and_changes_action() {
+        let (mut manager, registrar) = manager();
+        let mut settings = HotkeySettings::default();
+        manager.register_from_settings(&settings);
+        registrar.take_calls();
+
+        // Ctrl+Alt+L moves from lasso to refresh, replacing Ctrl+Alt+R
+        settings
+            .bindings
+            .insert(HotkeyAction::LassoWindow, Vec::new());
+        settings.bindings.insert(
+            HotkeyAction::RefreshWindows,
+            vec![HotkeyBinding::new(&["Ctrl", "Alt"], "L")],
+        );
+        manager.register_from_settings(&settings);
+
+        assert_eq!(
+            registrar.take_calls(),
+            [Call::Unregister(chord_id(&["Ctrl", "Alt"], "R"))]
+        );
+        assert_eq!(
+            manager.get_action(chord_id(&["Ctrl", "Alt"], "L")),
+            Some(HotkeyAction::RefreshWindows)
+        );
+    }
+
+    #[test]
+    fn refused_shortcuts_are_reported() {
+        let (mut manager, registrar) = manager();
+        let refused = chord_id(&["Ctrl", "Alt"], "P");
+        registrar.0.borrow_mut().refused.insert(refused);
+
+        let conflicts = manager.register_from_settings(&HotkeySettings::default());
+
+        assert_eq!(
+            conflicts.get(&(HotkeyAction::MoveToPrimary, 0)),
+            Some(&HotkeyConflict::Unavailable)
+        );
+        assert_eq!(manager.get_action(refused), None);
+    }
+
+    #[test]
+    fn unknown_modifiers_are_unsupported() {
+        let (mut manager, registrar) = manager();
+        let mut settings = HotkeySettings::default();
+        settings.set_binding(
+            HotkeyAction::LassoWindow,
+            Some(0),
+            HotkeyBinding::new(&["Hyper", "Ctrl"], "L"),
+        );
+        settings.set_binding(
+            HotkeyAction::RefreshWindows,
+            Some(0),
+            HotkeyBinding::new(&["Ctrl"], "L"),
+        );
+
+        let conflicts = manager.register_from_settings(&settings);
+
+        assert_eq!(
+            conflicts.get(&(HotkeyAction::LassoWindow, 0)),
+            Some(&HotkeyConflict::Unsupported)
+        );
+        assert_eq!(conflicts.get(&(HotkeyAction::RefreshWindows, 0)), None);
+        assert!(registrar.grabbed().contains(&chord_id(&["Ctrl"], "L")));
+    }
+
+    #[test]
+    fn unregister_all_releases_everything() {
+        let (mut manager, registrar) = manager();
+        manager.register_from_settings(&HotkeySettings::default());
+
+        manager.unregister_all();
+
+        assert!(registrar.grabbed().is_empty());
+        assert_eq!(manager.get_action(chord_id(&["Ctrl", "Alt"], "L")), None);
+    }
+
+    #[test]
+    fn drop_releases_everything() {
+        let (mut manager, registrar) = manager();
+        manager.register_from_settings(&HotkeySettings::default());
+        let leader = chord_id(&["Ctrl", "Alt"], "W");
+        manager.handle_press(leader, Instant::now());
+        assert!(!registrar.grabbed().is_empty());
+
+        drop(manager);
+
+        assert!(registrar.grabbed().is_empty());
+    }
+
+    #[test]
+    fn leader_grabs_follow_up_keys_until_one_is_pressed() {
+        let (mut manager, registrar) = manager();
+        manager.register_from_settings(&HotkeySettings::default());
+        let normal = registrar.grabbed();
+        let leader = chord_id(&["Ctrl", "Alt"], "W");
+        let now = Instant::now();
+
+        assert_eq!(
+            manager.handle_press(leader, now),
+            Some(HotkeyEvent::LeaderStarted)
+        );
+        let during = registrar.grabbed();
+        assert!(during.contains(&key_id("1")));
+        assert!(during.contains(&key_id("H")));
+        assert!(during.contains(&key_id("Escape")));
+
+        assert_eq!(
+            manager.handle_press(key_id("1"), now),
+            Some(HotkeyEvent::Action(HotkeyAction::SendToMonitor(
+                MonitorTarget::Position(1)
+            )))
+        );
+        assert_eq!(registrar.grabbed(), normal);
+        // Without the leader the plain key means nothing
+        assert_eq!(manager.handle_press(key_id("1"), now), None);
+    }
+
+    #[test]
+    fn leader_is_released_on_escape_timeout_and_repeat() {
+        let (mut manager, registrar) = manager();
+        let settings = HotkeySettings::default();
+        manager.register_from_settings(&settings);
+        let normal = registrar.grabbed();
+        let leader = chord_id(&["Ctrl", "Alt"], "W");
+        let now = Instant::now();
+
+        manager.handle_press(leader, now);
+        assert_eq!(
+            manager.handle_press(key_id("Escape"), now),
+            Some(HotkeyEvent::LeaderCancelled)
+        );
+        assert_eq!(registrar.grabbed(), normal);
+
+        manager.handle_press(leader, now);
+        assert_eq!(manager.tick(now), None);
+        let timeout = Duration::from_millis(settings.leader_timeout_ms);
+        assert_eq!(
+            manager.tick(now + timeout),
+            Some(HotkeyEvent::LeaderCancelled)
+        );
+        assert_eq!(registrar.grabbed(), normal);
+
+        manager.handle_press(leader, now);
+        assert_eq!(
+            manager.handle_press(leader, now),
+            Some(HotkeyEvent::LeaderCancelled)
+        );
+        assert_eq!(registrar.grabbed(), normal);
+    }
+
+    #[test]
+    fn other_hotkey_ends_the_leader_and_runs() {
+        let (mut manager, registrar) = manager();
+        manager.register_from_settings(&HotkeySettings::default());
+        let normal = registrar.grabbed();
+        let now = Instant::now();
+
+        manager.handle_press(chord_id(&["Ctrl", "Alt"], "W"), now);
+        assert_eq!(
+            manager.handle_press(chord_id(&["Ctrl", "Alt"], "R"), now),
+            Some(HotkeyEvent::Action(HotkeyAction::RefreshWindows))
+        );
+        assert_eq!(registrar.grabbed(), normal);
+    }
+
+    #[test]
+    fn settings_change_ends_the_leader() {
+        let (mut manager, registrar) = manager();
+        let settings = HotkeySettings::default();
+        manager.register_from_settings(&settings);
+        let normal = registrar.grabbed();
+
+        manager.handle_press(chord_id(&["Ctrl", "Alt"], "W"), Instant::now());
+        manager.register_from_settings(&settings);
+
+        assert_eq!(registrar.grabbed(), normal);
+    }
+}
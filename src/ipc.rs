@@ -0,0 +1,593 @@
+//! Single-instance channel
+//!
+//! The first WindowLasso process listens on a local socket (a named pipe on
+//! Windows). Later launches connect to it, hand over their command and exit,
+//! so there is only ever one tray icon and one set of hotkeys.
+//!
+//! A connection carries one request and one response, each a line of JSON.
+//! Both ends work on any `Read + Write` stream.
+
+use crate::cli::{self, Command, CommandOutput};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+#[cfg(unix)]
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Endpoint later launches connect to
+const INSTANCE_ENDPOINT: &str = "window-lasso";
+
+/// How long a launch may take to send its request
+#[cfg(unix)]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest message accepted, newline included
+pub const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// A request from a newly launched process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum IpcRequest {
+    /// Bring the window to the front
+    Show,
+    /// Refresh the window list
+    Refresh,
+    /// Pick the first off-screen window to move
+    Lasso,
+    /// Carry out a command-line command
+    Run { command: Command },
+}
+
+impl IpcRequest {
+    /// Request that hands a command to the running instance
+    ///
+    /// Launching without a command asks it to show its window. Help is
+    /// printed locally.
+    pub fn for_command(command: Option<&Command>) -> Option<Self> {
+        match command {
+            None | Some(Command::Show) => Some(IpcRequest::Show),
+            Some(Command::Refresh) => Some(IpcRequest::Refresh),
+            Some(Command::Lasso) => Some(IpcRequest::Lasso),
+            Some(Command::Help) => None,
+            Some(command) => Some(IpcRequest::Run {
+                command: command.clone(),
+            }),
+        }
+    }
+}
+
+/// Write a message as one line of JSON
+pub fn write_message<T: Serialize>(writer: &mut impl Write, message: &T) -> Result<(), String> {
+    let mut line =
+        serde_json::to_string(message).map_err(|e| format!("Failed to encode message: {}", e))?;
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .and_then(|()| writer.flush())
+        .map_err(|e| format!("Failed to send message: {}", e))
+}
+
+/// Read one line of at most `MAX_MESSAGE_LEN` bytes
+///
+/// A longer line fails with `ErrorKind::InvalidData`, so a peer can't make
+/// us buffer without end. Returns 0 once the peer has closed the connection.
+pub fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<usize> {
+    let read = reader.take(MAX_MESSAGE_LEN as u64).read_line(line)?;
+    if read == MAX_MESSAGE_LEN && !line.ends_with('\n') {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "Message is too long",
+        ));
+    }
+    Ok(read)
+}
+
+/// Read a message written by `write_message`
+pub fn read_message<T: DeserializeOwned>(reader: &mut impl BufRead) -> Result<T, String> {
+    let mut line = String::new();
+    match read_line(reader, &mut line) {
+        Ok(0) => Err("Connection closed".to_string()),
+        Ok(_) => serde_json::from_str(&line).map_err(|e| format!("Invalid message: {}", e)),
+        Err(e) => Err(format!("Failed to receive message: {}", e)),
+    }
+}
+
+/// Client side of a connection
+fn exchange<S: Read + Write>(mut stream: S, request: &IpcRequest) -> Result<CommandOutput, String> {
+    write_message(&mut stream, request)?;
+    read_message(&mut BufReader::new(stream))
+}
+
+/// Server side of a connection
+fn serve<S: Read + Write>(stream: S, app: &Sender<IpcRequest>) -> Result<(), String> {
+    let mut reader = BufReader::new(stream);
+    let request: IpcRequest = read_message(&mut reader)?;
+    info!("Received {:?} from another instance", request);
+
+    let response = handle(request, app);
+    write_message(reader.get_mut(), &response)
+}
+
+/// Commands run right away on the listener thread; the rest go to the app
+fn handle(request: IpcRequest, app: &Sender<IpcRequest>) -> CommandOutput {
+    match request {
+        IpcRequest::Run { command } => {
+            let output = cli::execute(&command);
+            // Let the window list catch up with whatever the command moved
+            let _ = app.send(IpcRequest::Refresh);
+            output
+        }
+        request => match app.send(request) {
+            Ok(()) => CommandOutput::succeeded(),
+            Err(_) => CommandOutput::failed("WindowLasso is shutting down"),
+        },
+    }
+}
+
+/// Why an endpoint couldn't be listened on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindError {
+    /// Another process is already listening on it
+    InUse,
+    Failed(String),
+}
+
+impl fmt::Display for BindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindError::InUse => f.write_str("Another process is already listening"),
+            BindError::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
+/// Hand a request to the running instance and wait for its answer
+///
+/// Returns `None` if no instance is running.
+pub fn send(request: &IpcRequest) -> Option<CommandOutput> {
+    let stream = transport::connect(INSTANCE_ENDPOINT)?;
+    Some(exchange(&stream, request).unwrap_or_else(|e| {
+        CommandOutput::failed(format!("Lost connection to WindowLasso: {}", e))
+    }))
+}
+
+/// Listens for later launches on a background thread
+pub struct InstanceServer {
+    requests: Receiver<IpcRequest>,
+}
+
+impl InstanceServer {
+    /// Start listening; fails with `BindError::InUse` if another instance already is
+    pub fn start() -> Result<Self, BindError> {
+        let listener = transport::Listener::bind(INSTANCE_ENDPOINT)?;
+        let (sender, requests) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("instance-server".to_string())
+            .spawn(move || {
+                // One request at a time; launches are rare and quick to answer
+                listener.run(|stream| {
+                    #[cfg(unix)]
+                    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+                    if let Err(e) = serve(&stream, &sender) {
+                        warn!("Instance request failed: {}", e);
+                    }
+                    transport::finish(&stream);
+                    true
+                })
+            })
+            .map_err(|e| BindError::Failed(format!("Failed to start instance server: {}", e)))?;
+
+        Ok(Self { requests })
+    }
+
+    /// Next request for the app, if any
+    pub fn poll(&self) -> Option<IpcRequest> {
+        self.requests.try_recv().ok()
+    }
+}
+
+/// Local sockets that only the current user can reach
+///
+/// An endpoint name maps to a Unix socket in the runtime directory, or to a
+/// named pipe on Windows.
+#[cfg(unix)]
+pub mod transport {
+    use super::BindError;
+    use std::fs::{self, DirBuilder};
+    use std::io::{self, ErrorKind};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use tracing::warn;
+
+    pub type Stream = UnixStream;
+
+    /// Socket path, private to the current user
+    ///
+    /// Without a runtime directory the socket goes in a directory of our own
+    /// under the temp directory, which other users can't enter.
+    fn socket_path(name: &str) -> io::Result<PathBuf> {
+        let dir = match dirs::runtime_dir() {
+            Some(dir) => dir,
+            None => {
+                let uid = unsafe { libc::getuid() };
+                let dir = std::env::temp_dir().join(format!("{}-{}", name, uid));
+                private_dir(&dir)?;
+                dir
+            }
+        };
+        Ok(dir.join(format!("{}.sock", name)))
+    }
+
+    /// Create a directory only the current user can use, or check that an
+    /// existing one is
+    pub(super) fn private_dir(dir: &Path) -> io::Result<()> {
+        match DirBuilder::new().mode(0o700).create(dir) {
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            result => result?,
+        }
+
+        // Another user may have created it first
+        let metadata = fs::symlink_metadata(dir)?;
+        let uid = unsafe { libc::getuid() };
+        if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                format!("{} is not private to this user", dir.display()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Connect to an endpoint, if something is listening on it
+    pub fn connect(name: &str) -> Option<Stream> {
+        UnixStream::connect(socket_path(name).ok()?).ok()
+    }
+
+    /// Let a stream that is about to close deliver what was written to it
+    pub fn finish(_stream: &Stream) {}
+
+    pub struct Listener(UnixListener);
+
+    impl Listener {
+        /// Listen on an endpoint; fails if another process already is
+        pub fn bind(name: &str) -> Result<Self, BindError> {
+            let path = socket_path(name)
+                .map_err(|e| BindError::Failed(format!("Failed to find socket path: {}", e)))?;
+            Self::bind_path(&path)
+        }
+
+        pub(super) fn bind_path(path: &Path) -> Result<Self, BindError> {
+            let failed = |e: io::Error| {
+                BindError::Failed(format!("Failed to listen on {}: {}", path.display(), e))
+            };
+
+            // The directory is private to us, so nobody else can reach the
+            // socket before its permissions are narrowed below
+            let listener = match UnixListener::bind(path) {
+                Err(e) if e.kind() == ErrorKind::AddrInUse => {
+                    if UnixStream::connect(path).is_ok() {
+                        return Err(BindError::InUse);
+                    }
+                    // Left behind by a process that didn't shut down cleanly
+                    let _ = fs::remove_file(path);
+                    UnixListener::bind(path)
+                }
+                result => result,
+            }
+            .map_err(failed)?;
+
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(failed)?;
+            Ok(Self(listener))
+        }
+
+        /// Hand each connection to `handle` until it returns `false`
+        pub fn run(self, mut handle: impl FnMut(Stream) -> bool) {
+            for stream in self.0.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        if !handle(stream) {
+                            return;
+                        }
+                    }
+                    Err(e) => warn!("Failed to accept connection: {}", e),
+                }
+            }
+        }
+    }
+}
+
+/// Local sockets that only the current user can reach
+///
+/// An endpoint name maps to a Unix socket in the runtime directory, or to a
+/// named pipe on Windows.
+#[cfg(windows)]
+pub mod transport {
+    use super::BindError;
+    use crate::windows_api::{
+        create_pipe, current_user_sid, pipe_owned_by_current_user, wait_for_pipe_client,
+    };
+    use std::fs::{File, OpenOptions};
+    use std::thread;
+    use std::time::Duration;
+    use tracing::warn;
+    use windows::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_PIPE_BUSY};
+
+    pub type Stream = File;
+
+    /// How often a client retries while every pipe instance is busy
+    const BUSY_RETRIES: u32 = 20;
+    const BUSY_DELAY: Duration = Duration::from_millis(50);
+
+    /// Pipe name, one per user
+    ///
+    /// Pipe names are machine-wide, so the name carries the user's SID.
+    fn pipe_name(name: &str) -> windows::core::Result<String> {
+        Ok(format!(r"\\.\pipe\{}-{}", name, current_user_sid()?))
+    }
+
+    /// Connect to an endpoint, if something is listening on it
+    ///
+    /// A pipe another user created under the name doesn't count.
+    pub fn connect(name: &str) -> Option<Stream> {
+        let path = pipe_name(name).ok()?;
+        for _ in 0..BUSY_RETRIES {
+            match OpenOptions::new().read(true).write(true).open(&path) {
+                Ok(pipe) if pipe_owned_by_current_user(&pipe) => return Some(pipe),
+                Ok(_) => {
+                    warn!("Ignoring pipe {} owned by another user", path);
+                    return None;
+                }
+                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY.0 as i32) => {
+                    thread::sleep(BUSY_DELAY)
+                }
+                Err(_) => return None,
+            }
+        }
+        None
+    }
+
+    /// Let a stream that is about to close deliver what was written to it
+    pub fn finish(stream: &Stream) {
+        // Waits until the client has read everything
+        let _ = stream.sync_all();
+    }
+
+    pub struct Listener {
+        path: String,
+        pipe: File,
+    }
+
+    impl Listener {
+        /// Listen on an endpoint; fails if another process already is
+        pub fn bind(name: &str) -> Result<Self, BindError> {
+            let path = pipe_name(name)
+                .map_err(|e| BindError::Failed(format!("Failed to name pipe: {}", e)))?;
+            let pipe = create_pipe(&path, true).map_err(|e| {
+                // What creating the first instance of an existing pipe fails with
+                if e.code() == ERROR_ACCESS_DENIED.to_hresult() {
+                    BindError::InUse
+                } else {
+                    BindError::Failed(format!("Failed to create pipe: {}", e))
+                }
+            })?;
+            Ok(Self { path, pipe })
+        }
+
+        /// Hand each connection to `handle` until it returns `false`
+        pub fn run(self, mut handle: impl FnMut(Stream) -> bool) {
+            let mut pipe = self.pipe;
+            loop {
+                if let Err(e) = wait_for_pipe_client(&pipe) {
+                    warn!("{}", e);
+                    return;
+                }
+
+                // Open the next instance first, so the pipe never disappears
+                // and a new client can't mistake us for gone
+                let next = match create_pipe(&self.path, false) {
+                    Ok(next) => next,
+                    Err(e) => {
+                        warn!("Failed to create pipe: {}", e);
+                        return;
+                    }
+                };
+
+                if !handle(std::mem::replace(&mut pipe, next)) {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use std::path::PathBuf;
+
+    /// Run one exchange against `serve` on a background thread
+    fn round_trip(request: &IpcRequest) -> (CommandOutput, Vec<IpcRequest>) {
+        let (client, server) = UnixStream::pair().unwrap();
+        let (sender, requests) = mpsc::channel();
+        let serving = thread::spawn(move || serve(&server, &sender));
+
+        let output = exchange(&client, request).unwrap();
+        serving.join().unwrap().unwrap();
+        (output, requests.try_iter().collect())
+    }
+
+    /// A socket path no other test uses
+    fn temp_socket(tag: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "window-lasso-test-{}-{}.sock",
+            std::process::id(),
+            tag
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn app_requests_are_forwarded_and_acknowledged() {
+        for request in [IpcRequest::Show, IpcRequest::Refresh, IpcRequest::Lasso] {
+            let (output, received) = round_trip(&request);
+            assert_eq!(output, CommandOutput::succeeded());
+            assert_eq!(received, vec![request]);
+        }
+    }
+
+    #[test]
+    fn commands_run_on_the_server_and_refresh_the_app() {
+        let (output, received) = round_trip(&IpcRequest::Run {
+            command: Command::Help,
+        });
+        assert!(output.success);
+        assert_eq!(output.stdout, format!("{}\n", cli::USAGE));
+        assert_eq!(received, vec![IpcRequest::Refresh]);
+    }
+
+    #[test]
+    fn requests_fail_once_the_app_is_gone() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let (sender, requests) = mpsc::channel();
+        drop(requests);
+        let serving = thread::spawn(move || serve(&server, &sender));
+
+        let output = exchange(&client, &IpcRequest::Show).unwrap();
+        serving.join().unwrap().unwrap();
+        assert!(!output.success);
+    }
+
+    #[test]
+    fn malformed_requests_are_rejected() {
+        let (mut client, server) = UnixStream::pair().unwrap();
+        let (sender, requests) = mpsc::channel();
+        client.write_all(b"{\"request\":\"explode\"}\n").unwrap();
+
+        let result = serve(&server, &sender);
+        assert!(result.unwrap_err().starts_with("Invalid message"));
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn a_closed_connection_is_an_error() {
+        let (client, server) = UnixStream::pair().unwrap();
+        drop(server);
+        assert!(exchange(&client, &IpcRequest::Show).is_err());
+    }
+
+    #[test]
+    fn requests_survive_encoding() {
+        let requests = [
+            IpcRequest::Show,
+            IpcRequest::Lasso,
+            IpcRequest::Run {
+                command: Command::Center { window: None },
+            },
+        ];
+        let mut buffer = Vec::new();
+        for request in &requests {
+            write_message(&mut buffer, request).unwrap();
+        }
+
+        let mut reader = buffer.as_slice();
+        for request in &requests {
+            assert_eq!(&read_message::<IpcRequest>(&mut reader).unwrap(), request);
+        }
+        assert_eq!(
+            read_message::<IpcRequest>(&mut reader),
+            Err("Connection closed".to_string())
+        );
+    }
+
+    #[test]
+    fn overlong_lines_are_refused() {
+        let mut long = vec![b'x'; MAX_MESSAGE_LEN];
+        long.push(b'\n');
+        let mut line = String::new();
+        let error = read_line(&mut long.as_slice(), &mut line).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        let mut fits = vec![b'x'; MAX_MESSAGE_LEN - 1];
+        fits.push(b'\n');
+        line.clear();
+        assert_eq!(
+            read_line(&mut fits.as_slice(), &mut line).unwrap(),
+            MAX_MESSAGE_LEN
+        );
+    }
+
+    #[test]
+    fn a_second_listener_is_refused() {
+        let path = temp_socket("in-use");
+        let _first = transport::Listener::bind_path(&path).unwrap();
+        assert_eq!(
+            transport::Listener::bind_path(&path).err(),
+            Some(BindError::InUse)
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn sockets_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_socket("private");
+        let _listener = transport::Listener::bind_path(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn the_fallback_directory_must_be_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("window-lasso-test-{}-dir", std::process::id()));
+        let _ = std::fs::remove_dir(&dir);
+
+        transport::private_dir(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // Reusing our own directory is fine
+        transport::private_dir(&dir).unwrap();
+
+        // One others can reach is not
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let error = transport::private_dir(&dir).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn a_stale_socket_is_replaced() {
+        let path = temp_socket("stale");
+        // Dropping a listener leaves its socket file behind, like a crash
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let listener = transport::Listener::bind_path(&path).unwrap();
+        let (sender, requests) = mpsc::channel();
+        let serving = thread::spawn(move || {
+            listener.run(|stream| {
+                serve(&stream, &sender).unwrap();
+                false
+            })
+        });
+
+        let client = UnixStream::connect(&path).unwrap();
+        let output = exchange(&client, &IpcRequest::Show).unwrap();
+        serving.join().unwrap();
+        assert_eq!(output, CommandOutput::succeeded());
+        assert_eq!(requests.try_recv(), Ok(IpcRequest::Show));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,414 @@
+//! Canonical key table
+//!
+//! Every key a hotkey can use, with the name stored in settings, the code
+//! registered with the system and the code the hotkey recorder sees. The
+//! recorder, the registrar and binding parsing all look keys up here.
+
+use global_hotkey::hotkey::{Code, Modifiers};
+use iced::keyboard::key::{Code as PhysicalCode, Physical};
+use iced::keyboard::Key;
+
+/// A key that can be bound to a hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyInfo {
+    /// Canonical name, as stored in settings and shown in the UI
+    pub name: &'static str,
+    /// Other accepted spellings
+    pub aliases: &'static [&'static str],
+    /// Code registered with the system
+    pub code: Code,
+    /// Code reported by the keyboard when the key is pressed
+    pub physical: PhysicalCode,
+}
+
+impl KeyInfo {
+    fn matches(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+
+    /// The key a hotkey on this one is registered as
+    ///
+    /// Windows gives both Enter keys the same virtual key, so a hotkey on
+    /// either fires for both and they count as one key there.
+    pub fn registered(&'static self) -> &'static KeyInfo {
+        registered_as(self, cfg!(windows))
+    }
+}
+
+/// Keys Windows registers with the same virtual key as another key
+const WINDOWS_SHARED_KEYS: &[(Code, Code)] = &[(Code::NumpadEnter, Code::Enter)];
+
+fn registered_as(key: &'static KeyInfo, windows: bool) -> &'static KeyInfo {
+    if !windows {
+        return key;
+    }
+    WINDOWS_SHARED_KEYS
+        .iter()
+        .find(|(code, _)| *code == key.code)
+        .and_then(|(_, shared)| KEYS.iter().find(|other| other.code == *shared))
+        .unwrap_or(key)
+}
+
+/// A hotkey modifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModifierInfo {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub flag: Modifiers,
+}
+
+/// Modifiers in the order they are written
+pub static MODIFIERS: &[ModifierInfo] = &[
+    ModifierInfo {
+        name: "Ctrl",
+        aliases: &["Control"],
+        flag: Modifiers::CONTROL,
+    },
+    ModifierInfo {
+        name: "Alt",
+        aliases: &["Option"],
+        flag: Modifiers::ALT,
+    },
+    ModifierInfo {
+        name: "Shift",
+        aliases: &[],
+        flag: Modifiers::SHIFT,
+    },
+    ModifierInfo {
+        name: "Win",
+        aliases: &["Super", "Meta", "Cmd"],
+        flag: Modifiers::META,
+    },
+];
+
+/// Both code types share the W3C key code names, so each entry names it once
+macro_rules! key_table {
+    ($($name:literal $(| $alias:literal)* => $code:ident,)*) => {
+        &[$(KeyInfo {
+            name: $name,
+            aliases: &[$($alias),*],
+            code: Code::$code,
+            physical: PhysicalCode::$code,
+        },)*]
+    };
+}
+
+/// Every bindable key
+pub static KEYS: &[KeyInfo] = key_table! {
+    "A" => KeyA,
+    "B" => KeyB,
+    "C" => KeyC,
+    "D" => KeyD,
+    "E" => KeyE,
+    "F" => KeyF,
+    "G" => KeyG,
+    "H" => KeyH,
+    "I" => KeyI,
+    "J" => KeyJ,
+    "K" => KeyK,
+    "L" => KeyL,
+    "M" => KeyM,
+    "N" => KeyN,
+    "O" => KeyO,
+    "P" => KeyP,
+    "Q" => KeyQ,
+    "R" => KeyR,
+    "S" => KeyS,
+    "T" => KeyT,
+    "U" => KeyU,
+    "V" => KeyV,
+    "W" => KeyW,
+    "X" => KeyX,
+    "Y" => KeyY,
+    "Z" => KeyZ,
+    "0" => Digit0,
+    "1" => Digit1,
+    "2" => Digit2,
+    "3" => Digit3,
+    "4" => Digit4,
+    "5" => Digit5,
+    "6" => Digit6,
+    "7" => Digit7,
+    "8" => Digit8,
+    "9" => Digit9,
+    "F1" => F1,
+    "F2" => F2,
+    "F3" => F3,
+    "F4" => F4,
+    "F5" => F5,
+    "F6" => F6,
+    "F7" => F7,
+    "F8" => F8,
+    "F9" => F9,
+    "F10" => F10,
+    "F11" => F11,
+    "F12" => F12,
+    "F13" => F13,
+    "F14" => F14,
+    "F15" => F15,
+    "F16" => F16,
+    "F17" => F17,
+    "F18" => F18,
+    "F19" => F19,
+    "F20" => F20,
+    "F21" => F21,
+    "F22" => F22,
+    "F23" => F23,
+    "F24" => F24,
+    "Backquote" | "`" => Backquote,
+    "Minus" | "-" => Minus,
+    "Equal" | "=" => Equal,
+    "BracketLeft" | "[" => BracketLeft,
+    "BracketRight" | "]" => BracketRight,
+    "Backslash" | "\\" => Backslash,
+    "Semicolon" | ";" => Semicolon,
+    "Quote" | "'" => Quote,
+    "Comma" | "," => Comma,
+    "Period" | "." => Period,
+    "Slash" | "/" => Slash,
+    "Space" => Space,
+    "Enter" | "Return" => Enter,
+    "Tab" => Tab,
+    "Escape" | "Esc" => Escape,
+    "Backspace" => Backspace,
+    "Insert" | "Ins" => Insert,
+    "Delete" | "Del" => Delete,
+    "Home" => Home,
+    "End" => End,
+    "PageUp" | "PgUp" => PageUp,
+    "PageDown" | "PgDn" => PageDown,
+    "Up" | "ArrowUp" => ArrowUp,
+    "Down" | "ArrowDown" => ArrowDown,
+    "Left" | "ArrowLeft" => ArrowLeft,
+    "Right" | "ArrowRight" => ArrowRight,
+    "Num0" | "Numpad0" => Numpad0,
+    "Num1" | "Numpad1" => Numpad1,
+    "Num2" | "Numpad2" => Numpad2,
+    "Num3" | "Numpad3" => Numpad3,
+    "Num4" | "Numpad4" => Numpad4,
+    "Num5" | "Numpad5" => Numpad5,
+    "Num6" | "Numpad6" => Numpad6,
+    "Num7" | "Numpad7" => Numpad7,
+    "Num8" | "Numpad8" => Numpad8,
+    "Num9" | "Numpad9" => Numpad9,
+    "NumAdd" | "NumpadAdd" => NumpadAdd,
+    "NumSubtract" | "NumpadSubtract" => NumpadSubtract,
+    "NumMultiply" | "NumpadMultiply" => NumpadMultiply,
+    "NumDivide" | "NumpadDivide" => NumpadDivide,
+    "NumDecimal" | "NumpadDecimal" => NumpadDecimal,
+    "NumEnter" | "NumpadEnter" => NumpadEnter,
+    "PrintScreen" | "PrtSc" => PrintScreen,
+    "ScrollLock" => ScrollLock,
+    "Pause" | "Break" => Pause,
+    "PlayPause" | "MediaPlayPause" => MediaPlayPause,
+    "MediaStop" => MediaStop,
+    "NextTrack" | "MediaTrackNext" => MediaTrackNext,
+    "PreviousTrack" | "MediaTrackPrevious" => MediaTrackPrevious,
+    "VolumeUp" | "AudioVolumeUp" => AudioVolumeUp,
+    "VolumeDown" | "AudioVolumeDown" => AudioVolumeDown,
+    "VolumeMute" | "Mute" => AudioVolumeMute,
+};
+
+/// Look up a key by name or alias, ignoring case
+pub fn find_key(name: &str) -> Option<&'static KeyInfo> {
+    KEYS.iter().find(|key| key.matches(name))
+}
+
+/// Look up a modifier by name or alias, ignoring case
+pub fn find_modifier(name: &str) -> Option<&'static ModifierInfo> {
+    MODIFIERS.iter().find(|modifier| {
+        modifier.name.eq_ignore_ascii_case(name)
+            || modifier
+                .aliases
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Key for a press seen by the hotkey recorder
+///
+/// Letters follow the keyboard layout, matching the virtual keys shortcuts
+/// are registered with. Everything else goes by physical key, so numpad keys
+/// stay apart from the digit row whatever the Num Lock state.
+pub fn key_for_press(key: &Key, physical: &Physical) -> Option<&'static KeyInfo> {
+    if let Key::Character(c) = key {
+        let mut chars = c.chars();
+        if let (Some(letter), None) = (chars.next(), chars.next()) {
+            if letter.is_ascii_alphabetic() {
+                return find_key(&letter.to_string());
+            }
+        }
+    }
+
+    match physical {
+        Physical::Code(code) => KEYS.iter().find(|key| key.physical == *code),
+        Physical::Unidentified(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::HotkeyBinding;
+    use std::collections::{HashMap, HashSet};
+
+    /// Every way a key or modifier can be spelled
+    fn spellings<'a>(name: &'a str, aliases: &'a [&'a str]) -> impl Iterator<Item = &'a str> {
+        std::iter::once(name).chain(aliases.iter().copied())
+    }
+
+    #[test]
+    fn every_key_round_trips_through_text() {
+        for key in KEYS {
+            for modifiers in [
+                &[][..],
+                &["Ctrl"],
+                &["Shift", "Win"],
+                &["Ctrl", "Alt", "Shift", "Win"],
+            ] {
+                let binding = HotkeyBinding::new(modifiers, key.name);
+                let text = binding.to_string();
+                assert_eq!(text.parse::<HotkeyBinding>(), Ok(binding), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn every_modifier_round_trips_through_text() {
+        for modifier in MODIFIERS {
+            let binding = HotkeyBinding::new(&[modifier.name], "A");
+            assert_eq!(binding.to_string().parse::<HotkeyBinding>(), Ok(binding));
+        }
+    }
+
+    #[test]
+    fn every_key_alias_parses_to_the_canonical_name() {
+        for key in KEYS {
+            for spelling in spellings(key.name, key.aliases) {
+                for text in [spelling.to_string(), spelling.to_lowercase()] {
+                    let parsed: HotkeyBinding = format!("ctrl+{}", text).parse().unwrap();
+                    assert_eq!(parsed.key, key.name, "{}", text);
+                    assert_eq!(find_key(&text).map(|k| k.code), Some(key.code));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_modifier_alias_parses_to_the_canonical_name() {
+        for modifier in MODIFIERS {
+            for spelling in spellings(modifier.name, modifier.aliases) {
+                for text in [spelling.to_string(), spelling.to_uppercase()] {
+                    let parsed: HotkeyBinding = format!("{}+A", text).parse().unwrap();
+                    assert_eq!(parsed.modifiers, [modifier.name], "{}", text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn modifiers_are_written_in_canonical_order_without_repeats() {
+        let parsed: HotkeyBinding = "super + shift + control + alt + ctrl + f5".parse().unwrap();
+        assert_eq!(parsed.to_string(), "Ctrl + Alt + Shift + Win + F5");
+    }
+
+    #[test]
+    fn unknown_keys_and_modifiers_are_rejected() {
+        assert!("Ctrl+Hyper+A".parse::<HotkeyBinding>().is_err());
+        assert!("Ctrl+F25".parse::<HotkeyBinding>().is_err());
+        assert!("Ctrl+".parse::<HotkeyBinding>().is_err());
+    }
+
+    #[test]
+    fn key_names_aliases_and_codes_are_unique() {
+        let mut spelled = HashSet::new();
+        let mut codes = HashSet::new();
+        let mut physical = HashSet::new();
+        for key in KEYS {
+            for spelling in spellings(key.name, key.aliases) {
+                assert!(
+                    spelled.insert(spelling.to_lowercase()),
+                    "duplicate key name {}",
+                    spelling
+                );
+            }
+            assert!(codes.insert(key.code), "duplicate code for {}", key.name);
+            assert!(
+                physical.insert(key.physical),
+                "duplicate physical code for {}",
+                key.name
+            );
+        }
+    }
+
+    #[test]
+    fn every_key_registers_a_distinct_code() {
+        // Codes global-hotkey gives the same virtual key on Windows
+        let windows_same_key = [
+            (Code::NumpadEnter, Code::Enter),
+            (Code::NumpadEqual, Code::KeyE),
+            (Code::MediaPause, Code::Pause),
+        ];
+
+        for windows in [false, true] {
+            let registrable = |code: Code| {
+                windows_same_key
+                    .iter()
+                    .find(|(other, _)| windows && *other == code)
+                    .map_or(code, |(_, same)| *same)
+            };
+
+            let mut registered = HashMap::new();
+            for key in KEYS {
+                let as_key = registered_as(key, windows);
+                assert!(KEYS.contains(as_key));
+                // Keys sharing a code must count as one, or the duplicate
+                // check lets them through and registration fails instead
+                let name = *registered
+                    .entry(registrable(key.code))
+                    .or_insert(as_key.name);
+                assert_eq!(name, as_key.name, "{} (windows: {})", key.name, windows);
+            }
+        }
+
+        assert_eq!(
+            registered_as(find_key("NumEnter").unwrap(), false).name,
+            "NumEnter"
+        );
+        assert_eq!(
+            registered_as(find_key("NumEnter").unwrap(), true).name,
+            "Enter"
+        );
+    }
+
+    #[test]
+    fn modifier_names_aliases_and_flags_are_unique() {
+        let mut spelled = HashSet::new();
+        let mut flags = Modifiers::empty();
+        for modifier in MODIFIERS {
+            for spelling in spellings(modifier.name, modifier.aliases) {
+                assert!(
+                    spelled.insert(spelling.to_lowercase()),
+                    "duplicate modifier name {}",
+                    spelling
+                );
+                // A modifier name must not also be a key name
+                assert!(find_key(spelling).is_none(), "{} is also a key", spelling);
+            }
+            assert!(!flags.intersects(modifier.flag), "duplicate flag");
+            flags |= modifier.flag;
+        }
+    }
+
+    #[test]
+    fn every_key_is_recognised_when_recorded() {
+        for key in KEYS {
+            let found = key_for_press(&Key::Unidentified, &Physical::Code(key.physical));
+            assert_eq!(found, Some(key), "{}", key.name);
+        }
+    }
+}
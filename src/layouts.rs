@@ -0,0 +1,603 @@
+//! Saved window layouts
+//!
+//! A layout records where every window was: its process, a pattern for its
+//! title, the monitor it was on by stable ID, and its bounds as fractions of
+//! that monitor's work area, so it fits again when the resolution changes.
+//! Layouts are kept one per file in the `layouts` folder next to the
+//! settings, and can be edited by hand.
+
+use crate::backend::{monitor_for_rect, WindowBackend};
+use crate::matcher::process_matches;
+use crate::placement::{PlacementPolicy, PlacementTarget};
+use crate::settings::layouts_dir;
+use crate::topology::topology_fingerprint;
+use crate::types::{MonitorInfo, WindowInfo, WindowRect};
+use crate::zones::ZoneRect;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// A named snapshot of window positions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    pub name: String,
+    /// Fingerprint of the monitor arrangement it was saved on
+    pub topology: String,
+    /// When it was saved, in seconds since the Unix epoch
+    #[serde(default)]
+    pub saved_at: u64,
+    pub windows: Vec<LayoutWindow>,
+}
+
+/// Where one window was
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutWindow {
+    pub process: String,
+    /// Title when the layout was saved
+    pub title: String,
+    /// Regex the title has to match, ignoring case
+    pub title_pattern: String,
+    /// Stable ID of the monitor the window was on
+    pub monitor: String,
+    /// Bounds as fractions of the monitor's work area
+    pub rect: ZoneRect,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub minimized: bool,
+}
+
+impl Layout {
+    /// Snapshot the windows as they are now
+    ///
+    /// Off-screen windows are left out, since they have no monitor to return to.
+    pub fn capture(name: &str, windows: &[WindowInfo], monitors: &[MonitorInfo]) -> Self {
+        let windows = windows
+            .iter()
+            .filter_map(|window| {
+                let Some(monitor) = monitor_for_rect(&window.rect, monitors) else {
+                    info!(
+                        "Leaving \"{}\" ({}) out of layout \"{}\": it is off-screen",
+                        window.title,
+                        window.process_name,
+                        name.trim()
+                    );
+                    return None;
+                };
+                Some(LayoutWindow {
+                    process: window.process_name.clone(),
+                    title: window.title.clone(),
+                    title_pattern: title_pattern(&window.title),
+                    monitor: monitor.stable_id.clone(),
+                    rect: fraction_of(&window.rect, &monitor.work_area),
+                    maximized: window.is_maximized,
+                    minimized: window.is_minimized,
+                })
+            })
+            .collect();
+
+        Self {
+            name: name.trim().to_string(),
+            topology: topology_fingerprint(monitors),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.as_secs())
+                .unwrap_or_default(),
+            windows,
+        }
+    }
+
+    /// Match open windows to the saved ones and work out where each goes
+    ///
+    /// A saved window takes an open window of the same process whose title
+    /// matches its pattern, preferring the exact title. Saved windows left
+    /// over then take any other window of their process, so a document that
+    /// was renamed still finds its window. Each window is used once.
+    pub fn plan_restore(&self, windows: &[WindowInfo], monitors: &[MonitorInfo]) -> RestorePlan {
+        let mut matches: Vec<Option<&WindowInfo>> = vec![None; self.windows.len()];
+        let mut taken = HashSet::new();
+
+        for by_title in [true, false] {
+            for (saved, slot) in self.windows.iter().zip(matches.iter_mut()) {
+                if slot.is_some() {
+                    continue;
+                }
+                let pattern = if by_title {
+                    let Some(pattern) = saved.title_regex() else {
+                        continue;
+                    };
+                    Some(pattern)
+                } else {
+                    None
+                };
+
+                let process = saved.process.to_lowercase();
+                let candidates: Vec<&WindowInfo> = windows
+                    .iter()
+                    .filter(|w| !taken.contains(&w.hwnd))
+                    .filter(|w| process_matches(&w.process_name, &process))
+                    .filter(|w| pattern.as_ref().is_none_or(|p| p.is_match(&w.title)))
+                    .collect();
+                *slot = candidates
+                    .iter()
+                    .find(|w| w.title == saved.title)
+                    .or(candidates.first())
+                    .copied();
+
+                if let Some(window) = slot {
+                    taken.insert(window.hwnd);
+                }
+            }
+        }
+
+        let mut plan = RestorePlan::default();
+        for (saved, window) in self.windows.iter().zip(matches) {
+            let Some(window) = window else {
+                plan.skipped.push(format!(
+                    "{}: {}: no open window",
+                    saved.process, saved.title
+                ));
+                continue;
+            };
+            let Some(monitor) = monitors.iter().find(|m| m.stable_id == saved.monitor) else {
+                plan.skipped.push(format!(
+                    "{}: {}: its monitor isn't connected",
+                    window.process_name, window.title
+                ));
+                continue;
+            };
+
+            let target = if saved.maximized {
+                PlacementTarget::Maximize
+            } else {
+                PlacementTarget::Zone(saved.rect)
+            };
+            plan.moves.push(LayoutMove {
+                window: window.clone(),
+                monitor: monitor.clone(),
+                policy: PlacementPolicy {
+                    target: Some(target),
+                    ..PlacementPolicy::default()
+                },
+                minimize: saved.minimized,
+            });
+        }
+        plan
+    }
+}
+
+impl LayoutWindow {
+    /// The title pattern, compiled; `None` if it isn't a valid regex
+    fn title_regex(&self) -> Option<Regex> {
+        RegexBuilder::new(&self.title_pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| {
+                warn!(
+                    "Ignoring title pattern \"{}\" for {}: {}",
+                    self.title_pattern, self.process, e
+                )
+            })
+            .ok()
+    }
+}
+
+/// What restoring a layout will do
+#[derive(Debug, Clone, Default)]
+pub struct RestorePlan {
+    pub moves: Vec<LayoutMove>,
+    /// Saved windows that stay out, and why
+    pub skipped: Vec<String>,
+}
+
+impl RestorePlan {
+    /// Carry out the moves, returning each window with how its move went
+    pub fn apply(self, backend: &dyn WindowBackend) -> Vec<(WindowInfo, Result<(), String>)> {
+        self.moves
+            .into_iter()
+            .map(|m| {
+                let result = m.apply(backend);
+                (m.window, result)
+            })
+            .collect()
+    }
+}
+
+/// A window going back to its saved spot
+#[derive(Debug, Clone)]
+pub struct LayoutMove {
+    pub window: WindowInfo,
+    pub monitor: MonitorInfo,
+    pub policy: PlacementPolicy,
+    /// Minimize the window once it is in place
+    pub minimize: bool,
+}
+
+impl LayoutMove {
+    pub fn apply(&self, backend: &dyn WindowBackend) -> Result<(), String> {
+        backend.move_window_to_monitor_with_options(
+            self.window.hwnd,
+            &self.monitor,
+            None,
+            &self.policy,
+            false,
+        )?;
+        if self.minimize {
+            backend.minimize_window(self.window.hwnd)?;
+        }
+        Ok(())
+    }
+}
+
+/// Title pattern saved for a window: the title itself, except that numbers
+/// may change, so "Inbox (3) - Outlook" still matches with new mail
+pub fn title_pattern(title: &str) -> String {
+    let mut pattern = String::from("^");
+    let mut literal = String::new();
+    let mut chars = title.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            while chars.next_if(char::is_ascii_digit).is_some() {}
+            pattern.push_str(&regex::escape(&std::mem::take(&mut literal)));
+            pattern.push_str(r"\d+");
+        } else {
+            literal.push(c);
+        }
+    }
+    pattern.push_str(&regex::escape(&literal));
+    pattern.push('$');
+    pattern
+}
+
+/// A rect as fractions of a work area
+fn fraction_of(rect: &WindowRect, area: &WindowRect) -> ZoneRect {
+    let width = area.width().max(1) as f64;
+    let height = area.height().max(1) as f64;
+    ZoneRect::new(
+        (rect.left - area.left) as f64 / width,
+        (rect.top - area.top) as f64 / height,
+        rect.width() as f64 / width,
+        rect.height() as f64 / height,
+    )
+}
+
+/// Names Windows reserves for devices, with any extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// File a layout is stored in
+fn layout_path(name: &str) -> Result<PathBuf, String> {
+    let file_name = layout_file_name(name)?;
+    let dir = layouts_dir().ok_or("Could not determine config directory")?;
+    Ok(dir.join(file_name))
+}
+
+/// File name for a layout
+///
+/// Characters that can't be used in file names are replaced, so names that
+/// differ only in those share a file. Device names such as "CON" get a `_`
+/// appended, on every platform so the folder can be copied between them.
+fn layout_file_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A layout needs a name".to_string());
+    }
+    let mut file_name: String = name
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') => c,
+            _ => '_',
+        })
+        .collect();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(&file_name))
+    {
+        file_name.push('_');
+    }
+
+    Ok(format!("{}.json", file_name))
+}
+
+/// Save a layout, replacing any layout with the same name
+pub fn save_layout(layout: &Layout) -> Result<(), String> {
+    let path = layout_path(&layout.name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create layouts directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(layout)
+        .map_err(|e| format!("Failed to serialize layout: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write layout: {}", e))
+}
+
+/// Load a saved layout by name
+pub fn load_layout(name: &str) -> Result<Layout, String> {
+    let path = layout_path(name)?;
+    let content = fs::read_to_string(&path).map_err(|_| format!("No layout named \"{}\"", name))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse layout {}: {}", path.display(), e))
+}
+
+/// Delete a saved layout
+pub fn delete_layout(name: &str) -> Result<(), String> {
+    fs::remove_file(layout_path(name)?).map_err(|_| format!("No layout named \"{}\"", name))
+}
+
+/// Every saved layout, sorted by name
+pub fn list_layouts() -> Vec<Layout> {
+    let Some(entries) = layouts_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut layouts: Vec<Layout> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&content)
+                .map_err(|e| warn!("Skipping layout {}: {}", path.display(), e))
+                .ok()
+        })
+        .collect();
+    layouts.sort_by_key(|layout: &Layout| layout.name.to_lowercase());
+    layouts
+}
+
+/// The most recently saved layout for a monitor arrangement
+pub fn layout_for_topology<'a>(layouts: &'a [Layout], topology: &str) -> Option<&'a Layout> {
+    layouts
+        .iter()
+        .filter(|layout| layout.topology == topology)
+        .max_by_key(|layout| layout.saved_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::{monitor, rect, window};
+
+    /// Primary monitor with a taskbar, and a larger one to its right
+    fn desk() -> Vec<MonitorInfo> {
+        let mut primary = monitor(0, rect(0, 0, 1920, 1080), 96);
+        primary.work_area.bottom = 1040;
+        vec![primary, monitor(1, rect(1920, 0, 4480, 1440), 144)]
+    }
+
+    /// The windows a layout was saved with, half on each monitor
+    fn saved_windows() -> Vec<WindowInfo> {
+        let left = rect(100, 100, 900, 700);
+        let right = rect(2000, 200, 3200, 1200);
+        vec![
+            window(1, "chrome.exe", "GitHub - Chrome", left),
+            window(2, "chrome.exe", "Docs - Chrome", right),
+            window(3, "chrome.exe", "Mail - Chrome", right),
+            window(4, "WINWORD.EXE", "Report.docx - Word", left),
+            window(5, "WINWORD.EXE", "Notes.docx - Word", right),
+            window(6, "OUTLOOK.EXE", "Inbox (3) - Outlook", left),
+            window(7, "EXCEL.EXE", "Budget.xlsx - Excel", right),
+            window(8, "Teams.exe", "Teams", left),
+            window(9, "explorer.exe", "Downloads", left),
+            window(10, "explorer.exe", "Documents", right),
+            window(11, "Code.exe", "main.rs - crate - Code", right),
+            window(12, "slack.exe", "Slack - general", left),
+        ]
+    }
+
+    #[test]
+    fn numbers_in_titles_may_change() {
+        let pattern = title_pattern("Inbox (3) - Outlook");
+        assert_eq!(pattern, r"^Inbox \(\d+\) \- Outlook$");
+
+        let regex = Regex::new(&pattern).unwrap();
+        assert!(regex.is_match("Inbox (3) - Outlook"));
+        assert!(regex.is_match("Inbox (127) - Outlook"));
+        assert!(!regex.is_match("Inbox - Outlook"));
+        assert!(!regex.is_match("Sent (3) - Outlook"));
+    }
+
+    #[test]
+    fn title_patterns_escape_the_rest() {
+        assert_eq!(title_pattern(""), "^$");
+        assert_eq!(title_pattern("v1.20.3"), r"^v\d+\.\d+\.\d+$");
+        assert_eq!(title_pattern("a+b [x] *"), r"^a\+b \[x\] \*$");
+        assert!(Regex::new(&title_pattern("C:\\Users\\me (admin)?"))
+            .unwrap()
+            .is_match("C:\\Users\\me (admin)?"));
+    }
+
+    #[test]
+    fn rects_are_fractions_of_the_work_area() {
+        let area = rect(1920, 40, 4480, 1480);
+        assert_eq!(
+            fraction_of(&rect(1920, 40, 3200, 760), &area),
+            ZoneRect::new(0.0, 0.0, 0.5, 0.5)
+        );
+        assert_eq!(
+            fraction_of(&rect(2560, 400, 4480, 1480), &area),
+            ZoneRect::new(0.25, 0.25, 0.75, 0.75)
+        );
+        // Sticking out past the work area stays outside 0..1
+        assert_eq!(
+            fraction_of(&rect(1664, 40, 2176, 400), &area),
+            ZoneRect::new(-0.1, 0.0, 0.2, 0.25)
+        );
+        // An empty work area doesn't divide by zero
+        let empty = rect(0, 0, 0, 0);
+        assert!(fraction_of(&rect(0, 0, 10, 10), &empty).width.is_finite());
+    }
+
+    #[test]
+    fn fractions_fit_again_at_another_resolution() {
+        let saved = fraction_of(&rect(2000, 200, 3200, 1200), &rect(1920, 0, 4480, 1440));
+        let placed = saved.resolve(&rect(1920, 0, 5760, 2160));
+        assert_eq!(placed, rect(2040, 300, 3840, 1800));
+    }
+
+    #[test]
+    fn capture_leaves_off_screen_windows_out() {
+        let mut windows = saved_windows();
+        windows[0].rect = rect(-2400, 100, -1600, 700);
+        windows[5].is_maximized = true;
+        windows[6].is_minimized = true;
+
+        let layout = Layout::capture("  Work  ", &windows, &desk());
+        assert_eq!(layout.name, "Work");
+        assert_eq!(layout.windows.len(), 11);
+        assert!(layout.windows.iter().all(|w| w.title != "GitHub - Chrome"));
+
+        let outlook = &layout.windows[4];
+        assert_eq!(outlook.monitor, "MON1");
+        assert!(outlook.maximized && !outlook.minimized);
+        assert_eq!(
+            outlook.rect,
+            fraction_of(&windows[5].rect, &desk()[0].work_area)
+        );
+        assert_eq!(layout.windows[5].monitor, "MON2");
+        assert!(layout.windows[5].minimized);
+    }
+
+    #[test]
+    fn restore_finds_renamed_and_duplicate_windows() {
+        let layout = Layout::capture("Work", &saved_windows(), &desk());
+
+        // Reopened in another order with new handles; the report was
+        // renamed, mail arrived, another file is open in the editor, and
+        // Slack wasn't started
+        let mut open: Vec<WindowInfo> = saved_windows()
+            .into_iter()
+            .filter(|w| w.process_name != "slack.exe")
+            .map(|w| WindowInfo {
+                hwnd: w.hwnd + 100,
+                rect: rect(0, 0, 640, 480),
+                ..w
+            })
+            .rev()
+            .collect();
+        for window in &mut open {
+            window.title = match window.title.as_str() {
+                "Report.docx - Word" => "Report final.docx - Word",
+                "Inbox (3) - Outlook" => "Inbox (12) - Outlook",
+                "Docs - Chrome" => "Sheets - Chrome",
+                "main.rs - crate - Code" => "lib.rs - crate - Code",
+                title => title,
+            }
+            .to_string();
+        }
+        open.push(window(
+            200,
+            "notepad.exe",
+            "Untitled - Notepad",
+            rect(0, 0, 640, 480),
+        ));
+
+        let plan = layout.plan_restore(&open, &desk());
+        assert_eq!(plan.moves.len(), 11);
+        assert_eq!(
+            plan.skipped,
+            vec!["slack.exe: Slack - general: no open window"]
+        );
+
+        // Every saved window found the reopened copy of itself
+        let hwnds: Vec<isize> = plan.moves.iter().map(|m| m.window.hwnd).collect();
+        assert_eq!(hwnds, (101..=111).collect::<Vec<_>>());
+
+        // Each window goes back to its own monitor, at its saved spot
+        for m in &plan.moves {
+            let saved = &saved_windows()[(m.window.hwnd - 101) as usize];
+            let expected = monitor_for_rect(&saved.rect, &desk())
+                .unwrap()
+                .stable_id
+                .clone();
+            assert_eq!(m.monitor.stable_id, expected);
+            let Some(PlacementTarget::Zone(zone)) = m.policy.target else {
+                panic!("{} isn't placed in a zone", saved.title);
+            };
+            assert_eq!(zone.resolve(&m.monitor.work_area), saved.rect);
+        }
+    }
+
+    #[test]
+    fn restore_prefers_the_exact_title() {
+        let windows = [
+            window(
+                1,
+                "OUTLOOK.EXE",
+                "Inbox (3) - Outlook",
+                rect(0, 0, 800, 600),
+            ),
+            window(
+                2,
+                "OUTLOOK.EXE",
+                "Inbox (5) - Outlook",
+                rect(0, 0, 800, 600),
+            ),
+        ];
+        let layout = Layout::capture("Mail", &windows[1..], &desk());
+
+        let plan = layout.plan_restore(&windows, &desk());
+        assert_eq!(plan.moves.len(), 1);
+        assert_eq!(plan.moves[0].window.hwnd, 2);
+    }
+
+    #[test]
+    fn restore_skips_windows_whose_monitor_is_gone() {
+        let layout = Layout::capture("Work", &saved_windows(), &desk());
+        let plan = layout.plan_restore(&saved_windows(), &desk()[..1]);
+
+        assert_eq!(plan.moves.len(), 6);
+        assert!(plan.moves.iter().all(|m| m.monitor.stable_id == "MON1"));
+        assert_eq!(plan.skipped.len(), 6);
+        assert_eq!(
+            plan.skipped[0],
+            "chrome.exe: Docs - Chrome: its monitor isn't connected"
+        );
+    }
+
+    #[test]
+    fn restore_keeps_window_states() {
+        let mut windows = saved_windows();
+        windows[5].is_maximized = true;
+        windows[6].is_minimized = true;
+        let layout = Layout::capture("Work", &windows, &desk());
+
+        let plan = layout.plan_restore(&saved_windows(), &desk());
+        let outlook = &plan.moves[5];
+        assert_eq!(outlook.policy.target, Some(PlacementTarget::Maximize));
+        assert!(!outlook.minimize);
+        assert!(plan.moves[6].minimize);
+    }
+
+    #[test]
+    fn device_names_get_their_own_file_names() {
+        assert_eq!(layout_file_name("CON"), Ok("CON_.json".to_string()));
+        assert_eq!(layout_file_name(" nul "), Ok("nul_.json".to_string()));
+        assert_eq!(layout_file_name("Com1"), Ok("Com1_.json".to_string()));
+        assert_eq!(layout_file_name("lpt9"), Ok("lpt9_.json".to_string()));
+        assert_eq!(layout_file_name("Console"), Ok("Console.json".to_string()));
+        assert_eq!(layout_file_name("COM10"), Ok("COM10.json".to_string()));
+        assert_eq!(
+            layout_file_name("con.json"),
+            Ok("con_json.json".to_string())
+        );
+    }
+
+    #[test]
+    fn file_names_replace_unsafe_characters() {
+        assert_eq!(
+            layout_file_name("Home / Work"),
+            Ok("Home _ Work.json".to_string())
+        );
+        assert_eq!(layout_file_name("..\\evil"), Ok("___evil.json".to_string()));
+        assert_eq!(layout_file_name("Café 2"), Ok("Café 2.json".to_string()));
+        assert!(layout_file_name("   ").is_err());
+    }
+}
@@ -20,6 +20,9 @@ tooltip-lasso = Dieses Fenster auf einen anderen Monitor verschieben
 tooltip-refresh = Fensterliste aktualisieren
 tooltip-settings = Einstellungen öffnen
 tooltip-back = Zurück
+tooltip-rename-monitor = Diesen Monitor umbenennen
+tooltip-add-hotkey = Tastenkombination hinzufügen
+tooltip-remove-hotkey = Diese Tastenkombination entfernen
 
 # Window list
 windows-title = Offene Fenster
@@ -30,12 +33,22 @@ windows-count = { $count ->
     [one] { $count } Fenster
    *[other] { $count } Fenster
 }
+windows-filter = Filtern, z. B. process:chrome title~"Meet" offscreen
+windows-filter-invalid = Ungültiger Filter: { $error }
+
+# Layouts
+layouts-name = Name des Layouts
+layouts-saved = Gespeicherte Layouts
+layouts-restore = Wiederherstellen
+layouts-delete = Löschen
 
 # Monitor picker
 monitor-title = Ziel Auswählen
 monitor-select = Wählen Sie einen Monitor, um das Fenster zu verschieben
 monitor-primary = Primär
 monitor-resolution = { $width } × { $height }
+monitor-scale = { $percent } % Skalierung ({ $dpi } DPI)
+monitor-map-hint = Auf einen Monitor klicken oder ziehen, um das Fenster zu platzieren. Ränder rasten auf Hälften und Viertel ein; der obere Rand maximiert.
 
 # Settings
 settings-title = Einstellungen
@@ -43,8 +56,39 @@ settings-language = Sprache
 settings-behavior = Verhalten
 settings-auto-focus = Fenster nach dem Einfangen fokussieren
 settings-close-after-recovery = App schließen wenn alle Fenster wiederhergestellt
+settings-auto-rescue = Fenster außerhalb des Bildschirms automatisch zurückholen
+settings-api = Lokale Automatisierung erlauben (JSON-RPC-API)
 settings-hotkeys = Tastenkombinationen
 settings-tray = In Taskleiste minimieren
+settings-placement = Fensterplatzierung
+settings-scaling = Fensterskalierung
+settings-monitors-changed = Wenn sich Monitore ändern
+
+# Monitor change actions
+monitors-changed-nothing = Nichts tun
+monitors-changed-rescue = Fenster außerhalb des Bildschirms zurückholen
+monitors-changed-restore-layout = Für diese Monitore gespeichertes Layout wiederherstellen
+
+# Placement modes
+placement-center = Auf Monitor zentrieren
+placement-relative = Relative Position beibehalten
+
+# Scaling modes
+scaling-resolution = An Auflösung anpassen
+scaling-physical = Physische Größe beibehalten
+
+# Snap zones
+zone-maximize = Maximieren
+zone-center = Mitte
+zone-left-half = Linke Hälfte
+zone-right-half = Rechte Hälfte
+zone-left-third = Linkes Drittel
+zone-center-third = Mittleres Drittel
+zone-right-third = Rechtes Drittel
+zone-top-left = Viertel Oben Links
+zone-top-right = Viertel Oben Rechts
+zone-bottom-left = Viertel Unten Links
+zone-bottom-right = Viertel Unten Rechts
 
 # Hotkeys
 hotkey-lasso = Fenster Einfangen
@@ -53,8 +97,22 @@ hotkey-primary = Zum Primären Verschieben
 hotkey-all-primary = Alle zum Primären
 hotkey-center = Fenster Zentrieren
 hotkey-next-monitor = Nächster Monitor
+hotkey-previous-monitor = Vorheriger Monitor
+hotkey-leader = Leader-Taste
+hotkey-leader-hint = Taste drücken oder Esc zum Abbrechen
+hotkey-send-to-monitor = Auf Monitor { $number } verschieben
+hotkey-send-to-named = Auf { $name } verschieben
+hotkey-monitor-left = Monitor Links
+hotkey-monitor-right = Monitor Rechts
+hotkey-monitor-up = Monitor Oben
+hotkey-monitor-down = Monitor Unten
+hotkey-zones = Andockzonen
 hotkey-edit = Zum Ändern klicken
 hotkey-press = Neue Tastenkombination drücken...
+hotkey-not-set = Nicht festgelegt
+hotkey-conflict-duplicate = Bereits belegt durch { $action }
+hotkey-conflict-unsupported = Diese Taste kann nicht als globale Tastenkombination verwendet werden
+hotkey-conflict-unavailable = Von einer anderen Anwendung belegt
 hotkey-enabled = Aktiviert
 
 # Dialogs
@@ -66,3 +124,5 @@ dialog-tray-remember = Meine Wahl merken
 status-moved = Fenster erfolgreich verschoben
 status-error = Fehler: { $message }
 status-refreshed = Fensterliste aktualisiert
+status-layout-saved = Layout „{ $name }“ gespeichert
+status-layout-restored = „{ $name }“ wiederhergestellt: { $moved } von { $total } Fenstern verschoben
@@ -20,6 +20,9 @@ tooltip-lasso = Lasso this window to another monitor
 tooltip-refresh = Refresh window list
 tooltip-settings = Open settings
 tooltip-back = Go back
+tooltip-rename-monitor = Rename this monitor
+tooltip-add-hotkey = Add a shortcut
+tooltip-remove-hotkey = Remove this shortcut
 
 # Window list
 windows-title = Open Windows
@@ -30,12 +33,22 @@ windows-count = { $count ->
     [one] { $count } window
    *[other] { $count } windows
 }
+windows-filter = Filter, e.g. process:chrome title~"Meet" offscreen
+windows-filter-invalid = Invalid filter: { $error }
+
+# Layouts
+layouts-name = Layout name
+layouts-saved = Saved layouts
+layouts-restore = Restore
+layouts-delete = Delete
 
 # Monitor picker
 monitor-title = Select Destination
 monitor-select = Choose a monitor to move the window to
 monitor-primary = Primary
 monitor-resolution = { $width } × { $height }
+monitor-scale = { $percent }% scale ({ $dpi } DPI)
+monitor-map-hint = Click or drag onto a monitor to place the window. Edges snap to halves and quarters; the top edge maximizes.
 
 # Settings
 settings-title = Settings
@@ -43,8 +56,39 @@ settings-language = Language
 settings-behavior = Behavior
 settings-auto-focus = Focus window after lasso
 settings-close-after-recovery = Close app when all windows recovered
+settings-auto-rescue = Rescue off-screen windows automatically
+settings-api = Allow local automation (JSON-RPC API)
 settings-hotkeys = Keyboard Shortcuts
 settings-tray = Minimize to system tray
+settings-placement = Window placement
+settings-scaling = Window scaling
+settings-monitors-changed = When monitors change
+
+# Monitor change actions
+monitors-changed-nothing = Do nothing
+monitors-changed-rescue = Rescue off-screen windows
+monitors-changed-restore-layout = Restore the layout saved for these monitors
+
+# Placement modes
+placement-center = Center on monitor
+placement-relative = Keep relative position
+
+# Scaling modes
+scaling-resolution = Match resolution
+scaling-physical = Keep physical size
+
+# Snap zones
+zone-maximize = Maximize
+zone-center = Center
+zone-left-half = Left Half
+zone-right-half = Right Half
+zone-left-third = Left Third
+zone-center-third = Center Third
+zone-right-third = Right Third
+zone-top-left = Top Left Quarter
+zone-top-right = Top Right Quarter
+zone-bottom-left = Bottom Left Quarter
+zone-bottom-right = Bottom Right Quarter
 
 # Hotkeys
 hotkey-lasso = Lasso Window
@@ -53,8 +97,22 @@ hotkey-primary = Move to Primary
 hotkey-all-primary = Move All to Primary
 hotkey-center = Center Window
 hotkey-next-monitor = Next Monitor
+hotkey-previous-monitor = Previous Monitor
+hotkey-leader = Leader Key
+hotkey-leader-hint = Press a key, or Esc to cancel
+hotkey-send-to-monitor = Move to Monitor { $number }
+hotkey-send-to-named = Move to { $name }
+hotkey-monitor-left = Monitor to the Left
+hotkey-monitor-right = Monitor to the Right
+hotkey-monitor-up = Monitor Above
+hotkey-monitor-down = Monitor Below
+hotkey-zones = Snap Zones
 hotkey-edit = Click to change
 hotkey-press = Press new shortcut...
+hotkey-not-set = Not set
+hotkey-conflict-duplicate = Already used by { $action }
+hotkey-conflict-unsupported = This key can't be used as a global shortcut
+hotkey-conflict-unavailable = In use by another application
 hotkey-enabled = Enabled
 
 # Dialogs
@@ -66,3 +124,5 @@ dialog-tray-remember = Remember my choice
 status-moved = Window moved successfully
 status-error = Error: { $message }
 status-refreshed = Window list refreshed
+status-layout-saved = Saved layout "{ $name }"
+status-layout-restored = Restored "{ $name }": { $moved } of { $total } windows moved
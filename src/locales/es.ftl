@@ -20,6 +20,9 @@ tooltip-lasso = Capturar esta ventana a otro monitor
 tooltip-refresh = Actualizar lista de ventanas
 tooltip-settings = Abrir configuración
 tooltip-back = Volver
+tooltip-rename-monitor = Renombrar este monitor
+tooltip-add-hotkey = Añadir un atajo
+tooltip-remove-hotkey = Quitar este atajo
 
 # Window list
 windows-title = Ventanas Abiertas
@@ -30,12 +33,22 @@ windows-count = { $count ->
     [one] { $count } ventana
    *[other] { $count } ventanas
 }
+windows-filter = Filtrar, p. ej. process:chrome title~"Meet" offscreen
+windows-filter-invalid = Filtro no válido: { $error }
+
+# Layouts
+layouts-name = Nombre del diseño
+layouts-saved = Diseños guardados
+layouts-restore = Restaurar
+layouts-delete = Eliminar
 
 # Monitor picker
 monitor-title = Seleccionar Destino
 monitor-select = Elige un monitor para mover la ventana
 monitor-primary = Principal
 monitor-resolution = { $width } × { $height }
+monitor-scale = Escala { $percent }% ({ $dpi } PPP)
+monitor-map-hint = Haz clic o arrastra sobre un monitor para colocar la ventana. Los bordes ajustan a mitades y cuartos; el borde superior maximiza.
 
 # Settings
 settings-title = Configuración
@@ -43,8 +56,39 @@ settings-language = Idioma
 settings-behavior = Comportamiento
 settings-auto-focus = Enfocar ventana después de capturar
 settings-close-after-recovery = Cerrar app cuando todas las ventanas recuperadas
+settings-auto-rescue = Recuperar automáticamente las ventanas fuera de pantalla
+settings-api = Permitir automatización local (API JSON-RPC)
 settings-hotkeys = Atajos de Teclado
 settings-tray = Minimizar a la bandeja del sistema
+settings-placement = Posición de la ventana
+settings-scaling = Escalado de ventanas
+settings-monitors-changed = Al cambiar los monitores
+
+# Monitor change actions
+monitors-changed-nothing = No hacer nada
+monitors-changed-rescue = Recuperar ventanas fuera de pantalla
+monitors-changed-restore-layout = Restaurar el diseño guardado para estos monitores
+
+# Placement modes
+placement-center = Centrar en el monitor
+placement-relative = Mantener posición relativa
+
+# Scaling modes
+scaling-resolution = Ajustar a la resolución
+scaling-physical = Mantener tamaño físico
+
+# Snap zones
+zone-maximize = Maximizar
+zone-center = Centro
+zone-left-half = Mitad Izquierda
+zone-right-half = Mitad Derecha
+zone-left-third = Tercio Izquierdo
+zone-center-third = Tercio Central
+zone-right-third = Tercio Derecho
+zone-top-left = Cuarto Superior Izquierdo
+zone-top-right = Cuarto Superior Derecho
+zone-bottom-left = Cuarto Inferior Izquierdo
+zone-bottom-right = Cuarto Inferior Derecho
 
 # Hotkeys
 hotkey-lasso = Capturar Ventana
@@ -53,8 +97,22 @@ hotkey-primary = Mover a Principal
 hotkey-all-primary = Mover Todas a Principal
 hotkey-center = Centrar Ventana
 hotkey-next-monitor = Siguiente Monitor
+hotkey-previous-monitor = Monitor Anterior
+hotkey-leader = Tecla Líder
+hotkey-leader-hint = Pulsa una tecla o Esc para cancelar
+hotkey-send-to-monitor = Mover al Monitor { $number }
+hotkey-send-to-named = Mover a { $name }
+hotkey-monitor-left = Monitor a la Izquierda
+hotkey-monitor-right = Monitor a la Derecha
+hotkey-monitor-up = Monitor Superior
+hotkey-monitor-down = Monitor Inferior
+hotkey-zones = Zonas de Ajuste
 hotkey-edit = Clic para cambiar
 hotkey-press = Presiona el nuevo atajo...
+hotkey-not-set = Sin asignar
+hotkey-conflict-duplicate = Ya lo usa { $action }
+hotkey-conflict-unsupported = Esta tecla no se puede usar como atajo global
+hotkey-conflict-unavailable = En uso por otra aplicación
 hotkey-enabled = Activado
 
 # Dialogs
@@ -66,3 +124,5 @@ dialog-tray-remember = Recordar mi elección
 status-moved = Ventana movida correctamente
 status-error = Error: { $message }
 status-refreshed = Lista de ventanas actualizada
+status-layout-saved = Diseño "{ $name }" guardado
+status-layout-restored = "{ $name }" restaurado: { $moved } de { $total } ventanas movidas
@@ -20,6 +20,9 @@ tooltip-lasso = Déplacer cette fenêtre vers un autre moniteur
 tooltip-refresh = Actualiser la liste des fenêtres
 tooltip-settings = Ouvrir les paramètres
 tooltip-back = Retour
+tooltip-rename-monitor = Renommer ce moniteur
+tooltip-add-hotkey = Ajouter un raccourci
+tooltip-remove-hotkey = Supprimer ce raccourci
 
 # Window list
 windows-title = Fenêtres Ouvertes
@@ -30,12 +33,22 @@ windows-count = { $count ->
     [one] { $count } fenêtre
    *[other] { $count } fenêtres
 }
+windows-filter = Filtrer, p. ex. process:chrome title~"Meet" offscreen
+windows-filter-invalid = Filtre invalide : { $error }
+
+# Layouts
+layouts-name = Nom de la disposition
+layouts-saved = Dispositions enregistrées
+layouts-restore = Restaurer
+layouts-delete = Supprimer
 
 # Monitor picker
 monitor-title = Sélectionner la Destination
 monitor-select = Choisissez un moniteur pour déplacer la fenêtre
 monitor-primary = Principal
 monitor-resolution = { $width } × { $height }
+monitor-scale = Échelle { $percent } % ({ $dpi } PPP)
+monitor-map-hint = Cliquez ou faites glisser sur un moniteur pour placer la fenêtre. Les bords l’ancrent en moitiés et quarts ; le bord supérieur maximise.
 
 # Settings
 settings-title = Paramètres
@@ -43,8 +56,39 @@ settings-language = Langue
 settings-behavior = Comportement
 settings-auto-focus = Activer la fenêtre après capture
 settings-close-after-recovery = Fermer l'app quand toutes les fenêtres récupérées
+settings-auto-rescue = Récupérer automatiquement les fenêtres hors écran
+settings-api = Autoriser l'automatisation locale (API JSON-RPC)
 settings-hotkeys = Raccourcis Clavier
 settings-tray = Réduire dans la barre des tâches
+settings-placement = Placement des fenêtres
+settings-scaling = Mise à l'échelle des fenêtres
+settings-monitors-changed = Quand les écrans changent
+
+# Monitor change actions
+monitors-changed-nothing = Ne rien faire
+monitors-changed-rescue = Récupérer les fenêtres hors écran
+monitors-changed-restore-layout = Restaurer la disposition enregistrée pour ces écrans
+
+# Placement modes
+placement-center = Centrer sur l'écran
+placement-relative = Conserver la position relative
+
+# Scaling modes
+scaling-resolution = Adapter à la résolution
+scaling-physical = Conserver la taille physique
+
+# Snap zones
+zone-maximize = Maximiser
+zone-center = Centre
+zone-left-half = Moitié Gauche
+zone-right-half = Moitié Droite
+zone-left-third = Tiers Gauche
+zone-center-third = Tiers Central
+zone-right-third = Tiers Droit
+zone-top-left = Quart Supérieur Gauche
+zone-top-right = Quart Supérieur Droit
+zone-bottom-left = Quart Inférieur Gauche
+zone-bottom-right = Quart Inférieur Droit
 
 # Hotkeys
 hotkey-lasso = Attraper Fenêtre
@@ -53,8 +97,22 @@ hotkey-primary = Déplacer vers Principal
 hotkey-all-primary = Déplacer Toutes vers Principal
 hotkey-center = Centrer Fenêtre
 hotkey-next-monitor = Moniteur Suivant
+hotkey-previous-monitor = Moniteur Précédent
+hotkey-leader = Touche Meneuse
+hotkey-leader-hint = Appuyez sur une touche, ou Échap pour annuler
+hotkey-send-to-monitor = Déplacer vers le Moniteur { $number }
+hotkey-send-to-named = Déplacer vers { $name }
+hotkey-monitor-left = Moniteur à Gauche
+hotkey-monitor-right = Moniteur à Droite
+hotkey-monitor-up = Moniteur au-Dessus
+hotkey-monitor-down = Moniteur en Dessous
+hotkey-zones = Zones d'Ancrage
 hotkey-edit = Cliquez pour modifier
 hotkey-press = Appuyez sur le nouveau raccourci...
+hotkey-not-set = Non défini
+hotkey-conflict-duplicate = Déjà utilisé par { $action }
+hotkey-conflict-unsupported = Cette touche ne peut pas servir de raccourci global
+hotkey-conflict-unavailable = Utilisé par une autre application
 hotkey-enabled = Activé
 
 # Dialogs
@@ -66,3 +124,5 @@ dialog-tray-remember = Se souvenir de mon choix
 status-moved = Fenêtre déplacée avec succès
 status-error = Erreur: { $message }
 status-refreshed = Liste des fenêtres actualisée
+status-layout-saved = Disposition « { $name } » enregistrée
+status-layout-restored = « { $name } » restaurée : { $moved } fenêtres déplacées sur { $total }
@@ -20,6 +20,9 @@ tooltip-lasso = このウィンドウを別のモニターに移動
 tooltip-refresh = ウィンドウリストを更新
 tooltip-settings = 設定を開く
 tooltip-back = 戻る
+tooltip-rename-monitor = このモニターの名前を変更
+tooltip-add-hotkey = ショートカットを追加
+tooltip-remove-hotkey = このショートカットを削除
 
 # Window list
 windows-title = 開いているウィンドウ
@@ -27,12 +30,22 @@ windows-empty = ウィンドウが見つかりません
 windows-offscreen = 画面外
 windows-minimized = 最小化
 windows-count = { $count } ウィンドウ
+windows-filter = フィルター（例: process:chrome title~"Meet" offscreen）
+windows-filter-invalid = 無効なフィルター: { $error }
+
+# Layouts
+layouts-name = レイアウト名
+layouts-saved = 保存済みレイアウト
+layouts-restore = 復元
+layouts-delete = 削除
 
 # Monitor picker
 monitor-title = 移動先を選択
 monitor-select = ウィンドウを移動するモニターを選択してください
 monitor-primary = プライマリ
 monitor-resolution = { $width } × { $height }
+monitor-scale = 拡大率 { $percent }% ({ $dpi } DPI)
+monitor-map-hint = モニターをクリックまたはドラッグしてウィンドウを配置します。端では半分・4分の1にスナップし、上端では最大化します。
 
 # Settings
 settings-title = 設定
@@ -40,8 +53,39 @@ settings-language = 言語
 settings-behavior = 動作
 settings-auto-focus = キャッチ後にウィンドウをフォーカス
 settings-close-after-recovery = 全てのウィンドウ回復後にアプリを閉じる
+settings-auto-rescue = 画面外のウィンドウを自動的に回復
+settings-api = ローカル自動化を許可 (JSON-RPC API)
 settings-hotkeys = キーボードショートカット
 settings-tray = システムトレイに最小化
+settings-placement = ウィンドウの配置
+settings-scaling = ウィンドウのスケーリング
+settings-monitors-changed = モニター構成の変更時
+
+# Monitor change actions
+monitors-changed-nothing = 何もしない
+monitors-changed-rescue = 画面外のウィンドウを回復
+monitors-changed-restore-layout = このモニター構成で保存したレイアウトを復元
+
+# Placement modes
+placement-center = モニターの中央に配置
+placement-relative = 相対位置を維持
+
+# Scaling modes
+scaling-resolution = 解像度に合わせる
+scaling-physical = 物理サイズを維持
+
+# Snap zones
+zone-maximize = 最大化
+zone-center = 中央
+zone-left-half = 左半分
+zone-right-half = 右半分
+zone-left-third = 左3分の1
+zone-center-third = 中央3分の1
+zone-right-third = 右3分の1
+zone-top-left = 左上4分の1
+zone-top-right = 右上4分の1
+zone-bottom-left = 左下4分の1
+zone-bottom-right = 右下4分の1
 
 # Hotkeys
 hotkey-lasso = ウィンドウをキャッチ
@@ -50,8 +94,22 @@ hotkey-primary = プライマリに移動
 hotkey-all-primary = 全てをプライマリに
 hotkey-center = ウィンドウを中央に
 hotkey-next-monitor = 次のモニター
+hotkey-previous-monitor = 前のモニター
+hotkey-leader = リーダーキー
+hotkey-leader-hint = キーを押してください（Esc でキャンセル）
+hotkey-send-to-monitor = モニター { $number } へ移動
+hotkey-send-to-named = { $name } へ移動
+hotkey-monitor-left = 左のモニター
+hotkey-monitor-right = 右のモニター
+hotkey-monitor-up = 上のモニター
+hotkey-monitor-down = 下のモニター
+hotkey-zones = スナップゾーン
 hotkey-edit = クリックして変更
 hotkey-press = 新しいショートカットを押してください...
+hotkey-not-set = 未設定
+hotkey-conflict-duplicate = { $action } で使用中
+hotkey-conflict-unsupported = このキーはグローバルショートカットに使用できません
+hotkey-conflict-unavailable = 別のアプリケーションが使用中
 hotkey-enabled = 有効
 
 # Dialogs
@@ -63,3 +121,5 @@ dialog-tray-remember = 選択を記憶する
 status-moved = ウィンドウを移動しました
 status-error = エラー: { $message }
 status-refreshed = ウィンドウリストを更新しました
+status-layout-saved = レイアウト「{ $name }」を保存しました
+status-layout-restored = 「{ $name }」を復元しました: { $total } 個中 { $moved } 個のウィンドウを移動
@@ -20,6 +20,9 @@ tooltip-lasso = 将此窗口移动到其他显示器
 tooltip-refresh = 刷新窗口列表
 tooltip-settings = 打开设置
 tooltip-back = 返回
+tooltip-rename-monitor = 重命名此显示器
+tooltip-add-hotkey = 添加快捷键
+tooltip-remove-hotkey = 删除此快捷键
 
 # Window list
 windows-title = 打开的窗口
@@ -27,12 +30,22 @@ windows-empty = 未找到窗口
 windows-offscreen = 屏幕外
 windows-minimized = 已最小化
 windows-count = { $count } 个窗口
+windows-filter = 筛选，例如 process:chrome title~"Meet" offscreen
+windows-filter-invalid = 无效的筛选条件：{ $error }
+
+# Layouts
+layouts-name = 布局名称
+layouts-saved = 已保存的布局
+layouts-restore = 恢复
+layouts-delete = 删除
 
 # Monitor picker
 monitor-title = 选择目标
 monitor-select = 选择要将窗口移动到的显示器
 monitor-primary = 主显示器
 monitor-resolution = { $width } × { $height }
+monitor-scale = 缩放 { $percent }% ({ $dpi } DPI)
+monitor-map-hint = 单击或拖动到显示器上以放置窗口。边缘吸附为二分之一或四分之一屏幕；顶部边缘最大化。
 
 # Settings
 settings-title = 设置
@@ -40,8 +53,39 @@ settings-language = 语言
 settings-behavior = 行为
 settings-auto-focus = 捕获后聚焦窗口
 settings-close-after-recovery = 所有窗口恢复后关闭应用
+settings-auto-rescue = 自动恢复屏幕外的窗口
+settings-api = 允许本地自动化 (JSON-RPC API)
 settings-hotkeys = 快捷键
 settings-tray = 最小化到系统托盘
+settings-placement = 窗口位置
+settings-scaling = 窗口缩放
+settings-monitors-changed = 显示器变化时
+
+# Monitor change actions
+monitors-changed-nothing = 不执行任何操作
+monitors-changed-rescue = 恢复屏幕外的窗口
+monitors-changed-restore-layout = 恢复为这些显示器保存的布局
+
+# Placement modes
+placement-center = 在显示器上居中
+placement-relative = 保持相对位置
+
+# Scaling modes
+scaling-resolution = 匹配分辨率
+scaling-physical = 保持物理尺寸
+
+# Snap zones
+zone-maximize = 最大化
+zone-center = 居中
+zone-left-half = 左半屏
+zone-right-half = 右半屏
+zone-left-third = 左三分之一
+zone-center-third = 中间三分之一
+zone-right-third = 右三分之一
+zone-top-left = 左上四分之一
+zone-top-right = 右上四分之一
+zone-bottom-left = 左下四分之一
+zone-bottom-right = 右下四分之一
 
 # Hotkeys
 hotkey-lasso = 捕获窗口
@@ -50,8 +94,22 @@ hotkey-primary = 移动到主显示器
 hotkey-all-primary = 全部移动到主显示器
 hotkey-center = 居中窗口
 hotkey-next-monitor = 下一个显示器
+hotkey-previous-monitor = 上一个显示器
+hotkey-leader = 引导键
+hotkey-leader-hint = 按下一个键，或按 Esc 取消
+hotkey-send-to-monitor = 移动到显示器 { $number }
+hotkey-send-to-named = 移动到 { $name }
+hotkey-monitor-left = 左侧显示器
+hotkey-monitor-right = 右侧显示器
+hotkey-monitor-up = 上方显示器
+hotkey-monitor-down = 下方显示器
+hotkey-zones = 吸附区域
 hotkey-edit = 点击更改
 hotkey-press = 按下新快捷键...
+hotkey-not-set = 未设置
+hotkey-conflict-duplicate = 已被“{ $action }”使用
+hotkey-conflict-unsupported = 此键不能用作全局快捷键
+hotkey-conflict-unavailable = 已被其他应用程序占用
 hotkey-enabled = 已启用
 
 # Dialogs
@@ -63,3 +121,5 @@ dialog-tray-remember = 记住我的选择
 status-moved = 窗口移动成功
 status-error = 错误: { $message }
 status-refreshed = 窗口列表已刷新
+status-layout-saved = 已保存布局“{ $name }”
+status-layout-restored = 已恢复“{ $name }”：移动了 { $total } 个窗口中的 { $moved } 个
@@ -157,6 +157,7 @@ pub mod keys {
 
     // Buttons
     pub const BTN_CANCEL: &str = "btn-cancel";
+    pub const BTN_SAVE: &str = "btn-save";
     pub const BTN_MOVE: &str = "btn-move";
     pub const BTN_YES: &str = "btn-yes";
     pub const BTN_NO: &str = "btn-no";
@@ -166,18 +167,31 @@ pub mod keys {
     pub const TOOLTIP_REFRESH: &str = "tooltip-refresh";
     pub const TOOLTIP_SETTINGS: &str = "tooltip-settings";
     pub const TOOLTIP_BACK: &str = "tooltip-back";
+    pub const TOOLTIP_RENAME_MONITOR: &str = "tooltip-rename-monitor";
+    pub const TOOLTIP_ADD_HOTKEY: &str = "tooltip-add-hotkey";
+    pub const TOOLTIP_REMOVE_HOTKEY: &str = "tooltip-remove-hotkey";
 
     // Window list
     pub const WINDOWS_EMPTY: &str = "windows-empty";
     pub const WINDOWS_OFFSCREEN: &str = "windows-offscreen";
     pub const WINDOWS_MINIMIZED: &str = "windows-minimized";
     pub const WINDOWS_COUNT: &str = "windows-count";
+    pub const WINDOWS_FILTER: &str = "windows-filter";
+    pub const WINDOWS_FILTER_INVALID: &str = "windows-filter-invalid";
+
+    // Layouts
+    pub const LAYOUTS_NAME: &str = "layouts-name";
+    pub const LAYOUTS_SAVED: &str = "layouts-saved";
+    pub const LAYOUTS_RESTORE: &str = "layouts-restore";
+    pub const LAYOUTS_DELETE: &str = "layouts-delete";
 
     // Monitor picker
     pub const MONITOR_TITLE: &str = "monitor-title";
     pub const MONITOR_SELECT: &str = "monitor-select";
     pub const MONITOR_PRIMARY: &str = "monitor-primary";
     pub const MONITOR_RESOLUTION: &str = "monitor-resolution";
+    pub const MONITOR_SCALE: &str = "monitor-scale";
+    pub const MONITOR_MAP_HINT: &str = "monitor-map-hint";
 
     // Settings
     pub const SETTINGS_TITLE: &str = "settings-title";
@@ -185,8 +199,39 @@ pub mod keys {
     pub const SETTINGS_BEHAVIOR: &str = "settings-behavior";
     pub const SETTINGS_AUTO_FOCUS: &str = "settings-auto-focus";
     pub const SETTINGS_CLOSE_AFTER_RECOVERY: &str = "settings-close-after-recovery";
+    pub const SETTINGS_AUTO_RESCUE: &str = "settings-auto-rescue";
+    pub const SETTINGS_API: &str = "settings-api";
     pub const SETTINGS_HOTKEYS: &str = "settings-hotkeys";
     pub const SETTINGS_TRAY: &str = "settings-tray";
+    pub const SETTINGS_PLACEMENT: &str = "settings-placement";
+    pub const SETTINGS_SCALING: &str = "settings-scaling";
+    pub const SETTINGS_MONITORS_CHANGED: &str = "settings-monitors-changed";
+
+    // Monitor change actions
+    pub const MONITORS_CHANGED_NOTHING: &str = "monitors-changed-nothing";
+    pub const MONITORS_CHANGED_RESCUE: &str = "monitors-changed-rescue";
+    pub const MONITORS_CHANGED_RESTORE_LAYOUT: &str = "monitors-changed-restore-layout";
+
+    // Placement modes
+    pub const PLACEMENT_CENTER: &str = "placement-center";
+    pub const PLACEMENT_RELATIVE: &str = "placement-relative";
+
+    // Snap zones
+    pub const ZONE_MAXIMIZE: &str = "zone-maximize";
+    pub const ZONE_CENTER: &str = "zone-center";
+    pub const ZONE_LEFT_HALF: &str = "zone-left-half";
+    pub const ZONE_RIGHT_HALF: &str = "zone-right-half";
+    pub const ZONE_LEFT_THIRD: &str = "zone-left-third";
+    pub const ZONE_CENTER_THIRD: &str = "zone-center-third";
+    pub const ZONE_RIGHT_THIRD: &str = "zone-right-third";
+    pub const ZONE_TOP_LEFT: &str = "zone-top-left";
+    pub const ZONE_TOP_RIGHT: &str = "zone-top-right";
+    pub const ZONE_BOTTOM_LEFT: &str = "zone-bottom-left";
+    pub const ZONE_BOTTOM_RIGHT: &str = "zone-bottom-right";
+
+    // Scaling modes
+    pub const SCALING_RESOLUTION: &str = "scaling-resolution";
+    pub const SCALING_PHYSICAL: &str = "scaling-physical";
 
     // Hotkeys
     pub const HOTKEY_LASSO: &str = "hotkey-lasso";
@@ -195,8 +240,22 @@ pub mod keys {
     pub const HOTKEY_ALL_PRIMARY: &str = "hotkey-all-primary";
     pub const HOTKEY_CENTER: &str = "hotkey-center";
     pub const HOTKEY_NEXT_MONITOR: &str = "hotkey-next-monitor";
+    pub const HOTKEY_PREVIOUS_MONITOR: &str = "hotkey-previous-monitor";
+    pub const HOTKEY_LEADER: &str = "hotkey-leader";
+    pub const HOTKEY_LEADER_HINT: &str = "hotkey-leader-hint";
+    pub const HOTKEY_SEND_TO_MONITOR: &str = "hotkey-send-to-monitor";
+    pub const HOTKEY_SEND_TO_NAMED: &str = "hotkey-send-to-named";
+    pub const HOTKEY_MONITOR_LEFT: &str = "hotkey-monitor-left";
+    pub const HOTKEY_MONITOR_RIGHT: &str = "hotkey-monitor-right";
+    pub const HOTKEY_MONITOR_UP: &str = "hotkey-monitor-up";
+    pub const HOTKEY_MONITOR_DOWN: &str = "hotkey-monitor-down";
+    pub const HOTKEY_ZONES: &str = "hotkey-zones";
     pub const HOTKEY_EDIT: &str = "hotkey-edit";
     pub const HOTKEY_PRESS: &str = "hotkey-press";
+    pub const HOTKEY_NOT_SET: &str = "hotkey-not-set";
+    pub const HOTKEY_CONFLICT_DUPLICATE: &str = "hotkey-conflict-duplicate";
+    pub const HOTKEY_CONFLICT_UNSUPPORTED: &str = "hotkey-conflict-unsupported";
+    pub const HOTKEY_CONFLICT_UNAVAILABLE: &str = "hotkey-conflict-unavailable";
 
     // Dialogs
     pub const DIALOG_TRAY_TITLE: &str = "dialog-tray-title";
@@ -206,4 +265,6 @@ pub mod keys {
     pub const STATUS_MOVED: &str = "status-moved";
     pub const STATUS_ERROR: &str = "status-error";
     pub const STATUS_REFRESHED: &str = "status-refreshed";
+    pub const STATUS_LAYOUT_SAVED: &str = "status-layout-saved";
+    pub const STATUS_LAYOUT_RESTORED: &str = "status-layout-restored";
 }
@@ -4,39 +4,129 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod api;
 mod app;
+mod backend;
+mod cli;
 mod hotkeys;
+mod ipc;
+mod keycodes;
+mod layouts;
 mod localization;
+mod matcher;
+mod placement;
+mod rescue;
 mod settings;
+mod topology;
 mod tray;
 mod types;
 mod views;
+#[cfg(windows)]
 mod windows_api;
+mod zones;
 
 use app::App;
+use cli::Command;
 use iced::window::icon;
 use iced::Size;
+use ipc::{BindError, InstanceServer, IpcRequest};
+use std::cell::Cell;
+use std::process::ExitCode;
 
-fn main() -> iced::Result {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
+fn main() -> ExitCode {
+    // Initialize logging; stdout is kept for command output
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
 
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            #[cfg(windows)]
+            windows_api::attach_parent_console();
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    #[cfg(windows)]
+    if command.is_some() {
+        windows_api::attach_parent_console();
+    }
+
+    // Hand the command to a running instance, so there's only ever one tray
+    // icon and one set of hotkeys
+    let request = IpcRequest::for_command(command.as_ref());
+    if let Some(request) = &request {
+        if let Some(output) = ipc::send(request) {
+            return output.finish();
+        }
+    }
+
+    match command {
+        None | Some(Command::Show | Command::Refresh | Command::Lasso) => {}
+        Some(command) => {
+            // Before any monitor is enumerated
+            #[cfg(windows)]
+            windows_api::enable_per_monitor_dpi_awareness();
+            return cli::run(command);
+        }
+    }
+
+    // Listen for later launches. If another launch got there first since we
+    // checked, that one is the running instance.
+    let instance_server = match InstanceServer::start() {
+        Ok(server) => Some(server),
+        Err(BindError::InUse) => {
+            return match ipc::send(&request.unwrap_or(IpcRequest::Show)) {
+                Some(output) => output.finish(),
+                None => {
+                    tracing::error!("WindowLasso is already running but isn't answering");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Err(e) => {
+            tracing::warn!("Not accepting commands from other launches: {}", e);
+            None
+        }
+    };
+
+    match run_ui(instance_server) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            tracing::error!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_ui(instance_server: Option<InstanceServer>) -> iced::Result {
     // Load window icon
     let window_icon = load_window_icon();
 
-    iced::application(App::new, App::update, App::view)
-        .title(|app: &App| app.title())
-        .theme(|app: &App| app.theme())
-        .subscription(App::subscription)
-        .window(iced::window::Settings {
-            size: Size::new(500.0, 600.0),
-            min_size: Some(Size::new(400.0, 400.0)),
-            position: iced::window::Position::Centered,
-            icon: window_icon,
-            exit_on_close_request: false,
-            ..Default::default()
-        })
-        .run()
+    let main_window = iced::window::Settings {
+        size: Size::new(500.0, 600.0),
+        min_size: Some(Size::new(400.0, 400.0)),
+        position: iced::window::Position::Centered,
+        icon: window_icon,
+        exit_on_close_request: false,
+        ..Default::default()
+    };
+
+    // Boot runs once; the server is handed over then
+    let instance_server = Cell::new(instance_server);
+
+    // A daemon, so the leader key hint can open a window of its own
+    iced::daemon(
+        move || App::new(main_window.clone(), instance_server.take()),
+        App::update,
+        App::view,
+    )
+    .title(App::title)
+    .theme(App::theme)
+    .subscription(App::subscription)
+    .run()
 }
 
 fn load_window_icon() -> Option<icon::Icon> {
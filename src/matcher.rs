@@ -0,0 +1,532 @@
+//! Window matching
+//!
+//! A small query language for picking windows, shared by the command line
+//! and the search box. Filters separated by spaces must all match, `or`
+//! gives alternatives, `not` (or a leading `-`) negates a filter and
+//! parentheses group:
+//!
+//! ```text
+//! process:chrome title~"Meet" offscreen
+//! (process:code or process:devenv) not minimized
+//! ```
+
+use crate::backend::monitor_for_rect;
+use crate::topology::find_monitor;
+use crate::types::{MonitorInfo, WindowInfo};
+use regex::{Regex, RegexBuilder};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+use std::vec;
+
+/// A condition on a window
+#[derive(Debug, Clone)]
+pub enum WindowMatcher {
+    /// Every condition holds; with none, every window matches
+    All(Vec<WindowMatcher>),
+    /// At least one condition holds
+    Any(Vec<WindowMatcher>),
+    Not(Box<WindowMatcher>),
+    /// A bare word: part of the title, the process name or a window handle
+    Text(String),
+    /// Part of the title, ignoring case
+    Title(String),
+    TitleRegex(Regex),
+    /// Process name, ignoring case and a trailing `.exe`
+    Process(String),
+    ProcessRegex(Regex),
+    Pid(u32),
+    Handle(isize),
+    /// Monitor the window is on: a position, a stable ID or a name
+    Monitor(String),
+    Offscreen,
+    Minimized,
+}
+
+impl Default for WindowMatcher {
+    fn default() -> Self {
+        WindowMatcher::All(Vec::new())
+    }
+}
+
+impl WindowMatcher {
+    /// Whether a window meets the condition
+    pub fn matches(&self, window: &WindowInfo, monitors: &[MonitorInfo]) -> bool {
+        match self {
+            WindowMatcher::All(matchers) => matchers.iter().all(|m| m.matches(window, monitors)),
+            WindowMatcher::Any(matchers) => matchers.iter().any(|m| m.matches(window, monitors)),
+            WindowMatcher::Not(matcher) => !matcher.matches(window, monitors),
+            WindowMatcher::Text(text) => {
+                window.title.to_lowercase().contains(text)
+                    || process_matches(&window.process_name, text)
+                    || parse_handle(text) == Some(window.hwnd)
+            }
+            WindowMatcher::Title(text) => window.title.to_lowercase().contains(text),
+            WindowMatcher::TitleRegex(regex) => regex.is_match(&window.title),
+            WindowMatcher::Process(name) => process_matches(&window.process_name, name),
+            WindowMatcher::ProcessRegex(regex) => regex.is_match(&window.process_name),
+            WindowMatcher::Pid(pid) => window.process_id == *pid,
+            WindowMatcher::Handle(hwnd) => window.hwnd == *hwnd,
+            WindowMatcher::Monitor(query) => {
+                match (
+                    monitor_for_rect(&window.rect, monitors),
+                    find_monitor(query, monitors),
+                ) {
+                    (Some(current), Some(wanted)) => current.stable_id == wanted.stable_id,
+                    _ => false,
+                }
+            }
+            WindowMatcher::Offscreen => window.is_offscreen,
+            WindowMatcher::Minimized => window.is_minimized,
+        }
+    }
+
+    /// The windows that meet the condition, in their original order
+    pub fn filter<'a>(
+        &self,
+        windows: &'a [WindowInfo],
+        monitors: &[MonitorInfo],
+    ) -> Vec<&'a WindowInfo> {
+        windows
+            .iter()
+            .filter(|window| self.matches(window, monitors))
+            .collect()
+    }
+}
+
+impl FromStr for WindowMatcher {
+    type Err = String;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(query)?;
+        if tokens.is_empty() {
+            return Ok(WindowMatcher::default());
+        }
+
+        let mut parser = Parser {
+            tokens: tokens.into_iter().peekable(),
+            depth: 0,
+        };
+        let matcher = parser.parse_or()?;
+        match parser.tokens.next() {
+            None => Ok(matcher),
+            Some(_) => Err("Unexpected )".to_string()),
+        }
+    }
+}
+
+/// Parse a window handle given in decimal or as `0x` hex
+pub fn parse_handle(text: &str) -> Option<isize> {
+    match text.strip_prefix("0x") {
+        Some(hex) => isize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Compare a process name, ignoring case and a trailing `.exe`
+///
+/// `name` is already lowercase.
+pub fn process_matches(process: &str, name: &str) -> bool {
+    let process = process.to_lowercase();
+    process == name || process.strip_suffix(".exe") == Some(name)
+}
+
+enum Token {
+    Open,
+    Close,
+    Or,
+    And,
+    Not,
+    Filter(WindowMatcher),
+}
+
+/// Split a query into tokens, turning each word into a filter
+///
+/// `-` and `!` negate only when written straight before a filter, so titles
+/// such as `Inbox - Outlook` can be typed as they are.
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    _ => Token::Close,
+                });
+            }
+            '-' | '!' if starts_filter(chars.clone().nth(1)) => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let word = read_word(&mut chars)?;
+                tokens.push(word_token(word)?);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Whether a negation sign followed by this character applies to a filter
+fn starts_filter(next: Option<char>) -> bool {
+    next.is_some_and(|c| !c.is_whitespace() && !matches!(c, ')' | '-' | '!'))
+}
+
+/// A word of the query, such as `title~"Meet now"`
+struct Word {
+    /// Field name and operator (`:` or `~`), if the word has one
+    field: Option<(String, char)>,
+    value: String,
+    quoted: bool,
+}
+
+/// Read a word, up to whitespace or a parenthesis outside quotes
+fn read_word(chars: &mut Peekable<Chars>) -> Result<Word, String> {
+    let mut word = Word {
+        field: None,
+        value: String::new(),
+        quoted: false,
+    };
+    let mut in_quotes = false;
+
+    while let Some(&c) = chars.peek() {
+        if !in_quotes && (c.is_whitespace() || c == '(' || c == ')') {
+            break;
+        }
+        chars.next();
+
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                word.quoted = true;
+            }
+            '\\' if in_quotes => {
+                if let Some(escaped) = chars.next() {
+                    word.value.push(escaped);
+                }
+            }
+            // The first `:` or `~` before any quote ends the field name, if
+            // it is one; otherwise `Re: Budget` would be a filter
+            ':' | '~'
+                if !in_quotes
+                    && !word.quoted
+                    && word.field.is_none()
+                    && is_field(&word.value) =>
+            {
+                word.field = Some((std::mem::take(&mut word.value), c));
+            }
+            _ => word.value.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("Unclosed quote".to_string());
+    }
+    Ok(word)
+}
+
+/// Field names a word can start with
+const FIELDS: [&str; 5] = ["title", "process", "pid", "handle", "monitor"];
+
+fn is_field(name: &str) -> bool {
+    FIELDS.iter().any(|field| field.eq_ignore_ascii_case(name))
+}
+
+fn word_token(word: Word) -> Result<Token, String> {
+    let Some((field, op)) = word.field else {
+        if word.quoted {
+            return Ok(Token::Filter(WindowMatcher::Text(
+                word.value.to_lowercase(),
+            )));
+        }
+        return Ok(match word.value.to_lowercase().as_str() {
+            "or" => Token::Or,
+            "and" => Token::And,
+            "not" => Token::Not,
+            "offscreen" | "off-screen" => Token::Filter(WindowMatcher::Offscreen),
+            "minimized" => Token::Filter(WindowMatcher::Minimized),
+            text => Token::Filter(WindowMatcher::Text(text.to_string())),
+        });
+    };
+
+    let value = word.value;
+    let matcher = match (field.to_lowercase().as_str(), op) {
+        ("title", ':') => WindowMatcher::Title(value.to_lowercase()),
+        ("title", '~') => WindowMatcher::TitleRegex(regex(&value)?),
+        ("process", ':') => WindowMatcher::Process(value.to_lowercase()),
+        ("process", '~') => WindowMatcher::ProcessRegex(regex(&value)?),
+        ("pid", ':') => WindowMatcher::Pid(
+            value
+                .parse()
+                .map_err(|_| format!("Invalid PID: {}", value))?,
+        ),
+        ("handle", ':') => WindowMatcher::Handle(
+            parse_handle(&value).ok_or_else(|| format!("Invalid window handle: {}", value))?,
+        ),
+        ("monitor", ':') => WindowMatcher::Monitor(value),
+        _ => return Err(format!("Unknown filter: {}{}", field, op)),
+    };
+    Ok(Token::Filter(matcher))
+}
+
+/// Compile a regex that ignores case, like the other text filters
+fn regex(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| format!("Invalid regex: {}", e))
+}
+
+/// Deepest nesting of parentheses and negations a query may have
+const MAX_NESTING: usize = 32;
+
+/// Recursive descent over the tokens; `or` binds looser than `and`
+struct Parser {
+    tokens: Peekable<vec::IntoIter<Token>>,
+    /// Parentheses and negations currently open
+    depth: usize,
+}
+
+impl Parser {
+    fn parse_or(&mut self) -> Result<WindowMatcher, String> {
+        let mut alternatives = vec![self.parse_and()?];
+        while matches!(self.tokens.peek(), Some(Token::Or)) {
+            self.tokens.next();
+            alternatives.push(self.parse_and()?);
+        }
+
+        Ok(match alternatives.len() {
+            1 => alternatives.remove(0),
+            _ => WindowMatcher::Any(alternatives),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<WindowMatcher, String> {
+        let mut conditions = Vec::new();
+        loop {
+            match self.tokens.peek() {
+                None | Some(Token::Close) | Some(Token::Or) => break,
+                Some(Token::And) => {
+                    self.tokens.next();
+                }
+                _ => conditions.push(self.parse_unary()?),
+            }
+        }
+
+        match conditions.len() {
+            0 => Err("Expected a filter".to_string()),
+            1 => Ok(conditions.remove(0)),
+            _ => Ok(WindowMatcher::All(conditions)),
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<WindowMatcher, String> {
+        match self.tokens.next() {
+            Some(Token::Not) => Ok(WindowMatcher::Not(Box::new(
+                self.nested(Self::parse_unary)?,
+            ))),
+            Some(Token::Open) => {
+                let inner = self.nested(Self::parse_or)?;
+                match self.tokens.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err("Missing )".to_string()),
+                }
+            }
+            Some(Token::Filter(matcher)) => Ok(matcher),
+            _ => Err("Expected a filter".to_string()),
+        }
+    }
+
+    /// Parse one level deeper, refusing queries nested deep enough to
+    /// exhaust the stack
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<WindowMatcher, String>,
+    ) -> Result<WindowMatcher, String> {
+        if self.depth >= MAX_NESTING {
+            return Err("Query is nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::finish_window_list;
+    use crate::types::test_support::{monitor, rect, window};
+
+    fn desktop() -> (Vec<WindowInfo>, Vec<MonitorInfo>) {
+        let mut monitors = vec![
+            monitor(0, rect(0, 0, 1920, 1080), 96),
+            monitor(1, rect(1920, 0, 3840, 1080), 96),
+        ];
+        monitors[1].nickname = Some("Right".to_string());
+
+        let mut windows = vec![
+            window(100, "OUTLOOK.EXE", "Inbox - Outlook", rect(0, 0, 800, 600)),
+            window(
+                200,
+                "thunderbird",
+                "Inbox - Thunderbird",
+                rect(2000, 0, 2800, 600),
+            ),
+            window(
+                300,
+                "chrome.exe",
+                "GitHub | Repo - Google Chrome",
+                rect(-2000, 0, -1000, 600),
+            ),
+            window(
+                400,
+                "notepad.exe",
+                "Notes -- draft",
+                rect(100, 100, 900, 700),
+            ),
+        ];
+        windows[3].is_minimized = true;
+        finish_window_list(&mut windows, &monitors);
+        (windows, monitors)
+    }
+
+    /// Handles of the windows a query matches, in handle order
+    fn matching(query: &str) -> Vec<isize> {
+        let (windows, monitors) = desktop();
+        let matcher: WindowMatcher = query.parse().unwrap();
+        let mut handles: Vec<isize> = matcher
+            .filter(&windows, &monitors)
+            .iter()
+            .map(|w| w.hwnd)
+            .collect();
+        handles.sort();
+        handles
+    }
+
+    #[test]
+    fn words_match_title_process_or_handle() {
+        assert_eq!(matching(""), [100, 200, 300, 400]);
+        assert_eq!(matching("inbox"), [100, 200]);
+        assert_eq!(matching("OUTLOOK"), [100]);
+        assert_eq!(matching("notepad"), [400]);
+        assert_eq!(matching("300"), [300]);
+        assert_eq!(matching("0x190"), [400]);
+        assert_eq!(matching("\"inbox - t\""), [200]);
+    }
+
+    #[test]
+    fn titles_with_symbols_can_be_typed_as_they_are() {
+        assert_eq!(matching("Inbox - Outlook"), [100]);
+        assert_eq!(matching("GitHub | Repo"), [300]);
+        assert_eq!(matching("Notes -- draft"), [400]);
+        assert_eq!(matching("Inbox & Outlook"), Vec::<isize>::new());
+    }
+
+    #[test]
+    fn titles_with_colons_and_tildes_are_text() {
+        let monitors = vec![monitor(0, rect(0, 0, 1920, 1080), 96)];
+        let mut windows = vec![
+            window(100, "OUTLOOK.EXE", "Re: Budget - Message", rect(0, 0, 800, 600)),
+            window(200, "notepad.exe", "Untitled: Notepad", rect(0, 0, 800, 600)),
+            window(300, "msedge.exe", "https://example.com/a~b", rect(0, 0, 800, 600)),
+        ];
+        finish_window_list(&mut windows, &monitors);
+
+        for (query, expected) in [
+            ("Re: Budget", 100),
+            ("Untitled: Notepad", 200),
+            ("https://example.com/a~b", 300),
+            ("example.com/a~b", 300),
+        ] {
+            let matcher: WindowMatcher = query.parse().unwrap();
+            let handles: Vec<isize> = matcher
+                .filter(&windows, &monitors)
+                .iter()
+                .map(|w| w.hwnd)
+                .collect();
+            assert_eq!(handles, [expected], "{}", query);
+        }
+    }
+
+    #[test]
+    fn negation_applies_to_the_filter_it_prefixes() {
+        assert_eq!(matching("inbox -outlook"), [200]);
+        assert_eq!(matching("inbox !outlook"), [200]);
+        assert_eq!(matching("inbox not outlook"), [200]);
+        assert_eq!(matching("-minimized"), [100, 200, 300]);
+        assert_eq!(matching("-(inbox or minimized)"), [300]);
+    }
+
+    #[test]
+    fn fields_and_states() {
+        assert_eq!(matching("process:outlook"), [100]);
+        assert_eq!(matching("process~\"^(chrome|thunder)\""), [200, 300]);
+        assert_eq!(matching("title:inbox"), [100, 200]);
+        assert_eq!(matching("title~\"^inbox - o\""), [100]);
+        assert_eq!(matching("pid:200"), [200]);
+        assert_eq!(matching("handle:0x64"), [100]);
+        assert_eq!(matching("offscreen"), [300]);
+        assert_eq!(matching("minimized"), [400]);
+    }
+
+    #[test]
+    fn monitor_filter_finds_nicknamed_monitors() {
+        assert_eq!(matching("monitor:2"), [200]);
+        assert_eq!(matching("monitor:Right"), [200]);
+        assert_eq!(matching("monitor:1"), [100, 400]);
+    }
+
+    #[test]
+    fn or_binds_looser_than_and() {
+        assert_eq!(matching("inbox outlook or notepad"), [100, 400]);
+        assert_eq!(matching("inbox (outlook or thunderbird)"), [100, 200]);
+        assert_eq!(matching("inbox and outlook"), [100]);
+    }
+
+    #[test]
+    fn malformed_queries_are_errors() {
+        for (query, error) in [
+            ("title:\"open", "Unclosed quote"),
+            ("(inbox", "Missing )"),
+            ("inbox)", "Unexpected )"),
+            ("or inbox", "Expected a filter"),
+            ("pid~5", "Unknown filter: pid~"),
+            ("pid:abc", "Invalid PID: abc"),
+        ] {
+            assert_eq!(
+                query.parse::<WindowMatcher>().err().as_deref(),
+                Some(error),
+                "{}",
+                query
+            );
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_refused() {
+        let parens = format!("{}inbox{}", "(".repeat(100_000), ")".repeat(100_000));
+        let nots = format!("{}inbox", "not ".repeat(100_000));
+        let signs = format!("{}inbox", "-(".repeat(100_000));
+        for query in [parens, nots, signs] {
+            assert_eq!(
+                query.parse::<WindowMatcher>().err().as_deref(),
+                Some("Query is nested too deeply")
+            );
+        }
+
+        let allowed = format!(
+            "{}inbox{}",
+            "(".repeat(MAX_NESTING),
+            ")".repeat(MAX_NESTING)
+        );
+        assert!(allowed.parse::<WindowMatcher>().is_ok());
+    }
+}
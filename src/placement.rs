@@ -0,0 +1,550 @@
+//! Window placement geometry
+//!
+//! Pure calculations for where a window should land on a monitor. Backends
+//! apply the result; nothing here touches the platform.
+
+use crate::types::{MonitorInfo, WindowRect};
+use crate::zones::ZoneRect;
+use serde::{Deserialize, Serialize};
+
+/// How a window is positioned on the target monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PlacementMode {
+    /// Center the window on the target work area
+    #[default]
+    Center,
+    /// Keep the window's proportional position within its source monitor
+    ///
+    /// Falls back to centering when the source monitor is unknown.
+    PreserveRelative,
+}
+
+/// How a window is resized when it moves to a different monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScalingMode {
+    /// Scale by the ratio between the monitors' work area resolutions
+    #[default]
+    Resolution,
+    /// Keep the window's physical size by scaling with the monitors' DPI
+    PhysicalSize,
+}
+
+/// DPI that corresponds to 100% display scaling
+pub const BASE_DPI: u32 = 96;
+
+/// Explicit spot on the target monitor, overriding the placement mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlacementTarget {
+    /// Fill a zone of the work area
+    Zone(ZoneRect),
+    /// Center the window on a point, given as fractions of the work area
+    Point { x: f64, y: f64 },
+    /// Maximize on the target monitor
+    Maximize,
+}
+
+/// Options controlling where a moved window lands
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PlacementPolicy {
+    pub mode: PlacementMode,
+    pub scaling: ScalingMode,
+    /// Maximize the window after moving
+    pub maximize: bool,
+    /// Spot chosen by the user, if any
+    pub target: Option<PlacementTarget>,
+}
+
+impl PlacementPolicy {
+    /// Center the window and maximize it
+    pub fn maximized() -> Self {
+        Self {
+            maximize: true,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a plain move needs to know the monitor the window came from
+    ///
+    /// Only relative placement and physical-size scaling do; otherwise the
+    /// window keeps its size, as a lasso always has.
+    pub fn needs_source_monitor(&self) -> bool {
+        self.mode == PlacementMode::PreserveRelative || self.scaling == ScalingMode::PhysicalSize
+    }
+}
+
+/// Show state the window should end up in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShowState {
+    Normal,
+    Maximized,
+}
+
+/// Result of a placement calculation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Restored (non-maximized) bounds on the target monitor
+    pub rect: WindowRect,
+    pub show: ShowState,
+}
+
+/// Calculate where a window should land on the target monitor
+///
+/// `source_rect` is the window's restored bounds. If `source_monitor` is given,
+/// the window size is scaled between the two monitors according to the policy.
+/// A target in the policy takes precedence over the placement mode.
+pub fn place(
+    source_rect: &WindowRect,
+    source_monitor: Option<&MonitorInfo>,
+    target_monitor: &MonitorInfo,
+    policy: &PlacementPolicy,
+) -> Placement {
+    let mut width = source_rect.width();
+    let mut height = source_rect.height();
+
+    // Scale window size based on monitor resolution if source is provided
+    if let Some(src) = source_monitor {
+        let scale = scale_factor(src, target_monitor, policy.scaling);
+        width = (width as f64 * scale) as i32;
+        height = (height as f64 * scale) as i32;
+    }
+
+    let work_area = &target_monitor.work_area;
+    let show = if policy.maximize || policy.target == Some(PlacementTarget::Maximize) {
+        ShowState::Maximized
+    } else {
+        ShowState::Normal
+    };
+
+    // Zones fill their region regardless of the window's size
+    if let Some(PlacementTarget::Zone(zone)) = policy.target {
+        return Placement {
+            rect: zone.resolve(work_area),
+            show,
+        };
+    }
+
+    // Ensure window doesn't exceed target monitor's work area
+    width = width.min(work_area.width());
+    height = height.min(work_area.height());
+
+    let (left, top) = match (policy.target, policy.mode, source_monitor) {
+        (Some(PlacementTarget::Point { x, y }), ..) => (
+            work_area.left + (x * work_area.width() as f64).round() as i32 - width / 2,
+            work_area.top + (y * work_area.height() as f64).round() as i32 - height / 2,
+        ),
+        (_, PlacementMode::PreserveRelative, Some(src)) => (
+            relative_offset(
+                source_rect.left,
+                source_rect.width(),
+                src.work_area.left,
+                src.work_area.width(),
+                work_area.left,
+                work_area.width(),
+                width,
+            ),
+            relative_offset(
+                source_rect.top,
+                source_rect.height(),
+                src.work_area.top,
+                src.work_area.height(),
+                work_area.top,
+                work_area.height(),
+                height,
+            ),
+        ),
+        _ => {
+            let (center_x, center_y) = target_monitor.center();
+            (center_x - width / 2, center_y - height / 2)
+        }
+    };
+
+    Placement {
+        rect: clamp_to_work_area(left, top, width, height, work_area),
+        show,
+    }
+}
+
+/// Center a window on a monitor's work area, keeping its size
+///
+/// Unlike `place`, a window larger than the work area is not shrunk.
+pub fn center(rect: &WindowRect, monitor: &MonitorInfo) -> WindowRect {
+    let (center_x, center_y) = monitor.center();
+    let width = rect.width();
+    let height = rect.height();
+    clamp_to_work_area(
+        center_x - width / 2,
+        center_y - height / 2,
+        width,
+        height,
+        &monitor.work_area,
+    )
+}
+
+/// Map a window's position along one axis from the source work area to the target
+///
+/// The window keeps its share of the free space on either side, so a window docked
+/// to an edge stays docked. If it fills the source axis, its center is kept instead.
+fn relative_offset(
+    start: i32,
+    size: i32,
+    src_start: i32,
+    src_size: i32,
+    tgt_start: i32,
+    tgt_size: i32,
+    new_size: i32,
+) -> i32 {
+    let src_free = src_size - size;
+    if src_free > 0 {
+        let fraction = ((start - src_start) as f64 / src_free as f64).clamp(0.0, 1.0);
+        tgt_start + (fraction * (tgt_size - new_size) as f64).round() as i32
+    } else if src_size > 0 {
+        let center = (start + size / 2 - src_start) as f64 / src_size as f64;
+        tgt_start + (center * tgt_size as f64).round() as i32 - new_size / 2
+    } else {
+        tgt_start + (tgt_size - new_size) / 2
+    }
+}
+
+/// Size ratio for a window moving between two monitors
+fn scale_factor(source: &MonitorInfo, target: &MonitorInfo, scaling: ScalingMode) -> f64 {
+    match scaling {
+        ScalingMode::Resolution => resolution_scale(source, target),
+        ScalingMode::PhysicalSize => dpi_scale(source.dpi, target.dpi),
+    }
+}
+
+/// Size ratio between two monitors' work areas, preserving aspect ratio
+fn resolution_scale(source: &MonitorInfo, target: &MonitorInfo) -> f64 {
+    if source.work_area.width() <= 0 || source.work_area.height() <= 0 {
+        return 1.0;
+    }
+
+    let scale_x = target.work_area.width() as f64 / source.work_area.width() as f64;
+    let scale_y = target.work_area.height() as f64 / source.work_area.height() as f64;
+    scale_x.min(scale_y)
+}
+
+/// Pixel ratio that keeps a window the same physical size across two DPIs
+///
+/// A window at 100% on a 96 DPI screen needs twice the pixels on a 192 DPI screen.
+pub fn dpi_scale(source_dpi: u32, target_dpi: u32) -> f64 {
+    if source_dpi == 0 || target_dpi == 0 {
+        return 1.0;
+    }
+
+    target_dpi as f64 / source_dpi as f64
+}
+
+/// Convert a DPI value to a display scaling percentage
+pub fn dpi_to_percent(dpi: u32) -> u32 {
+    (dpi * 100 + BASE_DPI / 2) / BASE_DPI
+}
+
+/// Build a rect of the given size, shifted so it lies inside the work area
+fn clamp_to_work_area(
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    work_area: &WindowRect,
+) -> WindowRect {
+    let left = left.max(work_area.left).min(work_area.right - width);
+    let top = top.max(work_area.top).min(work_area.bottom - height);
+
+    WindowRect {
+        left,
+        top,
+        right: left + width,
+        bottom: top + height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::{monitor, rect};
+
+    fn policy(mode: PlacementMode) -> PlacementPolicy {
+        PlacementPolicy {
+            mode,
+            ..PlacementPolicy::default()
+        }
+    }
+
+    #[test]
+    fn center_mode_centers_on_target_work_area() {
+        let target = monitor(1, rect(1920, 0, 3840, 1080), BASE_DPI);
+        let placed = place(
+            &rect(100, 100, 900, 700),
+            None,
+            &target,
+            &PlacementPolicy::default(),
+        );
+
+        assert_eq!(placed.rect, rect(2480, 240, 3280, 840));
+        assert_eq!(placed.show, ShowState::Normal);
+    }
+
+    #[test]
+    fn window_is_shrunk_to_fit_the_work_area() {
+        let mut target = monitor(0, rect(0, 0, 1280, 1024), BASE_DPI);
+        target.work_area = rect(0, 0, 1280, 984);
+        let placed = place(
+            &rect(0, 0, 1600, 1200),
+            None,
+            &target,
+            &PlacementPolicy::default(),
+        );
+
+        assert_eq!(placed.rect, target.work_area);
+    }
+
+    #[test]
+    fn resolution_scaling_keeps_aspect_ratio() {
+        let source = monitor(0, rect(0, 0, 1920, 1080), BASE_DPI);
+        let ultrawide = monitor(1, rect(1920, 0, 7040, 1440), BASE_DPI);
+        let placed = place(
+            &rect(0, 0, 800, 600),
+            Some(&source),
+            &ultrawide,
+            &PlacementPolicy::default(),
+        );
+
+        // The smaller ratio (1440 / 1080) applies to both axes
+        assert_eq!(placed.rect.width(), 1066);
+        assert_eq!(placed.rect.height(), 800);
+    }
+
+    #[test]
+    fn preserve_relative_keeps_docked_edges() {
+        let source = monitor(0, rect(0, 0, 1920, 1080), BASE_DPI);
+        let target = monitor(1, rect(1920, 0, 3840, 1080), BASE_DPI);
+        let docked_right = rect(1120, 0, 1920, 600);
+        let placed = place(
+            &docked_right,
+            Some(&source),
+            &target,
+            &policy(PlacementMode::PreserveRelative),
+        );
+
+        assert_eq!(placed.rect, rect(3040, 0, 3840, 600));
+    }
+
+    #[test]
+    fn preserve_relative_without_source_centers() {
+        let target = monitor(1, rect(1920, 0, 3840, 1080), BASE_DPI);
+        let placed = place(
+            &rect(0, 0, 800, 600),
+            None,
+            &target,
+            &policy(PlacementMode::PreserveRelative),
+        );
+
+        assert_eq!(placed.rect, rect(2480, 240, 3280, 840));
+    }
+
+    #[test]
+    fn zone_target_fills_the_zone() {
+        let target = monitor(0, rect(0, 0, 1920, 1080), BASE_DPI);
+        let placed = place(
+            &rect(10, 10, 20, 20),
+            None,
+            &target,
+            &PlacementPolicy {
+                target: Some(PlacementTarget::Zone(ZoneRect::new(0.5, 0.0, 0.5, 1.0))),
+                ..PlacementPolicy::default()
+            },
+        );
+
+        assert_eq!(placed.rect, rect(960, 0, 1920, 1080));
+        assert_eq!(placed.show, ShowState::Normal);
+    }
+
+    #[test]
+    fn point_target_is_clamped_inside_the_work_area() {
+        let target = monitor(0, rect(0, 0, 1920, 1080), BASE_DPI);
+        let placed = place(
+            &rect(0, 0, 800, 600),
+            None,
+            &target,
+            &PlacementPolicy {
+                target: Some(PlacementTarget::Point { x: 1.0, y: 0.0 }),
+                ..PlacementPolicy::default()
+            },
+        );
+
+        assert_eq!(placed.rect, rect(1120, 0, 1920, 600));
+    }
+
+    #[test]
+    fn maximize_sets_the_show_state() {
+        let target = monitor(0, rect(0, 0, 1920, 1080), BASE_DPI);
+        let window = rect(0, 0, 800, 600);
+
+        let by_flag = place(&window, None, &target, &PlacementPolicy::maximized());
+        let by_target = place(
+            &window,
+            None,
+            &target,
+            &PlacementPolicy {
+                target: Some(PlacementTarget::Maximize),
+                ..PlacementPolicy::default()
+            },
+        );
+
+        assert_eq!(by_flag.show, ShowState::Maximized);
+        assert_eq!(by_target, by_flag);
+    }
+
+    #[test]
+    fn center_keeps_the_window_size() {
+        let target = monitor(0, rect(0, 0, 1920, 1080), BASE_DPI);
+
+        assert_eq!(
+            center(&rect(0, 0, 800, 600), &target),
+            rect(560, 240, 1360, 840)
+        );
+        // Larger than the work area: moved, not resized
+        let oversized = center(&rect(-50, -50, 2050, 1250), &target);
+        assert_eq!(oversized.width(), 2100);
+        assert_eq!(oversized.height(), 1300);
+    }
+
+    #[test]
+    fn relative_offset_keeps_share_of_free_space() {
+        // Centered in 1000 px of free space, 500 px of free space on the target
+        assert_eq!(relative_offset(500, 1000, 0, 2000, 0, 1500, 1000), 250);
+        // Docked to either edge
+        assert_eq!(relative_offset(0, 1000, 0, 2000, 3000, 1500, 1000), 3000);
+        assert_eq!(relative_offset(1000, 1000, 0, 2000, 3000, 1500, 1000), 3500);
+        // Beyond the edge counts as docked
+        assert_eq!(relative_offset(-300, 1000, 0, 2000, 0, 1500, 1000), 0);
+    }
+
+    #[test]
+    fn relative_offset_keeps_center_of_full_size_window() {
+        assert_eq!(relative_offset(0, 2000, 0, 2000, 0, 1000, 800), 100);
+        // Degenerate source centers on the target
+        assert_eq!(relative_offset(0, 100, 0, 0, 0, 1000, 100), 450);
+    }
+
+    #[test]
+    fn clamp_moves_window_inside_the_work_area() {
+        let area = rect(0, 0, 1920, 1040);
+
+        assert_eq!(
+            clamp_to_work_area(-100, -100, 800, 600, &area),
+            rect(0, 0, 800, 600)
+        );
+        assert_eq!(
+            clamp_to_work_area(1500, 800, 800, 600, &area),
+            rect(1120, 440, 1920, 1040)
+        );
+        assert_eq!(
+            clamp_to_work_area(100, 100, 800, 600, &area),
+            rect(100, 100, 900, 700)
+        );
+    }
+
+    fn physical_size() -> PlacementPolicy {
+        PlacementPolicy {
+            scaling: ScalingMode::PhysicalSize,
+            ..PlacementPolicy::default()
+        }
+    }
+
+    #[test]
+    fn dpi_scale_between_common_scalings() {
+        assert_eq!(dpi_scale(96, 96), 1.0);
+        assert_eq!(dpi_scale(96, 144), 1.5);
+        assert_eq!(dpi_scale(96, 192), 2.0);
+        assert_eq!(dpi_scale(144, 192), 192.0 / 144.0);
+        assert_eq!(dpi_scale(192, 144), 0.75);
+        assert_eq!(dpi_scale(192, 96), 0.5);
+        // Unknown DPI leaves the size alone
+        assert_eq!(dpi_scale(0, 144), 1.0);
+        assert_eq!(dpi_scale(144, 0), 1.0);
+    }
+
+    #[test]
+    fn dpi_to_percent_rounds() {
+        assert_eq!(dpi_to_percent(96), 100);
+        assert_eq!(dpi_to_percent(120), 125);
+        assert_eq!(dpi_to_percent(144), 150);
+        assert_eq!(dpi_to_percent(192), 200);
+    }
+
+    #[test]
+    fn physical_size_scales_with_dpi() {
+        let at_96 = monitor(0, rect(0, 0, 1920, 1080), 96);
+        let at_144 = monitor(1, rect(1920, 0, 4480, 1440), 144);
+        let at_192 = monitor(2, rect(4480, 0, 8320, 2160), 192);
+
+        let up = place(
+            &rect(0, 0, 800, 600),
+            Some(&at_96),
+            &at_192,
+            &physical_size(),
+        );
+        assert_eq!((up.rect.width(), up.rect.height()), (1600, 1200));
+
+        let up = place(
+            &rect(0, 0, 800, 600),
+            Some(&at_96),
+            &at_144,
+            &physical_size(),
+        );
+        assert_eq!((up.rect.width(), up.rect.height()), (1200, 900));
+
+        let down = place(
+            &rect(1920, 0, 2820, 600),
+            Some(&at_144),
+            &at_96,
+            &physical_size(),
+        );
+        assert_eq!((down.rect.width(), down.rect.height()), (600, 400));
+
+        let down = place(
+            &rect(4480, 0, 6080, 1200),
+            Some(&at_192),
+            &at_144,
+            &physical_size(),
+        );
+        assert_eq!((down.rect.width(), down.rect.height()), (1200, 900));
+    }
+
+    #[test]
+    fn physical_size_round_trip_restores_the_size() {
+        let at_96 = monitor(0, rect(0, 0, 1920, 1080), 96);
+        let at_144 = monitor(1, rect(1920, 0, 4480, 1440), 144);
+        let window = rect(100, 100, 900, 700);
+
+        let there = place(&window, Some(&at_96), &at_144, &physical_size());
+        let back = place(&there.rect, Some(&at_144), &at_96, &physical_size());
+
+        assert_eq!(back.rect.width(), window.width());
+        assert_eq!(back.rect.height(), window.height());
+    }
+
+    #[test]
+    fn resolution_scaling_ignores_dpi() {
+        let source = monitor(0, rect(0, 0, 1920, 1080), 96);
+        let same_size = monitor(1, rect(1920, 0, 3840, 1080), 192);
+        let placed = place(
+            &rect(0, 0, 800, 600),
+            Some(&source),
+            &same_size,
+            &PlacementPolicy::default(),
+        );
+
+        assert_eq!((placed.rect.width(), placed.rect.height()), (800, 600));
+    }
+
+    #[test]
+    fn only_relative_and_physical_moves_need_a_source() {
+        assert!(!PlacementPolicy::default().needs_source_monitor());
+        assert!(!PlacementPolicy::maximized().needs_source_monitor());
+        assert!(policy(PlacementMode::PreserveRelative).needs_source_monitor());
+        assert!(physical_size().needs_source_monitor());
+    }
+}
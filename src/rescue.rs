@@ -0,0 +1,302 @@
+//! Automatic rescue of off-screen windows
+//!
+//! Follows windows across refreshes and picks out the ones that went
+//! off-screen and stayed there for the grace period. Windows already
+//! off-screen when tracking starts are left alone, and a window is only tried
+//! once each time it goes off-screen.
+
+use crate::matcher::process_matches;
+use crate::topology::find_monitor;
+use crate::types::{AutoRescueSettings, MonitorInfo, WindowInfo};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Tracks windows going off-screen
+#[derive(Debug, Default)]
+pub struct RescueTracker {
+    /// Off-screen windows waiting out the grace period, and since when
+    stranded: HashMap<isize, Instant>,
+    /// Off-screen windows not to move: already tried, excluded, or
+    /// off-screen since before tracking started
+    ignored: HashSet<isize>,
+    /// Whether a window list has been seen yet
+    started: bool,
+}
+
+impl RescueTracker {
+    /// Take in a fresh window list and return the windows due for rescue
+    pub fn update<'a>(
+        &mut self,
+        windows: &'a [WindowInfo],
+        settings: &AutoRescueSettings,
+        now: Instant,
+    ) -> Vec<&'a WindowInfo> {
+        let grace_period = Duration::from_secs(settings.grace_period_secs);
+        let mut due = Vec::new();
+
+        for window in windows {
+            let hwnd = window.hwnd;
+            if !window.is_offscreen {
+                // Back on-screen, so it can be rescued again next time
+                self.stranded.remove(&hwnd);
+                self.ignored.remove(&hwnd);
+                continue;
+            }
+            if self.ignored.contains(&hwnd) {
+                continue;
+            }
+            if !self.started {
+                self.ignored.insert(hwnd);
+                continue;
+            }
+
+            let since = *self.stranded.entry(hwnd).or_insert(now);
+            if now.duration_since(since) < grace_period {
+                continue;
+            }
+
+            self.stranded.remove(&hwnd);
+            self.ignored.insert(hwnd);
+            if is_excluded(window, settings) {
+                info!(
+                    "Leaving \"{}\" ({}) off-screen: its process is excluded from auto-rescue",
+                    window.title, window.process_name
+                );
+                continue;
+            }
+            due.push(window);
+        }
+
+        // Forget windows that were closed
+        let open: HashSet<isize> = windows.iter().map(|w| w.hwnd).collect();
+        self.stranded.retain(|hwnd, _| open.contains(hwnd));
+        self.ignored.retain(|hwnd| open.contains(hwnd));
+        self.started = true;
+
+        due
+    }
+}
+
+/// Whether a window's process is on the exclusion list
+///
+/// Names match with or without `.exe`, whatever the platform reports.
+pub fn is_excluded(window: &WindowInfo, settings: &AutoRescueSettings) -> bool {
+    settings.excluded_processes.iter().any(|name| {
+        let name = name.to_lowercase();
+        process_matches(&window.process_name, name.trim_end_matches(".exe"))
+    })
+}
+
+/// Monitor rescued windows go to: the fallback monitor if it is connected,
+/// otherwise the primary one
+pub fn rescue_monitor<'a>(
+    settings: &AutoRescueSettings,
+    monitors: &'a [MonitorInfo],
+) -> Option<&'a MonitorInfo> {
+    settings
+        .fallback_monitor
+        .as_deref()
+        .and_then(|query| find_monitor(query, monitors))
+        .or_else(|| monitors.iter().find(|m| m.is_primary))
+}
+
+/// Decides when close-after-recovery quits the app
+///
+/// A successful move arms the check; the next window list then tells whether
+/// it brought back the last off-screen window.
+#[derive(Debug, Default)]
+pub struct RecoveryCheck {
+    pending: bool,
+}
+
+impl RecoveryCheck {
+    /// A move finished; a failed one leaves the check as it was
+    pub fn moved(&mut self, result: &Result<(), String>) {
+        if result.is_ok() {
+            self.pending = true;
+        }
+    }
+
+    /// Take in a fresh window list and return whether to quit
+    ///
+    /// `before` is the list the move was made from.
+    pub fn windows_loaded(
+        &mut self,
+        close_after_recovery: bool,
+        before: &[WindowInfo],
+        after: &[WindowInfo],
+    ) -> bool {
+        if !std::mem::take(&mut self.pending) {
+            return false;
+        }
+        close_after_recovery
+            && before.iter().any(|w| w.is_offscreen)
+            && !after.iter().any(|w| w.is_offscreen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::{rect, window};
+
+    fn onscreen(hwnd: isize, process: &str) -> WindowInfo {
+        window(hwnd, process, "Window", rect(100, 100, 900, 700))
+    }
+
+    fn offscreen(hwnd: isize, process: &str) -> WindowInfo {
+        WindowInfo {
+            is_offscreen: true,
+            ..window(hwnd, process, "Window", rect(-2400, 100, -1600, 700))
+        }
+    }
+
+    fn settings(grace_period_secs: u64, excluded: &[&str]) -> AutoRescueSettings {
+        AutoRescueSettings {
+            enabled: true,
+            grace_period_secs,
+            excluded_processes: excluded.iter().map(|name| name.to_string()).collect(),
+            ..AutoRescueSettings::default()
+        }
+    }
+
+    fn due(
+        tracker: &mut RescueTracker,
+        windows: &[WindowInfo],
+        settings: &AutoRescueSettings,
+        now: Instant,
+    ) -> Vec<isize> {
+        tracker
+            .update(windows, settings, now)
+            .iter()
+            .map(|w| w.hwnd)
+            .collect()
+    }
+
+    #[test]
+    fn windows_wait_out_the_grace_period() {
+        let settings = settings(10, &[]);
+        let start = Instant::now();
+        let mut tracker = RescueTracker::default();
+        assert!(due(&mut tracker, &[onscreen(1, "excel.exe")], &settings, start).is_empty());
+
+        let stranded = [offscreen(1, "excel.exe")];
+        let at = |secs| start + Duration::from_secs(secs);
+        assert!(due(&mut tracker, &stranded, &settings, at(1)).is_empty());
+        assert!(due(&mut tracker, &stranded, &settings, at(10)).is_empty());
+        assert_eq!(due(&mut tracker, &stranded, &settings, at(11)), vec![1]);
+        // Tried once; left alone while it stays off-screen
+        assert!(due(&mut tracker, &stranded, &settings, at(30)).is_empty());
+    }
+
+    #[test]
+    fn a_window_back_on_screen_in_time_is_not_moved() {
+        let settings = settings(10, &[]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut tracker = RescueTracker::default();
+        due(&mut tracker, &[onscreen(1, "excel.exe")], &settings, start);
+
+        due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, at(1));
+        due(&mut tracker, &[onscreen(1, "excel.exe")], &settings, at(5));
+        // The clock starts over when it goes off-screen again
+        assert!(due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, at(6)).is_empty());
+        assert!(due(
+            &mut tracker,
+            &[offscreen(1, "excel.exe")],
+            &settings,
+            at(12)
+        )
+        .is_empty());
+        assert_eq!(
+            due(
+                &mut tracker,
+                &[offscreen(1, "excel.exe")],
+                &settings,
+                at(16)
+            ),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn windows_off_screen_at_start_are_left_alone() {
+        let settings = settings(0, &[]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut tracker = RescueTracker::default();
+
+        let windows = [offscreen(1, "excel.exe"), onscreen(2, "winword.exe")];
+        assert!(due(&mut tracker, &windows, &settings, start).is_empty());
+        assert!(due(&mut tracker, &windows, &settings, at(60)).is_empty());
+
+        // Others going off-screen later still are rescued
+        let windows = [offscreen(1, "excel.exe"), offscreen(2, "winword.exe")];
+        assert_eq!(due(&mut tracker, &windows, &settings, at(61)), vec![2]);
+    }
+
+    #[test]
+    fn windows_are_retried_after_coming_back() {
+        let settings = settings(0, &[]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut tracker = RescueTracker::default();
+        due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, start);
+
+        // Off-screen since the start, so only after it has been back
+        assert!(due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, at(1)).is_empty());
+        due(&mut tracker, &[onscreen(1, "excel.exe")], &settings, at(2));
+        assert_eq!(
+            due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, at(3)),
+            vec![1]
+        );
+        assert!(due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, at(4)).is_empty());
+
+        due(&mut tracker, &[onscreen(1, "excel.exe")], &settings, at(5));
+        assert_eq!(
+            due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, at(6)),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn closed_windows_are_forgotten() {
+        let settings = settings(0, &[]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut tracker = RescueTracker::default();
+        due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, start);
+        due(&mut tracker, &[], &settings, at(1));
+
+        // A new window that got the same handle
+        assert_eq!(
+            due(&mut tracker, &[offscreen(1, "excel.exe")], &settings, at(2)),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn excluded_processes_match_with_or_without_exe() {
+        for excluded in ["teams", "Teams.exe", "TEAMS.EXE"] {
+            let settings = settings(0, &[excluded]);
+            assert!(is_excluded(&offscreen(1, "Teams.exe"), &settings));
+            assert!(is_excluded(&offscreen(1, "teams"), &settings));
+            assert!(!is_excluded(&offscreen(1, "teamsupdate.exe"), &settings));
+        }
+    }
+
+    #[test]
+    fn excluded_windows_stay_off_screen() {
+        let settings = settings(0, &["teams"]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut tracker = RescueTracker::default();
+        let windows = [onscreen(1, "Teams.exe"), onscreen(2, "excel.exe")];
+        due(&mut tracker, &windows, &settings, start);
+
+        let windows = [offscreen(1, "Teams.exe"), offscreen(2, "excel.exe")];
+        assert_eq!(due(&mut tracker, &windows, &settings, at(1)), vec![2]);
+        assert!(due(&mut tracker, &windows, &settings, at(2)).is_empty());
+    }
+}
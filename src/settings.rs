@@ -3,12 +3,18 @@
 use crate::types::AppSettings;
 use std::fs;
 use std::path::PathBuf;
+use tracing::warn;
 
 /// Get the settings file path
 pub fn settings_path() -> Option<PathBuf> {
     dirs::config_dir().map(|p| p.join("WindowLasso").join("settings.json"))
 }
 
+/// Get the folder saved window layouts are kept in
+pub fn layouts_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("WindowLasso").join("layouts"))
+}
+
 /// Load settings from disk
 pub fn load_settings() -> AppSettings {
     let path = match settings_path() {
@@ -17,7 +23,9 @@ pub fn load_settings() -> AppSettings {
     };
 
     match fs::read_to_string(&path) {
-        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| warn!("Failed to parse settings {}: {}", path.display(), e))
+            .unwrap_or_default(),
         Err(_) => AppSettings::default(),
     }
 }
@@ -0,0 +1,822 @@
+//! Monitor identity and topology
+//!
+//! Display enumeration order and handles change whenever cables are replugged,
+//! so anything that refers to a monitor across sessions uses the stable ID
+//! computed here instead. Pure code; backends only supply the raw inputs.
+
+use crate::types::MonitorInfo;
+use serde::{Deserialize, Serialize};
+
+/// Identity fields read from a monitor's EDID block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edid {
+    /// Three-letter PNP manufacturer ID (e.g. "DEL")
+    pub manufacturer: String,
+    pub product_code: u16,
+    /// Numeric serial; many monitors leave this as 0
+    pub serial_number: u32,
+    /// Serial number string descriptor, if present
+    pub serial_text: Option<String>,
+    /// Monitor name descriptor, if present
+    pub model_name: Option<String>,
+}
+
+impl Edid {
+    /// Whether the EDID carries a serial that tells identical models apart
+    pub fn has_serial(&self) -> bool {
+        self.serial_number != 0 || self.serial_text.is_some()
+    }
+}
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+const EDID_BLOCK_LEN: usize = 128;
+
+/// Offsets of the four 18-byte display descriptors in the base block
+const DESCRIPTOR_OFFSETS: [usize; 4] = [54, 72, 90, 108];
+const DESCRIPTOR_SERIAL: u8 = 0xFF;
+const DESCRIPTOR_NAME: u8 = 0xFC;
+
+/// Parse the identity fields from an EDID base block
+///
+/// Returns `None` if the data is too short or lacks the EDID header.
+pub fn parse_edid(data: &[u8]) -> Option<Edid> {
+    if data.len() < EDID_BLOCK_LEN || data[..8] != EDID_HEADER {
+        return None;
+    }
+
+    // Manufacturer ID: three 5-bit letters, big-endian, 'A' = 1
+    let packed = u16::from_be_bytes([data[8], data[9]]);
+    let manufacturer = [10, 5, 0]
+        .iter()
+        .map(|shift| (b'A' - 1 + ((packed >> shift) & 0x1F) as u8) as char)
+        .collect();
+
+    let product_code = u16::from_le_bytes([data[10], data[11]]);
+    let serial_number = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+
+    let mut serial_text = None;
+    let mut model_name = None;
+    for offset in DESCRIPTOR_OFFSETS {
+        let descriptor = &data[offset..offset + 18];
+        // Display descriptors start with a zero pixel clock
+        if descriptor[0] != 0 || descriptor[1] != 0 {
+            continue;
+        }
+        match descriptor[3] {
+            DESCRIPTOR_SERIAL => serial_text = descriptor_text(&descriptor[5..]),
+            DESCRIPTOR_NAME => model_name = descriptor_text(&descriptor[5..]),
+            _ => {}
+        }
+    }
+
+    Some(Edid {
+        manufacturer,
+        product_code,
+        serial_number,
+        serial_text,
+        model_name,
+    })
+}
+
+/// Decode a descriptor string (terminated by a newline, padded with spaces)
+fn descriptor_text(bytes: &[u8]) -> Option<String> {
+    let end = bytes
+        .iter()
+        .position(|&b| b == b'\n')
+        .unwrap_or(bytes.len());
+    let text = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Compute a monitor's stable ID
+///
+/// An EDID serial identifies the physical screen on any port. Without one,
+/// the make and model are combined with the device path so two identical
+/// monitors stay apart. Without an EDID, the device path and resolution are
+/// all there is.
+pub fn stable_monitor_id(
+    device_path: &str,
+    edid: Option<&Edid>,
+    width: i32,
+    height: i32,
+) -> String {
+    let key = match edid {
+        Some(edid) if edid.has_serial() => format!(
+            "edid:{}:{:04X}:{}:{}",
+            edid.manufacturer,
+            edid.product_code,
+            edid.serial_number,
+            edid.serial_text.as_deref().unwrap_or_default()
+        ),
+        Some(edid) => format!(
+            "edid:{}:{:04X}:{}",
+            edid.manufacturer,
+            edid.product_code,
+            device_path.to_lowercase()
+        ),
+        None => format!("path:{}:{}x{}", device_path.to_lowercase(), width, height),
+    };
+
+    format!("{:016x}", fnv1a(key.as_bytes()))
+}
+
+/// Fingerprint of the whole monitor arrangement
+///
+/// Stays the same as long as the same monitors are connected in the same
+/// positions, regardless of enumeration order.
+pub fn topology_fingerprint(monitors: &[MonitorInfo]) -> String {
+    let mut entries: Vec<String> = monitors
+        .iter()
+        .map(|m| {
+            format!(
+                "{}@{},{},{},{}",
+                m.stable_id, m.bounds.left, m.bounds.top, m.bounds.right, m.bounds.bottom
+            )
+        })
+        .collect();
+    entries.sort();
+
+    format!("{:016x}", fnv1a(entries.join(";").as_bytes()))
+}
+
+/// How the connected monitors differ between two enumerations
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopologyChange {
+    pub added: Vec<MonitorInfo>,
+    pub removed: Vec<MonitorInfo>,
+    /// Monitors still connected that moved, changed resolution or scaling, or
+    /// became or stopped being primary, as (before, after)
+    pub changed: Vec<(MonitorInfo, MonitorInfo)>,
+}
+
+impl TopologyChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Whether a different monitor is primary now
+    pub fn primary_changed(&self) -> bool {
+        self.changed
+            .iter()
+            .any(|(before, after)| before.is_primary != after.is_primary)
+            || self.added.iter().any(|m| m.is_primary)
+            || self.removed.iter().any(|m| m.is_primary)
+    }
+}
+
+/// Compare two monitor lists, matching monitors by stable ID
+///
+/// Monitors left over are matched by device path, since a monitor without an
+/// EDID gets a new ID when its resolution changes. Work area changes alone,
+/// such as the taskbar being resized, don't count.
+pub fn diff_monitors(before: &[MonitorInfo], after: &[MonitorInfo]) -> TopologyChange {
+    let mut unmatched: Vec<&MonitorInfo> = before.iter().collect();
+    let mut previous: Vec<Option<&MonitorInfo>> = after
+        .iter()
+        .map(|monitor| take_match(&mut unmatched, |old| old.stable_id == monitor.stable_id))
+        .collect();
+    for (monitor, previous) in after.iter().zip(previous.iter_mut()) {
+        if previous.is_none() {
+            *previous = take_match(&mut unmatched, |old| old.device_path == monitor.device_path);
+        }
+    }
+
+    let mut change = TopologyChange {
+        removed: unmatched.into_iter().cloned().collect(),
+        ..Default::default()
+    };
+    for (monitor, previous) in after.iter().zip(previous) {
+        match previous {
+            None => change.added.push(monitor.clone()),
+            Some(old)
+                if old.bounds != monitor.bounds
+                    || old.dpi != monitor.dpi
+                    || old.is_primary != monitor.is_primary =>
+            {
+                change.changed.push((old.clone(), monitor.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    change
+}
+
+/// Remove and return the first monitor that matches
+fn take_match<'a>(
+    monitors: &mut Vec<&'a MonitorInfo>,
+    matches: impl Fn(&MonitorInfo) -> bool,
+) -> Option<&'a MonitorInfo> {
+    let index = monitors.iter().position(|m| matches(m))?;
+    Some(monitors.remove(index))
+}
+
+/// A monitor named in settings, independent of enumeration order
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorTarget {
+    /// 1-based position, counting left to right (then top to bottom)
+    Position(usize),
+    /// Stable monitor ID
+    Id(String),
+}
+
+impl MonitorTarget {
+    /// Find the connected monitor this target refers to
+    pub fn resolve<'a>(&self, monitors: &'a [MonitorInfo]) -> Option<&'a MonitorInfo> {
+        match self {
+            MonitorTarget::Position(position) => position
+                .checked_sub(1)
+                .and_then(|index| monitors_by_position(monitors).get(index).copied()),
+            MonitorTarget::Id(id) => monitors.iter().find(|m| m.stable_id == *id),
+        }
+    }
+}
+
+/// Find a monitor the user named: a position, a stable ID or a name
+///
+/// Names are the nickname or generated name, or the device name, ignoring case.
+pub fn find_monitor<'a>(query: &str, monitors: &'a [MonitorInfo]) -> Option<&'a MonitorInfo> {
+    // An ID can be all digits, so a number that is no position may be one
+    let by_position = query
+        .parse()
+        .ok()
+        .and_then(|position| MonitorTarget::Position(position).resolve(monitors));
+
+    by_position
+        .or_else(|| MonitorTarget::Id(query.to_string()).resolve(monitors))
+        .or_else(|| {
+            monitors.iter().find(|m| {
+                m.display_name().eq_ignore_ascii_case(query)
+                    || m.device_name.eq_ignore_ascii_case(query)
+            })
+        })
+}
+
+/// Monitors sorted left to right, then top to bottom
+pub fn monitors_by_position(monitors: &[MonitorInfo]) -> Vec<&MonitorInfo> {
+    let mut sorted: Vec<&MonitorInfo> = monitors.iter().collect();
+    sorted.sort_by_key(|m| (m.bounds.left, m.bounds.top));
+    sorted
+}
+
+/// Direction from one monitor to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub fn all() -> &'static [Direction] {
+        &[
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ]
+    }
+
+    /// Name used in settings
+    pub fn id(self) -> &'static str {
+        match self {
+            Direction::Left => "left",
+            Direction::Right => "right",
+            Direction::Up => "up",
+            Direction::Down => "down",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Direction> {
+        Direction::all().iter().copied().find(|d| d.id() == id)
+    }
+}
+
+/// Find the nearest monitor in a direction from `current`
+///
+/// Candidates must have their center beyond the current monitor's center in
+/// that direction. Monitors that share a stretch of edge with the current one
+/// win over diagonal neighbours; ties go to the smallest gap, then to the
+/// smallest sideways offset. This keeps staggered and overlapping layouts
+/// predictable.
+pub fn monitor_in_direction<'a>(
+    current: &MonitorInfo,
+    monitors: &'a [MonitorInfo],
+    direction: Direction,
+) -> Option<&'a MonitorInfo> {
+    let from = &current.bounds;
+    let (from_x, from_y) = from.center();
+
+    monitors
+        .iter()
+        .filter(|m| m.bounds != current.bounds)
+        .filter_map(|m| {
+            let to = &m.bounds;
+            let (to_x, to_y) = to.center();
+
+            // Distance along the direction, gap between facing edges, and
+            // overlap/offset across it
+            let (along, gap, overlap, offset) = match direction {
+                Direction::Left => (
+                    from_x - to_x,
+                    from.left - to.right,
+                    span_overlap(from.top, from.bottom, to.top, to.bottom),
+                    (from_y - to_y).abs(),
+                ),
+                Direction::Right => (
+                    to_x - from_x,
+                    to.left - from.right,
+                    span_overlap(from.top, from.bottom, to.top, to.bottom),
+                    (from_y - to_y).abs(),
+                ),
+                Direction::Up => (
+                    from_y - to_y,
+                    from.top - to.bottom,
+                    span_overlap(from.left, from.right, to.left, to.right),
+                    (from_x - to_x).abs(),
+                ),
+                Direction::Down => (
+                    to_y - from_y,
+                    to.top - from.bottom,
+                    span_overlap(from.left, from.right, to.left, to.right),
+                    (from_x - to_x).abs(),
+                ),
+            };
+
+            (along > 0).then_some(((overlap <= 0, gap.max(0), offset, along), m))
+        })
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, m)| m)
+}
+
+/// Length shared by two ranges on the same axis (negative if they are apart)
+fn span_overlap(a_start: i32, a_end: i32, b_start: i32, b_end: i32) -> i32 {
+    a_end.min(b_end) - a_start.max(b_start)
+}
+
+/// 64-bit FNV-1a hash
+///
+/// Used instead of `DefaultHasher` because IDs are persisted and must not
+/// change between builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::{monitor, rect};
+    use crate::types::WindowRect;
+    use Direction::{Down, Left, Right, Up};
+
+    /// Two side-by-side monitors, the left one primary
+    fn desk() -> Vec<MonitorInfo> {
+        vec![
+            monitor(0, rect(0, 0, 1920, 1080), 96),
+            monitor(1, rect(1920, 0, 4480, 1440), 144),
+        ]
+    }
+
+    /// A monitor without an EDID, whose ID depends on its resolution
+    fn without_edid(mut monitor: MonitorInfo) -> MonitorInfo {
+        monitor.stable_id = stable_monitor_id(
+            &monitor.device_path,
+            None,
+            monitor.bounds.width(),
+            monitor.bounds.height(),
+        );
+        monitor
+    }
+
+    #[test]
+    fn the_same_monitors_are_no_change() {
+        let mut after = desk();
+        after.reverse();
+        after[0].work_area.bottom -= 40;
+        assert!(diff_monitors(&desk(), &after).is_empty());
+    }
+
+    #[test]
+    fn connected_monitors_are_added() {
+        let mut after = desk();
+        after.push(monitor(2, rect(-1920, 0, 0, 1080), 96));
+
+        let change = diff_monitors(&desk(), &after);
+        assert_eq!(change.added, vec![after[2].clone()]);
+        assert!(change.removed.is_empty() && change.changed.is_empty());
+        assert!(!change.primary_changed());
+    }
+
+    #[test]
+    fn disconnected_monitors_are_removed() {
+        let before = desk();
+        let change = diff_monitors(&before, &before[..1]);
+        assert_eq!(change.removed, vec![before[1].clone()]);
+        assert!(change.added.is_empty() && change.changed.is_empty());
+        assert!(!change.primary_changed());
+    }
+
+    #[test]
+    fn moved_and_rescaled_monitors_are_changed() {
+        let before = desk();
+        let mut after = desk();
+        after[1].bounds = rect(0, -1440, 2560, 0);
+        after[1].dpi = 96;
+
+        let change = diff_monitors(&before, &after);
+        assert_eq!(change.changed, vec![(before[1].clone(), after[1].clone())]);
+        assert!(change.added.is_empty() && change.removed.is_empty());
+        assert!(!change.primary_changed());
+    }
+
+    #[test]
+    fn a_new_primary_is_a_change() {
+        let before = desk();
+        let mut after = desk();
+        after[0].is_primary = false;
+        after[1].is_primary = true;
+
+        let change = diff_monitors(&before, &after);
+        assert_eq!(change.changed.len(), 2);
+        assert!(change.primary_changed());
+
+        // Losing the primary monitor counts too
+        assert!(diff_monitors(&before, &before[1..]).primary_changed());
+    }
+
+    #[test]
+    fn monitors_without_edid_keep_their_identity_across_resolutions() {
+        let before: Vec<MonitorInfo> = desk().into_iter().map(without_edid).collect();
+        let mut after = before.clone();
+        after[1].bounds = rect(1920, 0, 5760, 2160);
+        after[1] = without_edid(after[1].clone());
+        assert_ne!(after[1].stable_id, before[1].stable_id);
+
+        let change = diff_monitors(&before, &after);
+        assert_eq!(change.changed, vec![(before[1].clone(), after[1].clone())]);
+        assert!(change.added.is_empty() && change.removed.is_empty());
+    }
+
+    #[test]
+    fn matching_ids_win_over_device_paths() {
+        let before = desk();
+        // Cables swapped between ports
+        let mut after = desk();
+        after[0].device_path = before[1].device_path.clone();
+        after[1].device_path = before[0].device_path.clone();
+        assert!(diff_monitors(&before, &after).is_empty());
+    }
+
+    /// Base block of a Dell U2720Q: a 3840x2160 timing, then serial, name
+    /// and range limit descriptors
+    const DELL_EDID: [u8; 128] = [
+        0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x10, 0xAC, 0xF4, 0x40, 0x33, 0x32, 0x4B,
+        0x4C, 0x0C, 0x1E, 0x01, 0x04, 0xB5, 0x3C, 0x22, 0x78, 0x3A, 0x2C, 0xB5, 0xAF, 0x4F, 0x47,
+        0xAB, 0x25, 0x0F, 0x50, 0x54, 0xA5, 0x4B, 0x00, 0xD1, 0xC0, 0xA9, 0xC0, 0x81, 0x80, 0x81,
+        0x00, 0x71, 0x4F, 0xE1, 0xC0, 0x01, 0x01, 0x01, 0x01, 0x4D, 0xD0, 0x00, 0xA0, 0xF0, 0x70,
+        0x3E, 0x80, 0x30, 0x20, 0x35, 0x00, 0x54, 0x4F, 0x21, 0x00, 0x00, 0x1A, 0x00, 0x00, 0x00,
+        0xFF, 0x00, 0x37, 0x4B, 0x33, 0x43, 0x59, 0x32, 0x33, 0x0A, 0x20, 0x20, 0x20, 0x20, 0x20,
+        0x00, 0x00, 0x00, 0xFC, 0x00, 0x44, 0x45, 0x4C, 0x4C, 0x20, 0x55, 0x32, 0x37, 0x32, 0x30,
+        0x51, 0x0A, 0x20, 0x00, 0x00, 0x00, 0xFD, 0x00, 0x18, 0x4B, 0x1E, 0x8C, 0x36, 0x01, 0x0A,
+        0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x01, 0x2B,
+    ];
+
+    const DELL_PATH: &str = r"\\?\DISPLAY#DEL40F4#5&2a3b4c&0&UID4352";
+
+    /// The Dell's identity without its serial, like many cheaper monitors
+    fn without_serial() -> Edid {
+        Edid {
+            serial_number: 0,
+            serial_text: None,
+            ..parse_edid(&DELL_EDID).unwrap()
+        }
+    }
+
+    #[test]
+    fn edid_identity_is_read() {
+        assert_eq!(
+            parse_edid(&DELL_EDID),
+            Some(Edid {
+                manufacturer: "DEL".to_string(),
+                product_code: 0x40F4,
+                serial_number: 0x4C4B_3233,
+                serial_text: Some("7K3CY23".to_string()),
+                model_name: Some("DELL U2720Q".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn edid_extension_blocks_are_ignored() {
+        let mut data = DELL_EDID.to_vec();
+        data.extend([0x02; 128]);
+        assert_eq!(parse_edid(&data), parse_edid(&DELL_EDID));
+    }
+
+    #[test]
+    fn edid_descriptors_are_optional() {
+        let mut data = DELL_EDID;
+        // Turn the serial and name descriptors into dummy descriptors
+        data[72 + 3] = 0x10;
+        data[90 + 3] = 0x10;
+        let edid = parse_edid(&data).unwrap();
+        assert_eq!(edid.serial_text, None);
+        assert_eq!(edid.model_name, None);
+        assert!(edid.has_serial());
+        assert!(!without_serial().has_serial());
+    }
+
+    #[test]
+    fn invalid_edid_is_rejected() {
+        assert_eq!(parse_edid(&[]), None);
+        assert_eq!(parse_edid(&DELL_EDID[..127]), None);
+        let mut data = DELL_EDID;
+        data[0] = 0xFF;
+        assert_eq!(parse_edid(&data), None);
+        assert_eq!(parse_edid(&[0xFF; 128]), None);
+    }
+
+    #[test]
+    fn monitor_ids_are_stable_across_builds() {
+        // Saved in settings and layouts; changing the key or the hash loses them
+        let edid = parse_edid(&DELL_EDID).unwrap();
+        assert_eq!(
+            stable_monitor_id(DELL_PATH, Some(&edid), 3840, 2160),
+            "cd35030f3d0ecac2"
+        );
+        assert_eq!(
+            stable_monitor_id(DELL_PATH, None, 1920, 1080),
+            "014a3239a38954e5"
+        );
+        // Layouts are found by this
+        assert_eq!(topology_fingerprint(&desk()), "7d9fc0cd22d779e3");
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn a_serial_keeps_its_id_on_any_port() {
+        let edid = parse_edid(&DELL_EDID).unwrap();
+        let docked = stable_monitor_id(DELL_PATH, Some(&edid), 3840, 2160);
+        let other_port = stable_monitor_id(
+            r"\\?\DISPLAY#DEL40F4#5&1f2e3d&0&UID4357",
+            Some(&edid),
+            2560,
+            1440,
+        );
+        assert_eq!(docked, other_port);
+    }
+
+    #[test]
+    fn identical_monitors_without_serial_stay_apart() {
+        let edid = without_serial();
+        let left = stable_monitor_id(
+            r"\\?\DISPLAY#DEL40F4#5&2a3b4c&0&UID4352",
+            Some(&edid),
+            3840,
+            2160,
+        );
+        let right = stable_monitor_id(
+            r"\\?\DISPLAY#DEL40F4#5&2a3b4c&0&UID4353",
+            Some(&edid),
+            3840,
+            2160,
+        );
+        assert_ne!(left, right);
+        // Device paths differ only in case between some APIs
+        assert_eq!(
+            left,
+            stable_monitor_id(&DELL_PATH.to_uppercase(), Some(&edid), 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn without_edid_the_resolution_is_part_of_the_id() {
+        assert_ne!(
+            stable_monitor_id(DELL_PATH, None, 3840, 2160),
+            stable_monitor_id(DELL_PATH, None, 1920, 1080)
+        );
+    }
+
+    #[test]
+    fn fingerprints_ignore_enumeration_order() {
+        let monitors = desk();
+        let mut reversed = desk();
+        reversed.reverse();
+        reversed[0].handle = 99;
+        assert_eq!(
+            topology_fingerprint(&monitors),
+            topology_fingerprint(&reversed)
+        );
+    }
+
+    #[test]
+    fn fingerprints_change_with_the_arrangement() {
+        let fingerprint = topology_fingerprint(&desk());
+
+        let mut moved = desk();
+        moved[1].bounds = rect(-2560, 0, 0, 1440);
+        assert_ne!(topology_fingerprint(&moved), fingerprint);
+
+        let mut replaced = desk();
+        replaced[1].stable_id = "MON9".to_string();
+        assert_ne!(topology_fingerprint(&replaced), fingerprint);
+
+        assert_ne!(topology_fingerprint(&desk()[..1]), fingerprint);
+        // The taskbar doesn't matter
+        let mut taskbar = desk();
+        taskbar[0].work_area.bottom = 1040;
+        assert_eq!(topology_fingerprint(&taskbar), fingerprint);
+    }
+
+    /// Stable ID of the monitor a query finds
+    fn found(query: &str, monitors: &[MonitorInfo]) -> Option<String> {
+        find_monitor(query, monitors).map(|m| m.stable_id.clone())
+    }
+
+    #[test]
+    fn monitors_are_found_by_position_id_or_name() {
+        let mut monitors = desk();
+        monitors[1].nickname = Some("Wide".to_string());
+
+        assert_eq!(found("1", &monitors).as_deref(), Some("MON1"));
+        assert_eq!(found("2", &monitors).as_deref(), Some("MON2"));
+        assert_eq!(found("MON2", &monitors).as_deref(), Some("MON2"));
+        assert_eq!(found("wide", &monitors).as_deref(), Some("MON2"));
+        assert_eq!(found("display2", &monitors).as_deref(), Some("MON2"));
+        assert_eq!(found("3", &monitors), None);
+        assert_eq!(found("Tall", &monitors), None);
+    }
+
+    #[test]
+    fn ids_made_only_of_digits_are_found() {
+        let mut monitors = desk();
+        monitors[1].stable_id = "4817203955106628".to_string();
+
+        assert_eq!(
+            found("4817203955106628", &monitors).as_deref(),
+            Some("4817203955106628")
+        );
+        // Small numbers are still positions
+        assert_eq!(found("1", &monitors).as_deref(), Some("MON1"));
+    }
+
+    /// Monitor reached from `from` in a direction, by index
+    fn neighbour(bounds: &[WindowRect], from: usize, direction: Direction) -> Option<usize> {
+        let monitors: Vec<MonitorInfo> = bounds
+            .iter()
+            .enumerate()
+            .map(|(index, bounds)| monitor(index, *bounds, 96))
+            .collect();
+        monitor_in_direction(&monitors[from], &monitors, direction).map(|m| m.display_index)
+    }
+
+    /// Check (from, direction, expected) cases against an arrangement
+    fn check(bounds: &[WindowRect], cases: &[(usize, Direction, Option<usize>)]) {
+        for &(from, direction, expected) in cases {
+            assert_eq!(
+                neighbour(bounds, from, direction),
+                expected,
+                "{:?} from {}",
+                direction,
+                from
+            );
+        }
+    }
+
+    #[test]
+    fn side_by_side() {
+        let bounds = [rect(0, 0, 1920, 1080), rect(1920, 0, 3840, 1080)];
+        check(
+            &bounds,
+            &[
+                (0, Right, Some(1)),
+                (1, Left, Some(0)),
+                (0, Left, None),
+                (1, Right, None),
+                (0, Up, None),
+                (0, Down, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn two_by_two_grid() {
+        let bounds = [
+            rect(0, 0, 1920, 1080),
+            rect(1920, 0, 3840, 1080),
+            rect(0, 1080, 1920, 2160),
+            rect(1920, 1080, 3840, 2160),
+        ];
+        // Straight across, never to the diagonal corner
+        check(
+            &bounds,
+            &[
+                (0, Right, Some(1)),
+                (0, Down, Some(2)),
+                (1, Left, Some(0)),
+                (1, Down, Some(3)),
+                (2, Up, Some(0)),
+                (2, Right, Some(3)),
+                (3, Up, Some(1)),
+                (3, Left, Some(2)),
+                (0, Up, None),
+                (3, Right, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn staggered_monitors_with_a_small_overlap() {
+        let bounds = [
+            rect(0, 0, 1920, 1080),
+            // Sits lower, sharing only 80 pixels of edge with the first
+            rect(1920, 1000, 3840, 2080),
+            rect(0, 1080, 1920, 2160),
+        ];
+        check(
+            &bounds,
+            &[
+                (0, Right, Some(1)),
+                // Mostly beside the lower one, so that one is to its left
+                (1, Left, Some(2)),
+                // The monitor straight below wins over the staggered one
+                (0, Down, Some(2)),
+                (2, Right, Some(1)),
+                (2, Up, Some(0)),
+            ],
+        );
+    }
+
+    #[test]
+    fn the_neighbour_sharing_more_edge_wins() {
+        let bounds = [
+            rect(0, 0, 1920, 1080),
+            rect(1920, -300, 3840, 780),
+            rect(1920, 780, 3840, 1860),
+            rect(3840, 0, 5760, 1080),
+        ];
+        check(
+            &bounds,
+            &[
+                // Both share an edge and touch; the one less offset wins
+                (0, Right, Some(1)),
+                // Touching wins over lining up
+                (1, Right, Some(3)),
+                (3, Left, Some(1)),
+            ],
+        );
+    }
+
+    #[test]
+    fn diagonal_neighbours_are_reached() {
+        let bounds = [rect(0, 0, 1920, 1080), rect(1920, 1080, 3840, 2160)];
+        check(
+            &bounds,
+            &[
+                (0, Right, Some(1)),
+                (0, Down, Some(1)),
+                (1, Left, Some(0)),
+                (1, Up, Some(0)),
+                (0, Left, None),
+                (0, Up, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn overlapping_monitors() {
+        let bounds = [rect(0, 0, 1920, 1080), rect(960, 0, 2880, 1080)];
+        check(
+            &bounds,
+            &[
+                (0, Right, Some(1)),
+                (1, Left, Some(0)),
+                (0, Left, None),
+                (0, Down, None),
+            ],
+        );
+    }
+
+    #[test]
+    fn mirrored_monitors_lead_nowhere() {
+        let bounds = [rect(0, 0, 1920, 1080), rect(0, 0, 1920, 1080)];
+        for direction in Direction::all() {
+            assert_eq!(neighbour(&bounds, 0, *direction), None);
+        }
+    }
+
+    #[test]
+    fn directions_round_trip_through_their_ids() {
+        for direction in Direction::all() {
+            assert_eq!(Direction::from_id(direction.id()), Some(*direction));
+        }
+        assert_eq!(Direction::from_id("sideways"), None);
+    }
+}
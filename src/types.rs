@@ -1,6 +1,15 @@
 //! Shared type definitions for WindowLasso
 
-use serde::{Deserialize, Serialize};
+use crate::keycodes;
+use crate::placement::{PlacementMode, PlacementPolicy, ScalingMode};
+use crate::topology::{Direction, MonitorTarget};
+use crate::zones::{CustomZone, SnapZone};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use tracing::warn;
 
 /// Application version (read from Cargo.toml at compile time)
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -22,13 +31,14 @@ pub struct WindowInfo {
     pub is_visible: bool,
     pub is_offscreen: bool,
     pub is_minimized: bool,
+    pub is_maximized: bool,
     pub monitor_name: Option<String>,
     pub icon_rgba: Option<Vec<u8>>,
     pub icon_size: u32,
 }
 
 /// Window rectangle/bounds
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub struct WindowRect {
     pub left: i32,
     pub top: i32,
@@ -63,16 +73,35 @@ pub struct MonitorInfo {
     pub handle: isize,
     pub name: String,
     pub device_name: String,
+    /// Hardware path of the display; unlike the stable ID it doesn't change
+    /// with the resolution when the monitor has no EDID
+    pub device_path: String,
     pub bounds: WindowRect,
     pub work_area: WindowRect,
     pub is_primary: bool,
     pub display_index: usize,
+    /// Identifier that survives reconnects and reboots (see `topology`)
+    pub stable_id: String,
+    /// User-chosen name, applied from settings
+    pub nickname: Option<String>,
+    /// Effective DPI (96 = 100% scaling)
+    pub dpi: u32,
 }
 
 impl MonitorInfo {
     pub fn center(&self) -> (i32, i32) {
         self.work_area.center()
     }
+
+    /// Name shown to the user: the nickname if set, otherwise the generated name
+    pub fn display_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Display scaling as a percentage (100 = 96 DPI)
+    pub fn scale_percent(&self) -> u32 {
+        crate::placement::dpi_to_percent(self.dpi)
+    }
 }
 
 /// Application settings
@@ -83,6 +112,27 @@ pub struct AppSettings {
     pub auto_focus_after_lasso: bool,
     #[serde(default)]
     pub close_after_recovery: bool,
+    /// Where windows land when moved between monitors
+    #[serde(default)]
+    pub placement_mode: PlacementMode,
+    /// How window size is adjusted between monitors
+    #[serde(default)]
+    pub scaling_mode: ScalingMode,
+    /// User-chosen monitor names, keyed by stable monitor ID
+    #[serde(default)]
+    pub monitor_nicknames: BTreeMap<String, String>,
+    /// User-defined snap zones
+    #[serde(default)]
+    pub custom_zones: Vec<CustomZone>,
+    /// Accept JSON-RPC requests from local automation tools
+    #[serde(default)]
+    pub api_enabled: bool,
+    /// Moving windows back on their own when they end up off-screen
+    #[serde(default)]
+    pub auto_rescue: AutoRescueSettings,
+    /// What to do when monitors are connected, disconnected or rearranged
+    #[serde(default)]
+    pub on_monitors_changed: TopologyAction,
     pub hotkeys: HotkeySettings,
     pub theme: ThemeSettings,
 }
@@ -94,71 +144,356 @@ impl Default for AppSettings {
             minimize_to_tray: None, // None = not yet asked
             auto_focus_after_lasso: true,
             close_after_recovery: false,
+            placement_mode: PlacementMode::default(),
+            scaling_mode: ScalingMode::default(),
+            monitor_nicknames: BTreeMap::new(),
+            custom_zones: Vec::new(),
+            api_enabled: false,
+            auto_rescue: AutoRescueSettings::default(),
+            on_monitors_changed: TopologyAction::default(),
             hotkeys: HotkeySettings::default(),
             theme: ThemeSettings::default(),
         }
     }
 }
 
+impl AppSettings {
+    /// Placement policy for moves, based on the user's choices
+    pub fn placement_policy(&self, maximize: bool) -> PlacementPolicy {
+        PlacementPolicy {
+            mode: self.placement_mode,
+            scaling: self.scaling_mode,
+            maximize,
+            target: None,
+        }
+    }
+}
+
 /// Hotkey configuration
+///
+/// Each action can have any number of bindings. Actions missing from the
+/// stored settings get their default bindings, so actions added in a new
+/// version show up bound; an empty list keeps an action unbound.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredHotkeySettings")]
 pub struct HotkeySettings {
-    pub lasso_window: HotkeyBinding,
-    pub refresh_windows: HotkeyBinding,
-    pub move_to_primary: HotkeyBinding,
-    #[serde(default = "default_move_all_to_primary")]
-    pub move_all_to_primary: HotkeyBinding,
-    #[serde(default = "default_center_window")]
-    pub center_window: HotkeyBinding,
-    #[serde(default = "default_next_monitor")]
-    pub next_monitor: HotkeyBinding,
+    pub bindings: BTreeMap<HotkeyAction, Vec<HotkeyBinding>>,
+    /// Actions picked by the key pressed after the leader chord, keyed by key name
+    pub leader_keys: BTreeMap<String, HotkeyAction>,
+    /// How long to wait for the key after the leader chord
+    pub leader_timeout_ms: u64,
 }
 
-fn default_move_all_to_primary() -> HotkeyBinding {
-    HotkeyBinding {
-        modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
-        key: "A".to_string(),
-        enabled: true,
+impl HotkeySettings {
+    /// Bindings for an action, empty if it has none
+    pub fn bindings_for(&self, action: &HotkeyAction) -> &[HotkeyBinding] {
+        self.bindings
+            .get(action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
     }
-}
 
-fn default_center_window() -> HotkeyBinding {
-    HotkeyBinding {
-        modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
-        key: "C".to_string(),
-        enabled: true,
+    /// Replace the binding at `index`, or add a new one if `index` is `None`
+    pub fn set_binding(
+        &mut self,
+        action: HotkeyAction,
+        index: Option<usize>,
+        binding: HotkeyBinding,
+    ) {
+        let bindings = self.bindings.entry(action).or_default();
+        match index.and_then(|index| bindings.get_mut(index)) {
+            Some(existing) => *existing = binding,
+            None => bindings.push(binding),
+        }
+    }
+
+    pub fn set_enabled(&mut self, action: &HotkeyAction, index: usize, enabled: bool) {
+        if let Some(binding) = self
+            .bindings
+            .get_mut(action)
+            .and_then(|bindings| bindings.get_mut(index))
+        {
+            binding.enabled = enabled;
+        }
+    }
+
+    /// Enabled bindings whose chord is already used by an earlier binding
+    ///
+    /// Keyed by (action, binding index), with the action that owns the chord.
+    /// The first binding in action order keeps the chord.
+    pub fn duplicates(&self) -> HashMap<(HotkeyAction, usize), HotkeyAction> {
+        let mut owners: HashMap<Chord, &HotkeyAction> = HashMap::new();
+        let mut duplicates = HashMap::new();
+
+        for (action, bindings) in &self.bindings {
+            for (index, binding) in bindings.iter().enumerate() {
+                if !binding.enabled || binding.key.is_empty() {
+                    continue;
+                }
+                match owners.entry(binding.chord()) {
+                    Entry::Occupied(owner) => {
+                        duplicates.insert((action.clone(), index), (*owner.get()).clone());
+                    }
+                    Entry::Vacant(slot) => {
+                        slot.insert(action);
+                    }
+                }
+            }
+        }
+
+        duplicates
     }
-}
 
-fn default_next_monitor() -> HotkeyBinding {
-    HotkeyBinding {
-        modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
-        key: "N".to_string(),
-        enabled: true,
+    /// Remove a binding; the action stays in the map so defaults aren't restored
+    pub fn remove_binding(&mut self, action: &HotkeyAction, index: usize) {
+        if let Some(bindings) = self.bindings.get_mut(action) {
+            if index < bindings.len() {
+                bindings.remove(index);
+            }
+        }
     }
 }
 
 impl Default for HotkeySettings {
     fn default() -> Self {
         Self {
-            lasso_window: HotkeyBinding {
-                modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
-                key: "L".to_string(),
-                enabled: true,
-            },
-            refresh_windows: HotkeyBinding {
-                modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
-                key: "R".to_string(),
-                enabled: true,
-            },
-            move_to_primary: HotkeyBinding {
-                modifiers: vec!["Ctrl".to_string(), "Alt".to_string()],
-                key: "P".to_string(),
-                enabled: true,
-            },
-            move_all_to_primary: default_move_all_to_primary(),
-            center_window: default_center_window(),
-            next_monitor: default_next_monitor(),
+            bindings: default_bindings(),
+            leader_keys: default_leader_keys(),
+            leader_timeout_ms: DEFAULT_LEADER_TIMEOUT_MS,
+        }
+    }
+}
+
+fn default_bindings() -> BTreeMap<HotkeyAction, Vec<HotkeyBinding>> {
+    const CTRL_ALT: &[&str] = &["Ctrl", "Alt"];
+    const CTRL_ALT_SHIFT: &[&str] = &["Ctrl", "Alt", "Shift"];
+
+    let mut bindings = BTreeMap::from([
+        (
+            HotkeyAction::LassoWindow,
+            vec![HotkeyBinding::new(CTRL_ALT, "L")],
+        ),
+        (
+            HotkeyAction::RefreshWindows,
+            vec![HotkeyBinding::new(CTRL_ALT, "R")],
+        ),
+        (
+            HotkeyAction::MoveToPrimary,
+            vec![HotkeyBinding::new(CTRL_ALT, "P")],
+        ),
+        (
+            HotkeyAction::MoveAllToPrimary,
+            vec![HotkeyBinding::new(CTRL_ALT, "A")],
+        ),
+        (
+            HotkeyAction::CenterWindow,
+            vec![HotkeyBinding::new(CTRL_ALT, "C")],
+        ),
+        (
+            HotkeyAction::NextMonitor,
+            vec![HotkeyBinding::new(CTRL_ALT, "N")],
+        ),
+        (
+            HotkeyAction::PreviousMonitor,
+            vec![HotkeyBinding::new(CTRL_ALT_SHIFT, "N")],
+        ),
+        (
+            HotkeyAction::Leader,
+            vec![HotkeyBinding::new(CTRL_ALT, "W")],
+        ),
+    ]);
+
+    // Ctrl+Alt+1 through Ctrl+Alt+9 for monitors 1-9 in position order, off
+    // by default since AltGr+digit types brackets and symbols on many layouts
+    for position in 1..=9 {
+        bindings.insert(
+            HotkeyAction::SendToMonitor(MonitorTarget::Position(position)),
+            vec![HotkeyBinding::disabled(CTRL_ALT, &position.to_string())],
+        );
+    }
+
+    for (direction, key) in [
+        (Direction::Left, "Left"),
+        (Direction::Right, "Right"),
+        (Direction::Up, "Up"),
+        (Direction::Down, "Down"),
+    ] {
+        bindings.insert(
+            HotkeyAction::MoveInDirection(direction),
+            vec![HotkeyBinding::new(CTRL_ALT_SHIFT, key)],
+        );
+    }
+
+    // Off by default: Ctrl+Alt is AltGr on Windows, which types characters
+    // on many keyboard layouts, and Ctrl+Alt+arrows often rotate the screen.
+    // The leader key reaches the same zones.
+    for (zone, key) in [
+        (SnapZone::Maximize, "Enter"),
+        (SnapZone::LeftHalf, "Left"),
+        (SnapZone::RightHalf, "Right"),
+        (SnapZone::LeftThird, "D"),
+        (SnapZone::CenterThird, "F"),
+        (SnapZone::RightThird, "G"),
+        (SnapZone::TopLeft, "U"),
+        (SnapZone::TopRight, "I"),
+        (SnapZone::BottomLeft, "J"),
+        (SnapZone::BottomRight, "K"),
+    ] {
+        bindings.insert(
+            HotkeyAction::SnapToZone(zone.id().to_string()),
+            vec![HotkeyBinding::disabled(CTRL_ALT, key)],
+        );
+    }
+
+    bindings
+}
+
+const DEFAULT_LEADER_TIMEOUT_MS: u64 = 2000;
+
+fn default_leader_timeout_ms() -> u64 {
+    DEFAULT_LEADER_TIMEOUT_MS
+}
+
+/// After the leader chord: digits pick a monitor, arrows a neighbouring
+/// monitor, and the same letters as the zone hotkeys pick a zone
+fn default_leader_keys() -> BTreeMap<String, HotkeyAction> {
+    let mut keys: BTreeMap<String, HotkeyAction> = (1..=9)
+        .map(|position| {
+            (
+                position.to_string(),
+                HotkeyAction::SendToMonitor(MonitorTarget::Position(position)),
+            )
+        })
+        .collect();
+
+    for (key, direction) in [
+        ("Left", Direction::Left),
+        ("Right", Direction::Right),
+        ("Up", Direction::Up),
+        ("Down", Direction::Down),
+    ] {
+        keys.insert(key.to_string(), HotkeyAction::MoveInDirection(direction));
+    }
+
+    for (key, zone) in [
+        ("Enter", SnapZone::Maximize),
+        ("H", SnapZone::LeftHalf),
+        ("L", SnapZone::RightHalf),
+        ("D", SnapZone::LeftThird),
+        ("F", SnapZone::CenterThird),
+        ("G", SnapZone::RightThird),
+        ("U", SnapZone::TopLeft),
+        ("I", SnapZone::TopRight),
+        ("J", SnapZone::BottomLeft),
+        ("K", SnapZone::BottomRight),
+    ] {
+        keys.insert(
+            key.to_string(),
+            HotkeyAction::SnapToZone(zone.id().to_string()),
+        );
+    }
+
+    keys.insert("C".to_string(), HotkeyAction::CenterWindow);
+    keys.insert("N".to_string(), HotkeyAction::NextMonitor);
+    keys
+}
+
+/// Hotkey settings as found in a settings file
+///
+/// The format is told by whether there is a `bindings` key, so a current file
+/// with one bad binding is never mistaken for an empty legacy one.
+enum StoredHotkeySettings {
+    Current(CurrentHotkeySettings),
+    Legacy(Box<LegacyHotkeySettings>),
+}
+
+impl<'de> Deserialize<'de> for StoredHotkeySettings {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let stored = if value.get("bindings").is_some() {
+            serde_json::from_value(value).map(StoredHotkeySettings::Current)
+        } else {
+            serde_json::from_value(value)
+                .map(|legacy| StoredHotkeySettings::Legacy(Box::new(legacy)))
+        };
+        stored.map_err(serde::de::Error::custom)
+    }
+}
+
+/// Hotkey settings keyed by action
+#[derive(Deserialize)]
+struct CurrentHotkeySettings {
+    /// Keyed by action name; names this version doesn't know are dropped.
+    /// Each action's list is read on its own, so a bad one only loses itself.
+    bindings: BTreeMap<String, serde_json::Value>,
+    leader_keys: Option<BTreeMap<String, String>>,
+    #[serde(default = "default_leader_timeout_ms")]
+    leader_timeout_ms: u64,
+}
+
+/// Hotkey settings from before bindings were keyed by action: one field per
+/// action
+#[derive(Deserialize)]
+struct LegacyHotkeySettings {
+    lasso_window: Option<HotkeyBinding>,
+    refresh_windows: Option<HotkeyBinding>,
+    move_to_primary: Option<HotkeyBinding>,
+    move_all_to_primary: Option<HotkeyBinding>,
+    center_window: Option<HotkeyBinding>,
+    next_monitor: Option<HotkeyBinding>,
+}
+
+impl LegacyHotkeySettings {
+    fn into_bindings(self) -> BTreeMap<HotkeyAction, Vec<HotkeyBinding>> {
+        [
+            (HotkeyAction::LassoWindow, self.lasso_window),
+            (HotkeyAction::RefreshWindows, self.refresh_windows),
+            (HotkeyAction::MoveToPrimary, self.move_to_primary),
+            (HotkeyAction::MoveAllToPrimary, self.move_all_to_primary),
+            (HotkeyAction::CenterWindow, self.center_window),
+            (HotkeyAction::NextMonitor, self.next_monitor),
+        ]
+        .into_iter()
+        .filter_map(|(action, binding)| Some((action, vec![binding?])))
+        .collect()
+    }
+}
+
+impl From<StoredHotkeySettings> for HotkeySettings {
+    fn from(stored: StoredHotkeySettings) -> Self {
+        let (mut bindings, leader_keys, leader_timeout_ms) = match stored {
+            StoredHotkeySettings::Current(current) => (
+                current
+                    .bindings
+                    .into_iter()
+                    .filter_map(|(action, list)| {
+                        let action = action.parse().ok()?;
+                        serde_json::from_value(list)
+                            .map_err(|e| warn!("Ignoring hotkey bindings for {}: {}", action, e))
+                            .ok()
+                            .map(|list| (action, list))
+                    })
+                    .collect(),
+                current.leader_keys.map(|keys| {
+                    keys.into_iter()
+                        .filter_map(|(key, action)| Some((key, action.parse().ok()?)))
+                        .collect()
+                }),
+                current.leader_timeout_ms,
+            ),
+            StoredHotkeySettings::Legacy(legacy) => {
+                (legacy.into_bindings(), None, DEFAULT_LEADER_TIMEOUT_MS)
+            }
+        };
+
+        for (action, defaults) in default_bindings() {
+            bindings.entry(action).or_insert(defaults);
+        }
+
+        Self {
+            bindings,
+            leader_keys: leader_keys.unwrap_or_else(default_leader_keys),
+            leader_timeout_ms,
         }
     }
 }
@@ -171,11 +506,143 @@ pub struct HotkeyBinding {
     pub enabled: bool,
 }
 
+/// Binding with aliases and letter case normalized away
+type Chord = (BTreeSet<String>, String);
+
 impl HotkeyBinding {
-    pub fn display_string(&self) -> String {
-        let mut parts = self.modifiers.clone();
-        parts.push(self.key.clone());
-        parts.join(" + ")
+    /// Create an enabled binding
+    pub fn new(modifiers: &[&str], key: &str) -> Self {
+        Self {
+            modifiers: modifiers.iter().map(|m| m.to_string()).collect(),
+            key: key.to_string(),
+            enabled: true,
+        }
+    }
+
+    /// Create a binding that is shown in Settings but off until the user turns it on
+    pub fn disabled(modifiers: &[&str], key: &str) -> Self {
+        Self {
+            enabled: false,
+            ..Self::new(modifiers, key)
+        }
+    }
+
+    /// Modifier names in canonical spelling and order; unknown names are kept as-is
+    fn canonical_modifiers(&self) -> Vec<&str> {
+        let known = keycodes::MODIFIERS
+            .iter()
+            .map(|modifier| modifier.name)
+            .filter(|name| {
+                self.modifiers
+                    .iter()
+                    .any(|m| keycodes::find_modifier(m).is_some_and(|found| found.name == *name))
+            });
+        let unknown = self
+            .modifiers
+            .iter()
+            .filter(|m| keycodes::find_modifier(m).is_none())
+            .map(String::as_str);
+
+        known.chain(unknown).collect()
+    }
+
+    /// Key name in canonical spelling; unknown names are kept as-is
+    fn canonical_key(&self) -> &str {
+        keycodes::find_key(&self.key).map_or(self.key.as_str(), |key| key.name)
+    }
+
+    /// Normalized form for comparing bindings, e.g. "Control+Return" and "ctrl+enter" match
+    ///
+    /// Unknown modifiers are kept, so "Hyper+Ctrl+L" doesn't clash with "Ctrl+L".
+    /// Keys the system registers as one, like both Enter keys on Windows, match.
+    fn chord(&self) -> Chord {
+        let modifiers = self
+            .modifiers
+            .iter()
+            .map(|m| keycodes::find_modifier(m).map_or(m.as_str(), |modifier| modifier.name))
+            .map(str::to_uppercase)
+            .collect();
+
+        let key =
+            keycodes::find_key(&self.key).map_or(self.key.as_str(), |key| key.registered().name);
+        (modifiers, key.to_uppercase())
+    }
+}
+
+/// Formats as e.g. `Ctrl + Alt + Num1`, which parses back to the same binding
+impl fmt::Display for HotkeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = self.canonical_modifiers();
+        parts.push(self.canonical_key());
+        f.write_str(&parts.join(" + "))
+    }
+}
+
+/// Parses e.g. `ctrl+alt+numpad1`, accepting any alias and letter case
+impl FromStr for HotkeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let Some((key, modifiers)) = parts.split_last() else {
+            return Err(format!("Empty hotkey: {}", s));
+        };
+
+        let key = keycodes::find_key(key).ok_or_else(|| format!("Unknown key: {}", key))?;
+        let modifiers = modifiers
+            .iter()
+            .map(|m| {
+                keycodes::find_modifier(m)
+                    .map(|modifier| modifier.name)
+                    .ok_or_else(|| format!("Unknown modifier: {}", m))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let binding = HotkeyBinding::new(&modifiers, key.name);
+        // Canonical order, without repeats
+        Ok(HotkeyBinding::new(&binding.canonical_modifiers(), key.name))
+    }
+}
+
+/// What to do when the monitor arrangement changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopologyAction {
+    #[default]
+    Nothing,
+    /// Move every off-screen window to the auto-rescue fallback monitor,
+    /// skipping excluded processes
+    RescueOffscreen,
+    /// Restore the layout most recently saved on the new arrangement
+    RestoreLayout,
+}
+
+/// Default time a window stays off-screen before it is rescued
+pub const DEFAULT_RESCUE_GRACE_PERIOD_SECS: u64 = 5;
+
+/// Automatic rescue of windows that go off-screen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoRescueSettings {
+    pub enabled: bool,
+    /// How long a window must stay off-screen before it is moved, so
+    /// monitors coming back after a quick reconnect don't cause moves
+    pub grace_period_secs: u64,
+    /// Monitor to move windows to, by position, ID or name; the primary
+    /// monitor when unset or disconnected
+    pub fallback_monitor: Option<String>,
+    /// Process names whose windows are left where they are
+    pub excluded_processes: Vec<String>,
+}
+
+impl Default for AutoRescueSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            grace_period_secs: DEFAULT_RESCUE_GRACE_PERIOD_SECS,
+            fallback_monitor: None,
+            excluded_processes: Vec::new(),
+        }
     }
 }
 
@@ -203,7 +670,10 @@ pub enum Screen {
 }
 
 /// Actions that can have hotkeys
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Stored in settings by name, e.g. `center_window`, `send_to_monitor:2` or
+/// `snap_to_zone:left-half`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum HotkeyAction {
     LassoWindow,
     RefreshWindows,
@@ -211,6 +681,87 @@ pub enum HotkeyAction {
     MoveAllToPrimary,
     CenterWindow,
     NextMonitor,
+    PreviousMonitor,
+    /// Move the focused window to a specific monitor
+    SendToMonitor(MonitorTarget),
+    /// Move the focused window to the neighbouring monitor in a direction
+    MoveInDirection(Direction),
+    /// Wait for one more key, which picks an action from the leader keys
+    Leader,
+    /// Snap the focused window into a zone (built-in or custom zone id)
+    SnapToZone(String),
+}
+
+impl fmt::Display for HotkeyAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HotkeyAction::LassoWindow => f.write_str("lasso_window"),
+            HotkeyAction::RefreshWindows => f.write_str("refresh_windows"),
+            HotkeyAction::MoveToPrimary => f.write_str("move_to_primary"),
+            HotkeyAction::MoveAllToPrimary => f.write_str("move_all_to_primary"),
+            HotkeyAction::CenterWindow => f.write_str("center_window"),
+            HotkeyAction::NextMonitor => f.write_str("next_monitor"),
+            HotkeyAction::PreviousMonitor => f.write_str("previous_monitor"),
+            HotkeyAction::SendToMonitor(MonitorTarget::Position(position)) => {
+                write!(f, "send_to_monitor:{}", position)
+            }
+            HotkeyAction::SendToMonitor(MonitorTarget::Id(id)) => {
+                write!(f, "send_to_monitor:id:{}", id)
+            }
+            HotkeyAction::MoveInDirection(direction) => {
+                write!(f, "move_in_direction:{}", direction.id())
+            }
+            HotkeyAction::Leader => f.write_str("leader"),
+            HotkeyAction::SnapToZone(zone) => write!(f, "snap_to_zone:{}", zone),
+        }
+    }
+}
+
+impl FromStr for HotkeyAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let action = match s.split_once(':') {
+            None => match s {
+                "lasso_window" => Some(HotkeyAction::LassoWindow),
+                "refresh_windows" => Some(HotkeyAction::RefreshWindows),
+                "move_to_primary" => Some(HotkeyAction::MoveToPrimary),
+                "move_all_to_primary" => Some(HotkeyAction::MoveAllToPrimary),
+                "center_window" => Some(HotkeyAction::CenterWindow),
+                "next_monitor" => Some(HotkeyAction::NextMonitor),
+                "previous_monitor" => Some(HotkeyAction::PreviousMonitor),
+                "leader" => Some(HotkeyAction::Leader),
+                _ => None,
+            },
+            Some(("send_to_monitor", target)) => match target.strip_prefix("id:") {
+                Some(id) => Some(MonitorTarget::Id(id.to_string())),
+                None => target.parse().ok().map(MonitorTarget::Position),
+            }
+            .map(HotkeyAction::SendToMonitor),
+            Some(("move_in_direction", direction)) => {
+                Direction::from_id(direction).map(HotkeyAction::MoveInDirection)
+            }
+            Some(("snap_to_zone", zone)) if !zone.is_empty() => {
+                Some(HotkeyAction::SnapToZone(zone.to_string()))
+            }
+            Some(_) => None,
+        };
+
+        action.ok_or_else(|| format!("Unknown hotkey action: {}", s))
+    }
+}
+
+impl Serialize for HotkeyAction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HotkeyAction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
 }
 
 /// Supported languages
@@ -270,3 +821,205 @@ impl Language {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A settings file as written before bindings were keyed by action
+    const LEGACY_SETTINGS: &str = r#"{
+        "language": "de",
+        "minimize_to_tray": true,
+        "auto_focus_after_lasso": false,
+        "close_after_recovery": true,
+        "hotkeys": {
+            "lasso_window": { "modifiers": ["Ctrl", "Shift"], "key": "L", "enabled": true },
+            "refresh_windows": { "modifiers": ["Ctrl", "Alt"], "key": "R", "enabled": false },
+            "move_to_primary": { "modifiers": ["Ctrl", "Alt"], "key": "P", "enabled": true },
+            "move_all_to_primary": { "modifiers": ["Ctrl", "Alt"], "key": "A", "enabled": true },
+            "center_window": { "modifiers": ["Win"], "key": "C", "enabled": true },
+            "next_monitor": { "modifiers": ["Ctrl", "Alt"], "key": "N", "enabled": true }
+        },
+        "theme": { "dark_mode": false }
+    }"#;
+
+    #[test]
+    fn legacy_settings_are_migrated() {
+        let settings: AppSettings = serde_json::from_str(LEGACY_SETTINGS).unwrap();
+        let hotkeys = &settings.hotkeys;
+
+        assert_eq!(settings.language, "de");
+        assert!(settings.close_after_recovery);
+        assert!(!settings.theme.dark_mode);
+        assert_eq!(
+            hotkeys.bindings_for(&HotkeyAction::LassoWindow),
+            [HotkeyBinding::new(&["Ctrl", "Shift"], "L")]
+        );
+        assert_eq!(
+            hotkeys.bindings_for(&HotkeyAction::CenterWindow),
+            [HotkeyBinding::new(&["Win"], "C")]
+        );
+        assert!(!hotkeys.bindings_for(&HotkeyAction::RefreshWindows)[0].enabled);
+
+        // Actions added since get their defaults
+        let defaults = HotkeySettings::default();
+        for action in [HotkeyAction::PreviousMonitor, HotkeyAction::Leader] {
+            assert_eq!(
+                hotkeys.bindings_for(&action),
+                defaults.bindings_for(&action)
+            );
+        }
+        assert_eq!(hotkeys.leader_keys, defaults.leader_keys);
+        assert_eq!(hotkeys.leader_timeout_ms, DEFAULT_LEADER_TIMEOUT_MS);
+    }
+
+    #[test]
+    fn current_settings_round_trip() {
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set_binding(
+            HotkeyAction::LassoWindow,
+            None,
+            HotkeyBinding::new(&["Win"], "L"),
+        );
+        hotkeys
+            .bindings
+            .insert(HotkeyAction::MoveToPrimary, Vec::new());
+        hotkeys.leader_timeout_ms = 1500;
+
+        let json = serde_json::to_string(&hotkeys).unwrap();
+        let loaded: HotkeySettings = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.bindings, hotkeys.bindings);
+        assert_eq!(loaded.leader_keys, hotkeys.leader_keys);
+        assert_eq!(loaded.leader_timeout_ms, 1500);
+    }
+
+    #[test]
+    fn malformed_current_settings_keep_the_good_bindings() {
+        let json = r#"{
+            "bindings": {
+                "lasso_window": [{ "modifiers": ["Win"], "key": "L", "enabled": true }],
+                "move_to_primary": [],
+                "center_window": [{ "modifiers": "Ctrl", "key": 5 }],
+                "no_such_action": [{ "modifiers": ["Ctrl"], "key": "X", "enabled": true }]
+            },
+            "leader_keys": { "1": "send_to_monitor:1" },
+            "leader_timeout_ms": 1500
+        }"#;
+        let hotkeys: HotkeySettings = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            hotkeys.bindings_for(&HotkeyAction::LassoWindow),
+            [HotkeyBinding::new(&["Win"], "L")]
+        );
+        assert!(hotkeys
+            .bindings_for(&HotkeyAction::MoveToPrimary)
+            .is_empty());
+        assert_eq!(hotkeys.leader_keys.len(), 1);
+        assert_eq!(hotkeys.leader_timeout_ms, 1500);
+
+        // Only the bad list falls back to its default
+        assert_eq!(
+            hotkeys.bindings_for(&HotkeyAction::CenterWindow),
+            HotkeySettings::default().bindings_for(&HotkeyAction::CenterWindow)
+        );
+    }
+
+    #[test]
+    fn duplicates_ignore_spelling_but_not_unknown_modifiers() {
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set_binding(
+            HotkeyAction::LassoWindow,
+            Some(0),
+            HotkeyBinding::new(&["Ctrl"], "Enter"),
+        );
+        hotkeys.set_binding(
+            HotkeyAction::RefreshWindows,
+            Some(0),
+            HotkeyBinding::new(&["control"], "return"),
+        );
+        hotkeys.set_binding(
+            HotkeyAction::MoveToPrimary,
+            Some(0),
+            HotkeyBinding::new(&["Hyper", "Ctrl"], "Enter"),
+        );
+
+        let duplicates = hotkeys.duplicates();
+        assert_eq!(
+            duplicates.get(&(HotkeyAction::RefreshWindows, 0)),
+            Some(&HotkeyAction::LassoWindow)
+        );
+        assert_eq!(duplicates.get(&(HotkeyAction::MoveToPrimary, 0)), None);
+    }
+
+    #[test]
+    fn both_enter_keys_are_duplicates_on_windows() {
+        let mut hotkeys = HotkeySettings::default();
+        hotkeys.set_binding(
+            HotkeyAction::LassoWindow,
+            Some(0),
+            HotkeyBinding::new(&["Ctrl"], "Enter"),
+        );
+        hotkeys.set_binding(
+            HotkeyAction::RefreshWindows,
+            Some(0),
+            HotkeyBinding::new(&["Ctrl"], "NumEnter"),
+        );
+
+        let expected = cfg!(windows).then_some(&HotkeyAction::LassoWindow);
+        assert_eq!(
+            hotkeys.duplicates().get(&(HotkeyAction::RefreshWindows, 0)),
+            expected
+        );
+    }
+}
+
+/// Builders for monitors, windows and rects used by unit tests
+#[cfg(test)]
+pub mod test_support {
+    use super::{MonitorInfo, WindowInfo, WindowRect};
+
+    pub fn rect(left: i32, top: i32, right: i32, bottom: i32) -> WindowRect {
+        WindowRect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// A monitor whose work area is its full bounds
+    pub fn monitor(index: usize, bounds: WindowRect, dpi: u32) -> MonitorInfo {
+        MonitorInfo {
+            handle: index as isize + 1,
+            name: format!("Display {}", index + 1),
+            device_name: format!("DISPLAY{}", index + 1),
+            device_path: format!("path{}", index + 1),
+            bounds,
+            work_area: bounds,
+            is_primary: index == 0,
+            display_index: index,
+            stable_id: format!("MON{}", index + 1),
+            nickname: None,
+            dpi,
+        }
+    }
+
+    /// A normal window; off-screen status is left for `finish_window_list`
+    pub fn window(hwnd: isize, process: &str, title: &str, rect: WindowRect) -> WindowInfo {
+        WindowInfo {
+            hwnd,
+            title: title.to_string(),
+            process_name: process.to_string(),
+            process_id: hwnd as u32,
+            rect,
+            is_visible: true,
+            is_offscreen: false,
+            is_minimized: false,
+            is_maximized: false,
+            monitor_name: None,
+            icon_rgba: None,
+            icon_size: 32,
+        }
+    }
+}
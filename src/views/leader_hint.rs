@@ -0,0 +1,78 @@
+//! Leader key hint popup
+
+use crate::app::Message;
+use crate::localization::{keys, Localization};
+use crate::types::{AppSettings, HotkeyAction};
+use crate::views::settings_view::hotkey_action_label;
+use crate::views::styles::{self, colors};
+use iced::widget::{column, container, row, text};
+use iced::{Alignment, Element, Fill, Size};
+
+/// Keys shown per row of the hint
+pub const COLUMNS: usize = 2;
+
+/// Build the list of keys that can follow the leader chord
+pub fn view<'a>(settings: &'a AppSettings, loc: &'a Localization) -> Element<'a, Message> {
+    let title = text(loc.get(keys::HOTKEY_LEADER))
+        .size(16)
+        .color(colors::TEXT);
+
+    let hint = text(loc.get(keys::HOTKEY_LEADER_HINT))
+        .size(12)
+        .color(colors::TEXT_DIM);
+
+    // Group related actions (monitors, directions, zones) together
+    let mut entries: Vec<(&String, &HotkeyAction)> = settings
+        .hotkeys
+        .leader_keys
+        .iter()
+        .filter(|(_, action)| **action != HotkeyAction::Leader)
+        .collect();
+    entries.sort_by_key(|(_, action)| *action);
+
+    let rows = entries.chunks(COLUMNS).map(|chunk| {
+        row(chunk
+            .iter()
+            .map(|(key, action)| build_entry(key, hotkey_action_label(action, settings, loc))))
+        .spacing(16)
+        .into()
+    });
+
+    container(
+        column![title, hint, column(rows).spacing(6)]
+            .spacing(8)
+            .width(Fill),
+    )
+    .style(styles::card_container)
+    .padding(16)
+    .width(Fill)
+    .height(Fill)
+    .into()
+}
+
+/// Size of the hint window, which grows with the number of leader keys
+pub fn window_size(settings: &AppSettings) -> Size {
+    let rows = settings.hotkeys.leader_keys.len().div_ceil(COLUMNS);
+    Size::new(460.0, 84.0 + rows as f32 * 30.0)
+}
+
+fn build_entry<'a>(key: &str, label: String) -> Element<'a, Message> {
+    let chip = container(text(key.to_string()).size(12).color(colors::TEXT))
+        .style(|_: &_| container::Style {
+            background: Some(iced::Background::Color(colors::SURFACE_HOVER)),
+            border: iced::Border {
+                radius: 4.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .padding([4, 8])
+        .width(64)
+        .center_x(64);
+
+    row![chip, text(label).size(13).color(colors::TEXT_DIM)]
+        .spacing(10)
+        .align_y(Alignment::Center)
+        .width(Fill)
+        .into()
+}
@@ -4,21 +4,32 @@ use crate::app::Message;
 use crate::localization::{keys, Localization};
 use crate::types::{WindowInfo, GITHUB_URL, ISSUES_URL, VERSION};
 use crate::views::styles::{self, colors};
-use iced::widget::{button, column, container, image, row, scrollable, svg, text, tooltip};
-use iced::{Alignment, Element, Fill};
+use iced::widget::{
+    button, column, container, image, pick_list, row, scrollable, svg, text, text_input, tooltip,
+};
+use iced::{padding, Alignment, Element, Fill};
 
 /// Build the main view showing the window list
+///
+/// `windows` are the windows that pass the filter typed into the search box.
+/// `layouts` are the names of the saved layouts.
 pub fn view<'a>(
-    windows: &'a [WindowInfo],
+    windows: Vec<&'a WindowInfo>,
+    filter: &'a str,
+    filter_error: Option<&'a str>,
+    layouts: &'a [String],
+    layout_name: &'a str,
     loc: &'a Localization,
     status_message: Option<&'a str>,
 ) -> Element<'a, Message> {
     let header = build_header(loc, windows.len());
+    let filter_bar = build_filter_bar(filter, filter_error, loc);
+    let layouts_bar = build_layouts_bar(layouts, layout_name, loc);
     let window_list = build_window_list(windows, loc);
     let footer = build_footer(status_message);
 
     container(
-        column![header, window_list, footer]
+        column![header, filter_bar, layouts_bar, window_list, footer]
             .spacing(0)
             .width(Fill)
             .height(Fill),
@@ -92,7 +103,89 @@ fn build_header<'a>(loc: &'a Localization, window_count: usize) -> Element<'a, M
     .into()
 }
 
-fn build_window_list<'a>(windows: &'a [WindowInfo], loc: &'a Localization) -> Element<'a, Message> {
+fn build_filter_bar<'a>(
+    filter: &'a str,
+    error: Option<&'a str>,
+    loc: &'a Localization,
+) -> Element<'a, Message> {
+    let input = text_input(&loc.get(keys::WINDOWS_FILTER), filter)
+        .on_input(Message::FilterWindows)
+        .style(styles::input_field)
+        .padding(8)
+        .size(14);
+
+    let content: Element<'a, Message> = match error {
+        Some(error) => column![
+            input,
+            text(loc.get_with_arg(keys::WINDOWS_FILTER_INVALID, "error", error))
+                .size(12)
+                .color(colors::WARNING),
+        ]
+        .spacing(4)
+        .into(),
+        None => input.into(),
+    };
+
+    container(content)
+        .style(styles::header_container)
+        .padding(padding::horizontal(16).bottom(12))
+        .width(Fill)
+        .into()
+}
+
+/// Save the current windows as a layout, or restore or delete a saved one
+///
+/// Picking a saved layout fills in its name; the buttons act on the name.
+fn build_layouts_bar<'a>(
+    layouts: &'a [String],
+    layout_name: &'a str,
+    loc: &'a Localization,
+) -> Element<'a, Message> {
+    let name = layout_name.trim();
+    let saved = layouts.iter().find(|layout| layout.as_str() == name);
+
+    let name_input = text_input(&loc.get(keys::LAYOUTS_NAME), layout_name)
+        .on_input(Message::LayoutNameInput)
+        .on_submit(Message::SaveLayout)
+        .style(styles::input_field)
+        .padding(8)
+        .size(14)
+        .width(Fill);
+
+    let picker = pick_list(layouts, saved, Message::LayoutNameInput)
+        .placeholder(loc.get(keys::LAYOUTS_SAVED))
+        .padding([6, 12]);
+
+    let save_btn = button(text(loc.get(keys::BTN_SAVE)).size(13))
+        .style(styles::secondary_button)
+        .padding([6, 12])
+        .on_press_maybe((!name.is_empty()).then_some(Message::SaveLayout));
+
+    let restore_btn = button(text(loc.get(keys::LAYOUTS_RESTORE)).size(13))
+        .style(styles::primary_button)
+        .padding([6, 12])
+        .on_press_maybe(saved.map(|name| Message::RestoreLayout(name.clone())));
+
+    let delete_btn = button(text(loc.get(keys::LAYOUTS_DELETE)).size(13))
+        .style(styles::secondary_button)
+        .padding([6, 12])
+        .on_press_maybe(saved.map(|name| Message::DeleteLayout(name.clone())));
+
+    container(
+        row![name_input, save_btn, picker, restore_btn, delete_btn]
+            .spacing(6)
+            .align_y(Alignment::Center),
+    )
+    .style(styles::header_container)
+    .padding(padding::horizontal(16).bottom(12))
+    .width(Fill)
+    .into()
+}
+
+fn build_window_list<'a>(
+    windows: Vec<&'a WindowInfo>,
+    loc: &'a Localization,
+) -> Element<'a, Message> {
     if windows.is_empty() {
         return container(
             text(loc.get(keys::WINDOWS_EMPTY))
@@ -106,7 +199,10 @@ fn build_window_list<'a>(windows: &'a [WindowInfo], loc: &'a Localization) -> El
         .into();
     }
 
-    let items: Vec<Element<Message>> = windows.iter().map(|w| build_window_item(w, loc)).collect();
+    let items: Vec<Element<Message>> = windows
+        .into_iter()
+        .map(|w| build_window_item(w, loc))
+        .collect();
 
     scrollable(column(items).spacing(8).padding(16).width(Fill))
         .style(styles::list_scrollable)
@@ -1,5 +1,6 @@
 //! View modules for the UI
 
+pub mod leader_hint;
 pub mod main_view;
 pub mod monitor_picker;
 pub mod settings_view;
@@ -1,20 +1,28 @@
 //! Monitor picker view
 
 use crate::app::Message;
+use crate::backend::monitor_for_rect;
 use crate::localization::{keys, Localization};
-use crate::types::{MonitorInfo, WindowInfo};
+use crate::placement::{place, PlacementPolicy, PlacementTarget, ShowState};
+use crate::types::{MonitorInfo, WindowInfo, WindowRect};
 use crate::views::styles::{self, colors};
-use iced::widget::{button, canvas, column, container, row, svg, text, tooltip};
-use iced::{Alignment, Element, Fill, Length, Point, Size};
+use crate::zones::{snap_zone_at, SnapZone};
+use iced::widget::{
+    button, canvas, column, container, row, scrollable, svg, text, text_input, tooltip,
+};
+use iced::{mouse, Alignment, Color, Element, Fill, Length, Point, Rectangle, Size, Vector};
 
 /// Build the monitor picker view
 pub fn view<'a>(
     selected_window: &'a WindowInfo,
     monitors: &'a [MonitorInfo],
+    editing_nickname: Option<&'a (String, String)>,
+    policy: PlacementPolicy,
     loc: &'a Localization,
 ) -> Element<'a, Message> {
     let header = build_header(selected_window, loc);
-    let monitor_grid = build_monitor_grid(monitors, loc);
+    let monitor_grid =
+        build_monitor_grid(selected_window, monitors, editing_nickname, policy, loc);
 
     container(
         column![header, monitor_grid]
@@ -77,7 +85,10 @@ fn build_header<'a>(window: &'a WindowInfo, loc: &'a Localization) -> Element<'a
 }
 
 fn build_monitor_grid<'a>(
+    selected_window: &'a WindowInfo,
     monitors: &'a [MonitorInfo],
+    editing_nickname: Option<&'a (String, String)>,
+    policy: PlacementPolicy,
     loc: &'a Localization,
 ) -> Element<'a, Message> {
     // Sort monitors: primary first, then by display index
@@ -88,23 +99,41 @@ fn build_monitor_grid<'a>(
         _ => a.display_index.cmp(&b.display_index),
     });
 
-    // Calculate the maximum resolution for relative scaling
-    let max_pixels = monitors
-        .iter()
-        .map(|m| (m.bounds.width() as u64) * (m.bounds.height() as u64))
-        .max()
-        .unwrap_or(1) as f64;
-
     let monitor_cards: Vec<Element<Message>> = sorted_monitors
         .iter()
-        .map(|m| build_monitor_card(m, loc, max_pixels))
+        .map(|m| {
+            let draft = editing_nickname
+                .filter(|(stable_id, _)| *stable_id == m.stable_id)
+                .map(|(_, draft)| draft.as_str());
+            build_monitor_card(m, draft, loc)
+        })
         .collect();
 
-    // Layout monitors in a column for simplicity
-    let content = column(monitor_cards).spacing(16).width(Fill);
+    // Map of the virtual desktop; clicking a monitor moves the window there
+    let map = canvas(MonitorMap {
+        monitors,
+        window: selected_window,
+        policy,
+    })
+    .width(Fill)
+    .height(Length::Fixed(MAP_HEIGHT));
+
+    let map_hint = text(loc.get(keys::MONITOR_MAP_HINT))
+        .size(12)
+        .color(colors::TEXT_DIM);
+
+    let content = column![
+        container(column![map, map_hint].spacing(8))
+            .style(styles::card_container)
+            .padding(8),
+        column(monitor_cards).spacing(16).width(Fill),
+    ]
+    .spacing(16)
+    .padding(16)
+    .width(Fill);
 
-    container(content)
-        .padding(16)
+    scrollable(content)
+        .style(styles::list_scrollable)
         .width(Fill)
         .height(Fill)
         .into()
@@ -112,8 +141,8 @@ fn build_monitor_grid<'a>(
 
 fn build_monitor_card<'a>(
     monitor: &'a MonitorInfo,
+    nickname_draft: Option<&'a str>,
     loc: &'a Localization,
-    max_pixels: f64,
 ) -> Element<'a, Message> {
     let style = if monitor.is_primary {
         styles::monitor_card_primary
@@ -121,8 +150,57 @@ fn build_monitor_card<'a>(
         styles::monitor_card
     };
 
-    // Monitor name
-    let name = text(&monitor.name).size(18).color(colors::TEXT);
+    // Monitor name, or the nickname editor while renaming
+    let name: Element<Message> = if let Some(draft) = nickname_draft {
+        row![
+            text_input(&monitor.name, draft)
+                .on_input(Message::MonitorNicknameInput)
+                .on_submit(Message::SaveMonitorNickname)
+                .style(styles::input_field)
+                .size(14)
+                .padding([6, 8])
+                .width(Fill),
+            button(text(loc.get(keys::BTN_SAVE)).size(13))
+                .style(styles::primary_button)
+                .padding([6, 12])
+                .on_press(Message::SaveMonitorNickname),
+            button(text(loc.get(keys::BTN_CANCEL)).size(13))
+                .style(styles::secondary_button)
+                .padding([6, 12])
+                .on_press(Message::CancelNicknameEdit),
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center)
+        .into()
+    } else {
+        let rename_icon = svg(svg::Handle::from_memory(include_bytes!(
+            "../../icons/interface/pencil.svg"
+        )))
+        .width(14)
+        .height(14)
+        .style(|_theme, _status| svg::Style {
+            color: Some(colors::TEXT_DIM),
+        });
+
+        let rename_btn = tooltip(
+            button(rename_icon)
+                .style(styles::icon_button)
+                .padding(4)
+                .on_press(Message::EditMonitorNickname(monitor.clone())),
+            text(loc.get(keys::TOOLTIP_RENAME_MONITOR)).size(13),
+            tooltip::Position::Top,
+        )
+        .gap(4)
+        .style(styles::tooltip_container);
+
+        row![
+            text(monitor.display_name()).size(18).color(colors::TEXT),
+            rename_btn,
+        ]
+        .spacing(6)
+        .align_y(Alignment::Center)
+        .into()
+    };
 
     // Resolution
     let width = monitor.bounds.width();
@@ -134,6 +212,17 @@ fn build_monitor_card<'a>(
         .size(13)
         .color(colors::TEXT_DIM);
 
+    // Display scaling
+    let mut args = fluent::FluentArgs::new();
+    args.set(
+        "percent",
+        fluent::FluentValue::from(monitor.scale_percent() as i64),
+    );
+    args.set("dpi", fluent::FluentValue::from(monitor.dpi as i64));
+    let scaling = text(loc.get_with_args(keys::MONITOR_SCALE, Some(&args)))
+        .size(13)
+        .color(colors::TEXT_DIM);
+
     // Primary badge
     let primary_badge: Element<Message> = if monitor.is_primary {
         container(
@@ -163,32 +252,8 @@ fn build_monitor_card<'a>(
         .padding([10, 20])
         .on_press(Message::MoveToMonitor(monitor.clone()));
 
-    // Calculate visual size based on relative resolution
-    // Base size for the largest monitor
-    let base_width: f32 = 140.0;
-
-    // Scale based on pixel count relative to max
-    let this_pixels = (width as u64 * height as u64) as f64;
-    let scale = (this_pixels / max_pixels).sqrt() as f32;
-
-    // Maintain aspect ratio
-    let aspect_ratio = width as f32 / height.max(1) as f32;
-    let box_width = base_width * scale;
-    let box_height = box_width / aspect_ratio;
-
-    // Create the monitor preview using canvas
-    let monitor_visual = canvas(MonitorPreview {
-        width: box_width,
-        height: box_height,
-        is_primary: monitor.is_primary,
-    })
-    .width(Length::Fixed(box_width))
-    .height(Length::Fixed(box_height));
-
     let content = row![
-        monitor_visual,
-        iced::widget::Space::new().width(16),
-        column![name, resolution, primary_badge,]
+        column![name, resolution, scaling, primary_badge,]
             .spacing(4)
             .width(Fill),
         move_btn,
@@ -209,84 +274,427 @@ fn truncate_string(s: &str, max_len: usize) -> String {
     }
 }
 
-/// Canvas-based monitor preview that renders a desktop-like visualization
-struct MonitorPreview {
-    width: f32,
-    height: f32,
-    is_primary: bool,
+/// Height of the monitor map canvas
+const MAP_HEIGHT: f32 = 180.0;
+
+/// Space kept free around the desktop inside the map
+const MAP_PADDING: f32 = 8.0;
+
+/// Canvas that draws the virtual desktop arrangement with the selected window on top
+struct MonitorMap<'a> {
+    monitors: &'a [MonitorInfo],
+    window: &'a WindowInfo,
+    /// Placement settings a drop is carried out with
+    policy: PlacementPolicy,
+}
+
+/// Where the window would land if dropped at the cursor
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MapDrop {
+    /// Index of the monitor under the cursor
+    monitor: usize,
+    target: PlacementTarget,
+}
+
+/// Interaction state of the monitor map
+#[derive(Debug, Default)]
+struct MapState {
+    hovered: Option<MapDrop>,
+    /// Whether the mouse was pressed on a monitor and not yet released
+    dragging: bool,
 }
 
-impl<Message> canvas::Program<Message> for MonitorPreview {
-    type State = ();
+impl MonitorMap<'_> {
+    /// Fit the monitors into the map, along with the window so an off-screen
+    /// window is still drawn where it can be seen
+    fn layout(&self, size: Size) -> Option<MapLayout> {
+        let window = (!self.window.is_minimized).then_some(&self.window.rect);
+        MapLayout::new(self.monitors.iter().map(|m| &m.bounds).chain(window), size)
+    }
+
+    /// Drop target under a point in canvas coordinates
+    fn drop_at(&self, size: Size, point: Point) -> Option<MapDrop> {
+        let layout = self.layout(size)?;
+        let (index, monitor) = self
+            .monitors
+            .iter()
+            .enumerate()
+            .find(|(_, m)| layout.project(&m.bounds).contains(point))?;
+
+        // Position as fractions of the work area
+        let area = layout.project(&monitor.work_area);
+        let x = ((point.x - area.x) / area.width.max(1.0)).clamp(0.0, 1.0) as f64;
+        let y = ((point.y - area.y) / area.height.max(1.0)).clamp(0.0, 1.0) as f64;
+
+        Some(MapDrop {
+            monitor: index,
+            target: snap_zone_at(x, y)
+                .map(SnapZone::target)
+                .unwrap_or(PlacementTarget::Point { x, y }),
+        })
+    }
+
+    /// Rect the window would occupy after a drop, in desktop coordinates
+    ///
+    /// Placed the way the app moves it, so the preview matches where the
+    /// window really lands.
+    fn preview_rect(&self, drop: &MapDrop) -> Option<WindowRect> {
+        let monitor = self.monitors.get(drop.monitor)?;
+        let policy = PlacementPolicy {
+            target: Some(drop.target),
+            ..self.policy
+        };
+        let source_monitor = policy
+            .needs_source_monitor()
+            .then(|| monitor_for_rect(&self.window.rect, self.monitors))
+            .flatten();
+
+        let placement = place(&self.window.rect, source_monitor, monitor, &policy);
+        Some(match placement.show {
+            ShowState::Maximized => monitor.work_area,
+            ShowState::Normal => placement.rect,
+        })
+    }
+}
+
+impl canvas::Program<Message> for MonitorMap<'_> {
+    type State = MapState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &iced::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        let hovered = cursor
+            .position_in(bounds)
+            .and_then(|point| self.drop_at(bounds.size(), point));
+
+        match event {
+            iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if hovered.is_some() =>
+            {
+                state.dragging = true;
+                state.hovered = hovered;
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if state.dragging =>
+            {
+                state.dragging = false;
+                // Releasing outside the monitors cancels the drag
+                let action = match hovered {
+                    Some(drop) => canvas::Action::publish(Message::PlaceOnMonitor(
+                        self.monitors[drop.monitor].clone(),
+                        drop.target,
+                    )),
+                    None => canvas::Action::request_redraw(),
+                };
+                Some(action.and_capture())
+            }
+            iced::Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft)
+                if state.hovered != hovered =>
+            {
+                state.hovered = hovered;
+                Some(canvas::Action::request_redraw())
+            }
+            _ => None,
+        }
+    }
 
     fn draw(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         renderer: &iced::Renderer,
         _theme: &iced::Theme,
-        bounds: iced::Rectangle,
-        _cursor: iced::mouse::Cursor,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
-
-        // Draw monitor bezel
-        let bezel_color = if self.is_primary {
-            iced::Color::from_rgb(0.36, 0.56, 0.96)
-        } else {
-            colors::BORDER
+        let Some(layout) = self.layout(bounds.size()) else {
+            return vec![frame.into_geometry()];
         };
 
-        frame.fill_rectangle(
-            Point::ORIGIN,
-            Size::new(self.width, self.height),
-            bezel_color,
+        let bezel_width = 2.0;
+        let taskbar_color = Color::from_rgb(0.08, 0.09, 0.12);
+
+        for (index, monitor) in self.monitors.iter().enumerate() {
+            let hovered = state.hovered.is_some_and(|drop| drop.monitor == index);
+            let outer = layout.project(&monitor.bounds);
+
+            // Bezel
+            let bezel_color = if hovered {
+                colors::PRIMARY_HOVER
+            } else if monitor.is_primary {
+                colors::PRIMARY
+            } else {
+                colors::BORDER
+            };
+            frame.fill_rectangle(outer.position(), outer.size(), bezel_color);
+
+            // Screen, with the area outside the work area drawn as taskbar
+            let screen = outer.shrink(bezel_width);
+            frame.fill_rectangle(screen.position(), screen.size(), taskbar_color);
+
+            let screen_color = if hovered {
+                Color::from_rgb(0.16, 0.19, 0.25)
+            } else {
+                Color::from_rgb(0.12, 0.14, 0.18)
+            };
+            if let Some(work_area) = layout.project(&monitor.work_area).intersection(&screen) {
+                frame.fill_rectangle(work_area.position(), work_area.size(), screen_color);
+            }
+
+            frame.fill_text(canvas::Text {
+                content: monitor.display_name().to_string(),
+                position: outer.center(),
+                max_width: screen.width,
+                color: colors::TEXT,
+                size: 12.0.into(),
+                align_x: iced::widget::text::Alignment::Center,
+                align_y: iced::alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+
+        // Selected window (minimized windows have no meaningful position)
+        if !self.window.is_minimized {
+            let rect = layout.project(&self.window.rect);
+            let color = if self.window.is_offscreen {
+                colors::WARNING
+            } else {
+                colors::PRIMARY
+            };
+
+            frame.fill_rectangle(rect.position(), rect.size(), Color { a: 0.25, ..color });
+            frame.stroke_rectangle(
+                rect.position(),
+                rect.size(),
+                canvas::Stroke::default().with_color(color).with_width(1.5),
+            );
+        }
+
+        // Where the window would land
+        if let Some(preview) = state.hovered.and_then(|drop| self.preview_rect(&drop)) {
+            let rect = layout.project(&preview);
+            let alpha = if state.dragging { 0.5 } else { 0.3 };
+
+            frame.fill_rectangle(
+                rect.position(),
+                rect.size(),
+                Color {
+                    a: alpha,
+                    ..colors::PRIMARY
+                },
+            );
+            frame.stroke_rectangle(
+                rect.position(),
+                rect.size(),
+                canvas::Stroke::default()
+                    .with_color(colors::PRIMARY_HOVER)
+                    .with_width(1.5),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        _bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match (state.dragging, state.hovered) {
+            (true, _) => mouse::Interaction::Grabbing,
+            (false, Some(_)) => mouse::Interaction::Pointer,
+            (false, None) => mouse::Interaction::default(),
+        }
+    }
+}
+
+/// Scale and offset that fit the whole virtual desktop into the map
+struct MapLayout {
+    desktop: WindowRect,
+    scale: f32,
+    offset: Vector,
+}
+
+impl MapLayout {
+    /// Fit the smallest area containing all the rects into the map
+    fn new<'r>(rects: impl Iterator<Item = &'r WindowRect>, size: Size) -> Option<Self> {
+        let desktop = rects
+            .copied()
+            .reduce(|a, b| WindowRect {
+                left: a.left.min(b.left),
+                top: a.top.min(b.top),
+                right: a.right.max(b.right),
+                bottom: a.bottom.max(b.bottom),
+            })?;
+        if desktop.width() <= 0 || desktop.height() <= 0 {
+            return None;
+        }
+
+        let available = Size::new(
+            (size.width - MAP_PADDING * 2.0).max(1.0),
+            (size.height - MAP_PADDING * 2.0).max(1.0),
         );
+        let scale = (available.width / desktop.width() as f32)
+            .min(available.height / desktop.height() as f32);
 
-        // Draw screen area (inset by bezel)
-        let bezel_width = 3.0;
-        let screen_color = iced::Color::from_rgb(0.12, 0.14, 0.18);
-
-        frame.fill_rectangle(
-            Point::new(bezel_width, bezel_width),
-            Size::new(
-                self.width - bezel_width * 2.0,
-                self.height - bezel_width * 2.0,
-            ),
-            screen_color,
+        // Center the desktop in the available space
+        let offset = Vector::new(
+            (size.width - desktop.width() as f32 * scale) / 2.0,
+            (size.height - desktop.height() as f32 * scale) / 2.0,
         );
 
-        // Draw taskbar at bottom
-        let taskbar_height = (self.height * 0.08).max(4.0);
-        let taskbar_color = iced::Color::from_rgb(0.08, 0.09, 0.12);
+        Some(Self {
+            desktop,
+            scale,
+            offset,
+        })
+    }
 
-        frame.fill_rectangle(
-            Point::new(bezel_width, self.height - bezel_width - taskbar_height),
-            Size::new(self.width - bezel_width * 2.0, taskbar_height),
-            taskbar_color,
+    /// Convert a rect in desktop coordinates to canvas coordinates
+    fn project(&self, rect: &WindowRect) -> Rectangle {
+        Rectangle {
+            x: (rect.left - self.desktop.left) as f32 * self.scale + self.offset.x,
+            y: (rect.top - self.desktop.top) as f32 * self.scale + self.offset.y,
+            width: rect.width() as f32 * self.scale,
+            height: rect.height() as f32 * self.scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::finish_window_list;
+    use crate::placement::ScalingMode;
+    use crate::types::test_support::{monitor, rect, window};
+    use crate::zones::ZoneRect;
+
+    /// A 1920x1080 monitor at 96 DPI with a 2560x1440 one at 192 DPI to its
+    /// right, and a window on the first
+    fn desktop() -> (Vec<MonitorInfo>, WindowInfo) {
+        let monitors = vec![
+            monitor(0, rect(0, 0, 1920, 1080), 96),
+            monitor(1, rect(1920, 0, 4480, 1440), 192),
+        ];
+        let mut windows = vec![window(100, "notepad.exe", "Notepad", rect(100, 100, 900, 700))];
+        finish_window_list(&mut windows, &monitors);
+        (monitors, windows.remove(0))
+    }
+
+    /// A map with room for the desktop at 1/8 scale: 560x180 plus the
+    /// padding, with 20 spare across
+    fn map_size() -> Size {
+        Size::new(600.0, 196.0)
+    }
+
+    #[test]
+    fn the_desktop_is_scaled_to_fit_and_centered() {
+        let (monitors, _) = desktop();
+        let layout = MapLayout::new(monitors.iter().map(|m| &m.bounds), map_size()).unwrap();
+        assert_eq!(layout.scale, 0.125);
+
+        assert_eq!(
+            layout.project(&monitors[0].bounds),
+            Rectangle::new(Point::new(20.0, 8.0), Size::new(240.0, 135.0))
+        );
+        assert_eq!(
+            layout.project(&monitors[1].bounds),
+            Rectangle::new(Point::new(260.0, 8.0), Size::new(320.0, 180.0))
         );
+    }
 
-        // Draw some fake windows to give it a "desktop" look
-        let window_color = iced::Color::from_rgb(0.2, 0.22, 0.28);
-
-        // Window 1 (top-left area)
-        let w1_x = bezel_width + (self.width * 0.08);
-        let w1_y = bezel_width + (self.height * 0.1);
-        let w1_w = self.width * 0.35;
-        let w1_h = self.height * 0.4;
-        frame.fill_rectangle(Point::new(w1_x, w1_y), Size::new(w1_w, w1_h), window_color);
-
-        // Window 2 (overlapping, center-right)
-        let w2_x = bezel_width + (self.width * 0.3);
-        let w2_y = bezel_width + (self.height * 0.25);
-        let w2_w = self.width * 0.45;
-        let w2_h = self.height * 0.45;
-        let window_color_2 = iced::Color::from_rgb(0.25, 0.27, 0.32);
-        frame.fill_rectangle(
-            Point::new(w2_x, w2_y),
-            Size::new(w2_w, w2_h),
-            window_color_2,
+    #[test]
+    fn off_screen_windows_stay_inside_the_map() {
+        let (monitors, _) = desktop();
+        let stray = rect(-4480, 0, -3520, 720);
+        let layout = MapLayout::new(
+            monitors.iter().map(|m| &m.bounds).chain([&stray]),
+            map_size(),
+        )
+        .unwrap();
+        assert!(layout.scale < 0.125);
+
+        let left = layout.project(&stray);
+        let right = layout.project(&monitors[1].bounds);
+        assert!(left.x >= 0.0);
+        assert!(right.x + right.width <= map_size().width);
+    }
+
+    #[test]
+    fn nothing_to_draw_without_an_area() {
+        assert!(MapLayout::new(std::iter::empty::<&WindowRect>(), map_size()).is_none());
+        let flat = rect(0, 0, 1920, 0);
+        assert!(MapLayout::new([&flat].into_iter(), map_size()).is_none());
+    }
+
+    #[test]
+    fn drops_snap_near_edges_and_land_freely_elsewhere() {
+        let (monitors, window) = desktop();
+        let map = MonitorMap {
+            monitors: &monitors,
+            window: &window,
+            policy: PlacementPolicy::default(),
+        };
+        let drop = |x, y| map.drop_at(map_size(), Point::new(x, y));
+
+        assert_eq!(
+            drop(265.0, 98.0),
+            Some(MapDrop {
+                monitor: 1,
+                target: PlacementTarget::Zone(ZoneRect::new(0.0, 0.0, 0.5, 1.0)),
+            })
+        );
+        assert_eq!(
+            drop(420.0, 10.0),
+            Some(MapDrop {
+                monitor: 1,
+                target: PlacementTarget::Maximize,
+            })
+        );
+        assert_eq!(
+            drop(22.0, 140.0),
+            Some(MapDrop {
+                monitor: 0,
+                target: PlacementTarget::Zone(ZoneRect::new(0.0, 0.5, 0.5, 0.5)),
+            })
+        );
+        assert_eq!(
+            drop(80.0, 58.625),
+            Some(MapDrop {
+                monitor: 0,
+                target: PlacementTarget::Point { x: 0.25, y: 0.375 },
+            })
         );
+        // The margin around the desktop is no drop target
+        assert_eq!(drop(5.0, 98.0), None);
+    }
 
-        vec![frame.into_geometry()]
+    #[test]
+    fn the_preview_uses_the_placement_settings() {
+        let (monitors, window) = desktop();
+        let center = MapDrop {
+            monitor: 1,
+            target: PlacementTarget::Point { x: 0.5, y: 0.5 },
+        };
+        let preview = |scaling| {
+            let map = MonitorMap {
+                monitors: &monitors,
+                window: &window,
+                policy: PlacementPolicy {
+                    scaling,
+                    ..PlacementPolicy::default()
+                },
+            };
+            map.preview_rect(&center).unwrap()
+        };
+
+        assert_eq!(preview(ScalingMode::default()), rect(2800, 420, 3600, 1020));
+        assert_eq!(preview(ScalingMode::PhysicalSize), rect(2400, 120, 4000, 1320));
     }
 }
@@ -1,16 +1,25 @@
 //! Settings view
 
 use crate::app::Message;
+use crate::hotkeys::{HotkeyConflict, HotkeyConflicts};
 use crate::localization::{keys, Localization};
-use crate::types::{AppSettings, HotkeyAction, HotkeyBinding, Language};
+use crate::placement::{PlacementMode, ScalingMode};
+use crate::topology::{Direction, MonitorTarget};
+use crate::types::{AppSettings, HotkeyAction, HotkeyBinding, Language, TopologyAction};
 use crate::views::styles::{self, colors};
+use crate::zones::SnapZone;
 use iced::widget::{button, column, container, pick_list, row, scrollable, svg, text, toggler, tooltip};
 use iced::{Alignment, Element, Fill};
+use std::collections::BTreeSet;
 
 /// Build the settings view
-pub fn view<'a>(settings: &'a AppSettings, loc: &'a Localization) -> Element<'a, Message> {
+pub fn view<'a>(
+    settings: &'a AppSettings,
+    conflicts: &HotkeyConflicts,
+    loc: &'a Localization,
+) -> Element<'a, Message> {
     let header = build_header(loc);
-    let content = build_settings_content(settings, loc);
+    let content = build_settings_content(settings, conflicts, loc);
 
     container(column![header, content].spacing(0).width(Fill).height(Fill))
         .style(styles::main_container)
@@ -60,6 +69,7 @@ fn build_header<'a>(loc: &'a Localization) -> Element<'a, Message> {
 
 fn build_settings_content<'a>(
     settings: &'a AppSettings,
+    conflicts: &HotkeyConflicts,
     loc: &'a Localization,
 ) -> Element<'a, Message> {
     // Language section
@@ -85,56 +95,87 @@ fn build_settings_content<'a>(
         Message::SetCloseAfterRecovery,
     );
 
+    let auto_rescue_row = build_toggle_row(
+        loc.get(keys::SETTINGS_AUTO_RESCUE),
+        settings.auto_rescue.enabled,
+        Message::SetAutoRescue,
+    );
+
+    let placement_row = build_setting_row(
+        loc.get(keys::SETTINGS_PLACEMENT),
+        build_choice_picker(
+            vec![
+                (PlacementMode::Center, loc.get(keys::PLACEMENT_CENTER)),
+                (
+                    PlacementMode::PreserveRelative,
+                    loc.get(keys::PLACEMENT_RELATIVE),
+                ),
+            ],
+            settings.placement_mode,
+            Message::SetPlacementMode,
+        ),
+    );
+
+    let scaling_row = build_setting_row(
+        loc.get(keys::SETTINGS_SCALING),
+        build_choice_picker(
+            vec![
+                (ScalingMode::Resolution, loc.get(keys::SCALING_RESOLUTION)),
+                (ScalingMode::PhysicalSize, loc.get(keys::SCALING_PHYSICAL)),
+            ],
+            settings.scaling_mode,
+            Message::SetScalingMode,
+        ),
+    );
+
+    let monitors_changed_row = build_setting_row(
+        loc.get(keys::SETTINGS_MONITORS_CHANGED),
+        build_choice_picker(
+            vec![
+                (TopologyAction::Nothing, loc.get(keys::MONITORS_CHANGED_NOTHING)),
+                (
+                    TopologyAction::RescueOffscreen,
+                    loc.get(keys::MONITORS_CHANGED_RESCUE),
+                ),
+                (
+                    TopologyAction::RestoreLayout,
+                    loc.get(keys::MONITORS_CHANGED_RESTORE_LAYOUT),
+                ),
+            ],
+            settings.on_monitors_changed,
+            Message::SetTopologyAction,
+        ),
+    );
+
     let tray_row = build_toggle_row(
         loc.get(keys::SETTINGS_TRAY),
         settings.minimize_to_tray.unwrap_or(false),
         |enabled| Message::SetMinimizeToTray(Some(enabled)),
     );
 
+    let api_row = build_toggle_row(
+        loc.get(keys::SETTINGS_API),
+        settings.api_enabled,
+        Message::SetApiEnabled,
+    );
+
     // Hotkeys section header
     let hotkeys_header = text(loc.get(keys::SETTINGS_HOTKEYS))
         .size(13)
         .color(colors::TEXT_DIM);
 
-    let hotkey_rows = column![
-        build_hotkey_row(
-            loc.get(keys::HOTKEY_LASSO),
-            &settings.hotkeys.lasso_window,
-            HotkeyAction::LassoWindow,
-            loc,
-        ),
-        build_hotkey_row(
-            loc.get(keys::HOTKEY_REFRESH),
-            &settings.hotkeys.refresh_windows,
-            HotkeyAction::RefreshWindows,
-            loc,
-        ),
-        build_hotkey_row(
-            loc.get(keys::HOTKEY_PRIMARY),
-            &settings.hotkeys.move_to_primary,
-            HotkeyAction::MoveToPrimary,
-            loc,
-        ),
-        build_hotkey_row(
-            loc.get(keys::HOTKEY_ALL_PRIMARY),
-            &settings.hotkeys.move_all_to_primary,
-            HotkeyAction::MoveAllToPrimary,
-            loc,
-        ),
-        build_hotkey_row(
-            loc.get(keys::HOTKEY_CENTER),
-            &settings.hotkeys.center_window,
-            HotkeyAction::CenterWindow,
-            loc,
-        ),
-        build_hotkey_row(
-            loc.get(keys::HOTKEY_NEXT_MONITOR),
-            &settings.hotkeys.next_monitor,
-            HotkeyAction::NextMonitor,
-            loc,
-        ),
-    ]
-    .spacing(0);
+    // Zone hotkeys get their own section
+    let (zone_actions, other_actions): (Vec<_>, Vec<_>) = hotkey_actions(settings)
+        .into_iter()
+        .partition(|action| matches!(action, HotkeyAction::SnapToZone(_)));
+
+    let hotkey_rows = build_hotkey_rows(other_actions, settings, conflicts, loc);
+
+    let zones_header = text(loc.get(keys::HOTKEY_ZONES))
+        .size(13)
+        .color(colors::TEXT_DIM);
+
+    let zone_rows = build_hotkey_rows(zone_actions, settings, conflicts, loc);
 
     let content = column![
         language_row,
@@ -142,10 +183,17 @@ fn build_settings_content<'a>(
         behavior_header,
         auto_focus_row,
         close_after_recovery_row,
+        auto_rescue_row,
+        monitors_changed_row,
+        placement_row,
+        scaling_row,
         tray_row,
+        api_row,
         divider(),
         hotkeys_header,
         hotkey_rows,
+        zones_header,
+        zone_rows,
     ]
     .spacing(12)
     .padding(20)
@@ -219,16 +267,141 @@ fn build_language_picker<'a>(settings: &'a AppSettings) -> Element<'a, Message>
     .into()
 }
 
+/// Pick list over a fixed set of values with localized labels
+fn build_choice_picker<'a, T>(
+    choices: Vec<(T, String)>,
+    selected: T,
+    on_select: fn(T) -> Message,
+) -> Element<'a, Message>
+where
+    T: Copy + PartialEq + 'a,
+{
+    let labels: Vec<String> = choices.iter().map(|(_, label)| label.clone()).collect();
+
+    let current = choices
+        .iter()
+        .find(|(value, _)| *value == selected)
+        .map(|(_, label)| label.clone());
+
+    pick_list(labels, current, move |label| {
+        let value = choices
+            .iter()
+            .find(|(_, l)| *l == label)
+            .map(|(value, _)| *value)
+            .unwrap_or(selected);
+        on_select(value)
+    })
+    .padding([6, 12])
+    .into()
+}
+
+/// Actions listed in settings: everything with bindings, plus every zone
+fn hotkey_actions(settings: &AppSettings) -> BTreeSet<HotkeyAction> {
+    let builtin_zones = SnapZone::all()
+        .iter()
+        // Centering already has its own hotkey
+        .filter(|zone| **zone != SnapZone::Center)
+        .map(|zone| zone.id().to_string());
+    let custom_zones = settings
+        .custom_zones
+        .iter()
+        .filter(|zone| SnapZone::from_id(&zone.name).is_none())
+        .map(|zone| zone.name.clone());
+
+    settings
+        .hotkeys
+        .bindings
+        .keys()
+        .cloned()
+        .chain(builtin_zones.chain(custom_zones).map(HotkeyAction::SnapToZone))
+        .collect()
+}
+
+fn build_hotkey_rows<'a>(
+    actions: Vec<HotkeyAction>,
+    settings: &'a AppSettings,
+    conflicts: &HotkeyConflicts,
+    loc: &'a Localization,
+) -> Element<'a, Message> {
+    column(actions.into_iter().map(|action| {
+        let label = hotkey_action_label(&action, settings, loc);
+        let bindings = settings.hotkeys.bindings_for(&action);
+        let warnings = (0..bindings.len())
+            .map(|index| {
+                conflicts
+                    .get(&(action.clone(), index))
+                    .map(|conflict| conflict_message(conflict, settings, loc))
+            })
+            .collect();
+        build_hotkey_row(label, bindings, warnings, action, loc)
+    }))
+    .spacing(0)
+    .into()
+}
+
 fn build_hotkey_row<'a>(
     label_text: String,
-    binding: &'a HotkeyBinding,
+    bindings: &[HotkeyBinding],
+    warnings: Vec<Option<String>>,
     action: HotkeyAction,
     loc: &'a Localization,
 ) -> Element<'a, Message> {
     let name = text(label_text).size(14).color(colors::TEXT);
 
+    let add_btn = tooltip(
+        button(interface_icon(include_bytes!("../../icons/interface/plus.svg")))
+            .style(styles::icon_button)
+            .padding(4)
+            .on_press(Message::EditHotkey(action.clone(), None)),
+        text(loc.get(keys::TOOLTIP_ADD_HOTKEY)).size(13),
+        tooltip::Position::Top,
+    )
+    .gap(4)
+    .style(styles::tooltip_container);
+
+    // One line per binding, each with its own controls
+    let binding_rows: Element<'a, Message> = if bindings.is_empty() {
+        text(loc.get(keys::HOTKEY_NOT_SET))
+            .size(12)
+            .color(colors::TEXT_DIM)
+            .into()
+    } else {
+        column(
+            bindings
+                .iter()
+                .zip(warnings)
+                .enumerate()
+                .map(|(index, (binding, warning))| {
+                    build_binding_row(binding, warning, action.clone(), index, loc)
+                }),
+        )
+        .spacing(6)
+        .align_x(Alignment::End)
+        .into()
+    };
+
+    row![
+        name,
+        add_btn,
+        iced::widget::Space::new().width(Fill),
+        binding_rows,
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center)
+    .padding([10, 0])
+    .width(Fill)
+    .into()
+}
+
+fn build_binding_row<'a>(
+    binding: &HotkeyBinding,
+    warning: Option<String>,
+    action: HotkeyAction,
+    index: usize,
+    loc: &'a Localization,
+) -> Element<'a, Message> {
     let shortcut_display = container(
-        text(binding.display_string())
+        text(binding.to_string())
             .size(12)
             .color(colors::TEXT_DIM),
     )
@@ -245,36 +418,118 @@ fn build_hotkey_row<'a>(
     let edit_btn = button(text(loc.get(keys::HOTKEY_EDIT)).size(11))
         .style(styles::secondary_button)
         .padding([4, 8])
-        .on_press(Message::EditHotkey(action));
+        .on_press(Message::EditHotkey(action.clone(), Some(index)));
+
+    let remove_btn = tooltip(
+        button(interface_icon(include_bytes!("../../icons/interface/x.svg")))
+            .style(styles::icon_button)
+            .padding(4)
+            .on_press(Message::RemoveHotkey(action.clone(), index)),
+        text(loc.get(keys::TOOLTIP_REMOVE_HOTKEY)).size(13),
+        tooltip::Position::Top,
+    )
+    .gap(4)
+    .style(styles::tooltip_container);
 
     let enabled_toggle = toggler(binding.enabled)
-        .on_toggle(move |enabled| Message::ToggleHotkey(action, enabled))
+        .on_toggle(move |enabled| Message::ToggleHotkey(action.clone(), index, enabled))
         .size(18);
 
-    row![
-        name,
-        iced::widget::Space::new().width(Fill),
-        shortcut_display,
-        edit_btn,
-        enabled_toggle,
-    ]
-    .spacing(10)
-    .align_y(Alignment::Center)
-    .padding([10, 0])
-    .width(Fill)
-    .into()
+    let controls = row![shortcut_display, edit_btn, remove_btn, enabled_toggle]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+    // Explain why an enabled binding isn't working
+    match warning {
+        Some(warning) => column![controls, text(warning).size(11).color(colors::WARNING)]
+            .spacing(4)
+            .align_x(Alignment::End)
+            .into(),
+        None => controls.into(),
+    }
 }
 
-/// Build the hotkey editing dialog
-pub fn hotkey_edit_view<'a>(action: HotkeyAction, loc: &'a Localization) -> Element<'a, Message> {
-    let action_name = match action {
+fn conflict_message(
+    conflict: &HotkeyConflict,
+    settings: &AppSettings,
+    loc: &Localization,
+) -> String {
+    match conflict {
+        HotkeyConflict::Duplicate(owner) => loc.get_with_arg(
+            keys::HOTKEY_CONFLICT_DUPLICATE,
+            "action",
+            &hotkey_action_label(owner, settings, loc),
+        ),
+        HotkeyConflict::Unsupported => loc.get(keys::HOTKEY_CONFLICT_UNSUPPORTED),
+        HotkeyConflict::Unavailable => loc.get(keys::HOTKEY_CONFLICT_UNAVAILABLE),
+    }
+}
+
+/// Small dimmed icon for inline buttons
+fn interface_icon<'a>(bytes: &'static [u8]) -> Element<'a, Message> {
+    svg(svg::Handle::from_memory(bytes))
+        .width(14)
+        .height(14)
+        .style(|_theme, _status| svg::Style {
+            color: Some(colors::TEXT_DIM),
+        })
+        .into()
+}
+
+/// Display name for a hotkey action
+pub fn hotkey_action_label(
+    action: &HotkeyAction,
+    settings: &AppSettings,
+    loc: &Localization,
+) -> String {
+    match action {
         HotkeyAction::LassoWindow => loc.get(keys::HOTKEY_LASSO),
         HotkeyAction::RefreshWindows => loc.get(keys::HOTKEY_REFRESH),
         HotkeyAction::MoveToPrimary => loc.get(keys::HOTKEY_PRIMARY),
         HotkeyAction::MoveAllToPrimary => loc.get(keys::HOTKEY_ALL_PRIMARY),
         HotkeyAction::CenterWindow => loc.get(keys::HOTKEY_CENTER),
         HotkeyAction::NextMonitor => loc.get(keys::HOTKEY_NEXT_MONITOR),
-    };
+        HotkeyAction::PreviousMonitor => loc.get(keys::HOTKEY_PREVIOUS_MONITOR),
+        HotkeyAction::Leader => loc.get(keys::HOTKEY_LEADER),
+        HotkeyAction::SendToMonitor(target) => monitor_target_label(target, settings, loc),
+        HotkeyAction::MoveInDirection(direction) => loc.get(match direction {
+            Direction::Left => keys::HOTKEY_MONITOR_LEFT,
+            Direction::Right => keys::HOTKEY_MONITOR_RIGHT,
+            Direction::Up => keys::HOTKEY_MONITOR_UP,
+            Direction::Down => keys::HOTKEY_MONITOR_DOWN,
+        }),
+        HotkeyAction::SnapToZone(zone) => SnapZone::from_id(zone)
+            .map(|zone| loc.get(zone.label_key()))
+            .unwrap_or_else(|| zone.clone()),
+    }
+}
+
+/// Label for a "move to monitor" hotkey
+///
+/// Monitors named by ID show their nickname when one is set.
+fn monitor_target_label(
+    target: &MonitorTarget,
+    settings: &AppSettings,
+    loc: &Localization,
+) -> String {
+    match target {
+        MonitorTarget::Position(position) => {
+            loc.get_with_arg(keys::HOTKEY_SEND_TO_MONITOR, "number", &position.to_string())
+        }
+        MonitorTarget::Id(id) => {
+            let name = settings.monitor_nicknames.get(id).unwrap_or(id);
+            loc.get_with_arg(keys::HOTKEY_SEND_TO_NAMED, "name", name)
+        }
+    }
+}
+
+/// Build the hotkey editing dialog
+pub fn hotkey_edit_view<'a>(
+    action: &HotkeyAction,
+    settings: &AppSettings,
+    loc: &'a Localization,
+) -> Element<'a, Message> {
+    let action_name = hotkey_action_label(action, settings, loc);
 
     let title = text(format!("{}: {}", loc.get(keys::HOTKEY_EDIT), action_name))
         .size(20)
@@ -1,6 +1,6 @@
 //! Custom styles for the application
 
-use iced::widget::{button, container, scrollable};
+use iced::widget::{button, container, scrollable, text_input};
 use iced::{Background, Border, Color, Theme};
 
 /// Colors for the dark theme
@@ -273,3 +273,25 @@ pub fn icon_button(_theme: &Theme, status: button::Status) -> button::Style {
         button::Status::Disabled => base,
     }
 }
+
+/// Text input style
+pub fn input_field(_theme: &Theme, status: text_input::Status) -> text_input::Style {
+    let border_color = match status {
+        text_input::Status::Focused { .. } => colors::PRIMARY,
+        text_input::Status::Hovered => colors::TEXT_DIM,
+        text_input::Status::Active | text_input::Status::Disabled => colors::BORDER,
+    };
+
+    text_input::Style {
+        background: Background::Color(colors::BACKGROUND),
+        border: Border {
+            color: border_color,
+            width: 1.0,
+            radius: 4.0.into(),
+        },
+        icon: colors::TEXT_DIM,
+        placeholder: colors::TEXT_DIM,
+        value: colors::TEXT,
+        selection: Color::from_rgba(0.36, 0.56, 0.96, 0.4),
+    }
+}
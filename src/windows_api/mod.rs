@@ -1,7 +1,63 @@
 //! Windows API integration modules
 
 pub mod monitors;
+pub mod pipe;
 pub mod windows;
 
 pub use monitors::*;
+pub use pipe::*;
 pub use windows::*;
+
+use crate::backend::WindowBackend;
+use crate::placement::PlacementPolicy;
+use crate::types::{MonitorInfo, WindowInfo, WindowRect};
+
+/// Win32 implementation of the window backend
+pub struct Win32Backend;
+
+impl WindowBackend for Win32Backend {
+    fn enumerate_monitors(&self) -> Vec<MonitorInfo> {
+        enumerate_monitors()
+    }
+
+    fn enumerate_windows(&self, monitors: &[MonitorInfo]) -> Vec<WindowInfo> {
+        enumerate_windows(monitors)
+    }
+
+    fn window_rect(&self, hwnd: isize) -> Result<WindowRect, String> {
+        get_window_rect(hwnd)
+    }
+
+    fn set_window_rect(&self, hwnd: isize, rect: &WindowRect) -> Result<(), String> {
+        set_window_rect(hwnd, rect)
+    }
+
+    fn minimize_window(&self, hwnd: isize) -> Result<(), String> {
+        minimize_window(hwnd)
+    }
+
+    fn move_window_to_monitor_with_options(
+        &self,
+        hwnd: isize,
+        target_monitor: &MonitorInfo,
+        source_monitor: Option<&MonitorInfo>,
+        policy: &PlacementPolicy,
+        auto_focus: bool,
+    ) -> Result<(), String> {
+        move_window_to_monitor_with_options(
+            hwnd,
+            target_monitor,
+            source_monitor,
+            policy,
+            auto_focus,
+        )
+    }
+
+    fn focus_self(&self) {
+        focus_self()
+    }
+
+    fn get_foreground_window(&self) -> Option<isize> {
+        get_foreground_window()
+    }
+}
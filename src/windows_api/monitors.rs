@@ -1,13 +1,20 @@
 //! Monitor enumeration using Windows API
 
+use crate::placement::BASE_DPI;
+use crate::topology::{parse_edid, stable_monitor_id};
 use crate::types::{MonitorInfo, WindowRect};
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
 use std::sync::Mutex;
-use windows::Win32::Foundation::{BOOL, LPARAM, RECT, TRUE};
+use windows::core::{HSTRING, PCWSTR};
+use windows::Win32::Foundation::{BOOL, ERROR_SUCCESS, LPARAM, RECT, TRUE};
 use windows::Win32::Graphics::Gdi::{
-    EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
+    EnumDisplayDevicesW, EnumDisplayMonitors, GetMonitorInfoW, DISPLAY_DEVICEW, HDC, HMONITOR,
+    MONITORINFOEXW,
 };
+use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_BINARY};
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+use windows::Win32::UI::WindowsAndMessaging::EDD_GET_DEVICE_INTERFACE_NAME;
 
 /// Enumerate all connected monitors
 pub fn enumerate_monitors() -> Vec<MonitorInfo> {
@@ -89,14 +96,93 @@ fn get_monitor_info(handle: HMONITOR, index: usize) -> Option<MonitorInfo> {
             format!("Display {}", index + 1)
         };
 
+        // Effective DPI reflects the user's scaling setting for this monitor
+        let mut dpi_x = BASE_DPI;
+        let mut dpi_y = BASE_DPI;
+        if GetDpiForMonitor(handle, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_err() {
+            dpi_x = BASE_DPI;
+        }
+
+        let device_path = display_device_path(&device_name).unwrap_or_else(|| device_name.clone());
+        let edid = read_edid(&device_path).and_then(|data| parse_edid(&data));
+        let stable_id =
+            stable_monitor_id(&device_path, edid.as_ref(), bounds.width(), bounds.height());
+
         Some(MonitorInfo {
             handle: handle.0 as isize,
             name,
             device_name,
+            device_path,
             bounds,
             work_area,
             is_primary,
             display_index: index,
+            stable_id,
+            nickname: None,
+            dpi: dpi_x,
         })
     }
 }
+
+/// Get the device interface path of the monitor attached to an adapter
+///
+/// e.g. `\\?\DISPLAY#DEL40F4#5&2a3b4c&0&UID4352#{e6f07b5f-...}`
+fn display_device_path(device_name: &str) -> Option<String> {
+    unsafe {
+        let mut device = DISPLAY_DEVICEW {
+            cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+            ..Default::default()
+        };
+
+        if !EnumDisplayDevicesW(
+            &HSTRING::from(device_name),
+            0,
+            &mut device,
+            EDD_GET_DEVICE_INTERFACE_NAME,
+        )
+        .as_bool()
+        {
+            return None;
+        }
+
+        let len = device
+            .DeviceID
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(device.DeviceID.len());
+        let path = String::from_utf16_lossy(&device.DeviceID[..len]);
+        (!path.is_empty()).then_some(path)
+    }
+}
+
+/// Read a monitor's raw EDID from the registry
+///
+/// The interface path names the device's key under `Enum\DISPLAY`.
+fn read_edid(device_path: &str) -> Option<Vec<u8>> {
+    let mut parts = device_path.trim_start_matches(r"\\?\").split('#');
+    let (_, model, instance) = (parts.next()?, parts.next()?, parts.next()?);
+    let key = HSTRING::from(format!(
+        r"SYSTEM\CurrentControlSet\Enum\DISPLAY\{}\{}\Device Parameters",
+        model, instance
+    ));
+
+    unsafe {
+        let mut data = vec![0u8; 512];
+        let mut size = data.len() as u32;
+        let result = RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            PCWSTR(key.as_ptr()),
+            &HSTRING::from("EDID"),
+            RRF_RT_REG_BINARY,
+            None,
+            Some(data.as_mut_ptr() as *mut _),
+            Some(&mut size),
+        );
+        if result != ERROR_SUCCESS {
+            return None;
+        }
+
+        data.truncate(size as usize);
+        Some(data)
+    }
+}
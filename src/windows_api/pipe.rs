@@ -0,0 +1,168 @@
+//! Named pipes for the single-instance channel
+//!
+//! Pipe names are shared by every session on the machine, so pipes are
+//! owned by and only open to the current user, and clients check the owner
+//! of the pipe they reach.
+
+use std::fs::File;
+use std::os::windows::io::{AsRawHandle, FromRawHandle};
+use windows::core::{HSTRING, PWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, LocalFree, ERROR_PIPE_CONNECTED, ERROR_SUCCESS, HANDLE, HLOCAL,
+    INVALID_HANDLE_VALUE,
+};
+use windows::Win32::Security::Authorization::{
+    ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW, GetSecurityInfo,
+    SDDL_REVISION_1, SE_KERNEL_OBJECT,
+};
+use windows::Win32::Security::{
+    GetTokenInformation, TokenUser, OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR, PSID,
+    SECURITY_ATTRIBUTES, TOKEN_QUERY, TOKEN_USER,
+};
+use windows::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+    PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+const BUFFER_SIZE: u32 = 4096;
+
+/// Security descriptor allocated by the system, freed on drop
+struct LocalDescriptor(PSECURITY_DESCRIPTOR);
+
+impl Drop for LocalDescriptor {
+    fn drop(&mut self) {
+        unsafe {
+            LocalFree(Some(HLOCAL(self.0 .0)));
+        }
+    }
+}
+
+/// String form of a SID, e.g. `S-1-5-21-…`
+unsafe fn sid_string(sid: PSID) -> windows::core::Result<String> {
+    let mut string = PWSTR::null();
+    ConvertSidToStringSidW(sid, &mut string)?;
+    let result = string.to_string();
+    LocalFree(Some(HLOCAL(string.0.cast())));
+    result.map_err(|_| windows::core::Error::from_win32())
+}
+
+/// SID of the user running this process, in string form
+pub fn current_user_sid() -> windows::core::Result<String> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)?;
+
+        let mut len = 0;
+        // The first call only reports the size needed
+        let _ = GetTokenInformation(token, TokenUser, None, 0, &mut len);
+        // u64s keep the buffer aligned for TOKEN_USER
+        let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+        let result = GetTokenInformation(
+            token,
+            TokenUser,
+            Some(buffer.as_mut_ptr().cast()),
+            len,
+            &mut len,
+        );
+        let _ = CloseHandle(token);
+        result?;
+
+        let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+        sid_string(user.User.Sid)
+    }
+}
+
+/// Descriptor for an object owned by and only open to the user `sid`
+fn private_descriptor(sid: &str) -> windows::core::Result<LocalDescriptor> {
+    let sddl = format!("O:{0}D:P(A;;GA;;;{0})", sid);
+    let mut descriptor = PSECURITY_DESCRIPTOR::default();
+    unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            &HSTRING::from(sddl),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            None,
+        )?;
+    }
+    Ok(LocalDescriptor(descriptor))
+}
+
+/// Create an instance of a named pipe for a client to connect to
+///
+/// Only the current user can open the pipe. With `first`, creation fails if
+/// another process already owns the pipe.
+pub fn create_pipe(name: &str, first: bool) -> windows::core::Result<File> {
+    let mut open_mode = PIPE_ACCESS_DUPLEX;
+    if first {
+        open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+    }
+
+    let descriptor = private_descriptor(&current_user_sid()?)?;
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor.0 .0,
+        bInheritHandle: false.into(),
+    };
+
+    unsafe {
+        let handle = CreateNamedPipeW(
+            &HSTRING::from(name),
+            open_mode,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            PIPE_UNLIMITED_INSTANCES,
+            BUFFER_SIZE,
+            BUFFER_SIZE,
+            0,
+            Some(&attributes),
+        );
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(windows::core::Error::from_win32());
+        }
+        Ok(File::from_raw_handle(handle.0))
+    }
+}
+
+/// Block until a client connects to a pipe instance
+pub fn wait_for_pipe_client(pipe: &File) -> Result<(), String> {
+    unsafe {
+        match ConnectNamedPipe(HANDLE(pipe.as_raw_handle()), None) {
+            Ok(()) => Ok(()),
+            // The client connected between creation and this call
+            Err(e) if e.code() == ERROR_PIPE_CONNECTED.to_hresult() => Ok(()),
+            Err(e) => Err(format!("Failed to wait for pipe client: {}", e)),
+        }
+    }
+}
+
+/// Whether the current user owns the pipe a client opened
+///
+/// Another user can create a pipe under the same name first; talking to it
+/// would hand them our requests.
+pub fn pipe_owned_by_current_user(pipe: &File) -> bool {
+    let Ok(user) = current_user_sid() else {
+        return false;
+    };
+
+    unsafe {
+        let mut owner = PSID::default();
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        let result = GetSecurityInfo(
+            HANDLE(pipe.as_raw_handle()),
+            SE_KERNEL_OBJECT,
+            OWNER_SECURITY_INFORMATION,
+            Some(&mut owner),
+            None,
+            None,
+            None,
+            Some(&mut descriptor),
+        );
+        if result != ERROR_SUCCESS {
+            return false;
+        }
+        // The owner points into the descriptor, so keep it until compared
+        let _descriptor = LocalDescriptor(descriptor);
+        sid_string(owner).is_ok_and(|owner| owner == user)
+    }
+}
@@ -1,29 +1,31 @@
 //! Window enumeration and manipulation using Windows API
 
+use crate::backend::finish_window_list;
+use crate::placement::{place, PlacementPolicy, ShowState};
 use crate::types::{MonitorInfo, WindowInfo, WindowRect};
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
 use std::sync::Mutex;
 use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT, TRUE};
 use windows::Win32::Graphics::Gdi::{
-    CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, SelectObject, BITMAPINFO,
-    BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetMonitorInfoW, MonitorFromWindow,
+    SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, MONITORINFO,
+    MONITOR_DEFAULTTONEAREST,
 };
 use windows::Win32::System::ProcessStatus::GetModuleBaseNameW;
 use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ};
 use windows::Win32::UI::WindowsAndMessaging::{
     EnumWindows, GetClassLongPtrW, GetIconInfo, GetWindowLongPtrW, GetWindowPlacement,
     GetWindowRect, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsIconic,
-    IsWindowVisible, SendMessageTimeoutW, SetWindowPos, ShowWindow, GCLP_HICON, GCLP_HICONSM,
-    GWL_EXSTYLE, GWL_STYLE, HWND_TOP, ICONINFO, SMTO_ABORTIFHUNG, SWP_NOZORDER, SWP_SHOWWINDOW,
-    SW_MAXIMIZE, SW_RESTORE, WINDOWPLACEMENT, WM_GETICON, WS_EX_APPWINDOW, WS_EX_NOACTIVATE,
-    WS_EX_TOOLWINDOW, WS_VISIBLE,
+    IsWindowVisible, IsZoomed, SendMessageTimeoutW, SetWindowPos, ShowWindow, GCLP_HICON,
+    GCLP_HICONSM, GWL_EXSTYLE, GWL_STYLE, HWND_TOP, ICONINFO, SMTO_ABORTIFHUNG, SWP_NOZORDER,
+    SWP_SHOWWINDOW, SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, WINDOWPLACEMENT, WM_GETICON,
+    WS_EX_APPWINDOW, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_VISIBLE,
 };
 
 /// Enumerate all visible application windows
 pub fn enumerate_windows(monitors: &[MonitorInfo]) -> Vec<WindowInfo> {
     let windows: Mutex<Vec<WindowInfo>> = Mutex::new(Vec::new());
-    let monitors_clone = monitors.to_vec();
 
     unsafe {
         let _ = EnumWindows(
@@ -33,19 +35,7 @@ pub fn enumerate_windows(monitors: &[MonitorInfo]) -> Vec<WindowInfo> {
     }
 
     let mut result = windows.into_inner().unwrap_or_default();
-
-    // Update off-screen status based on monitors
-    for window in &mut result {
-        window.is_offscreen = !is_window_on_any_monitor(&window.rect, &monitors_clone);
-        window.monitor_name = find_window_monitor(&window.rect, &monitors_clone);
-    }
-
-    // Sort: off-screen windows first, then by title
-    result.sort_by(|a, b| match (a.is_offscreen, b.is_offscreen) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
-    });
+    finish_window_list(&mut result, monitors);
 
     result
 }
@@ -111,7 +101,14 @@ unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> B
         if GetWindowPlacement(hwnd, &mut placement).is_err() {
             return TRUE;
         }
-        placement.rcNormalPosition
+        let (dx, dy) = workspace_offset(hwnd);
+        let r = placement.rcNormalPosition;
+        RECT {
+            left: r.left + dx,
+            top: r.top + dy,
+            right: r.right + dx,
+            bottom: r.bottom + dy,
+        }
     } else {
         let mut r = RECT::default();
         if GetWindowRect(hwnd, &mut r).is_err() {
@@ -153,6 +150,7 @@ unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> B
         is_visible: !is_minimized,
         is_offscreen: false,
         is_minimized,
+        is_maximized: IsZoomed(hwnd).as_bool(),
         monitor_name: None,
         icon_rgba,
         icon_size,
@@ -391,61 +389,50 @@ fn should_skip_process(process_name: &str) -> bool {
     SKIP_PROCESSES.iter().any(|p| name_lower == p.to_lowercase())
 }
 
-/// Check if a window rect intersects with any monitor
-fn is_window_on_any_monitor(rect: &WindowRect, monitors: &[MonitorInfo]) -> bool {
-    monitors
-        .iter()
-        .any(|monitor| rect.intersects(&monitor.bounds))
-}
-
-/// Find which monitor a window is primarily on
-fn find_window_monitor(rect: &WindowRect, monitors: &[MonitorInfo]) -> Option<String> {
-    let window_center = rect.center();
-
-    // Find monitor that contains the window center
-    for monitor in monitors {
-        if window_center.0 >= monitor.bounds.left
-            && window_center.0 < monitor.bounds.right
-            && window_center.1 >= monitor.bounds.top
-            && window_center.1 < monitor.bounds.bottom
-        {
-            return Some(monitor.name.clone());
-        }
+/// How far a window's workspace coordinates, which `WINDOWPLACEMENT` uses,
+/// are from screen coordinates
+///
+/// Workspace coordinates start at the work area, so they differ when the
+/// taskbar is on the top or left of the window's monitor. Tool windows use
+/// screen coordinates.
+unsafe fn workspace_offset(hwnd: HWND) -> (i32, i32) {
+    if is_tool_window(hwnd) {
+        return (0, 0);
+    }
+
+    // Uses the normal position for minimized windows
+    let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+        return (0, 0);
     }
-
-    // If center isn't on any monitor, find the one with most overlap
-    monitors
-        .iter()
-        .filter(|m| rect.intersects(&m.bounds))
-        .max_by_key(|m| {
-            let overlap_left = rect.left.max(m.bounds.left);
-            let overlap_right = rect.right.min(m.bounds.right);
-            let overlap_top = rect.top.max(m.bounds.top);
-            let overlap_bottom = rect.bottom.min(m.bounds.bottom);
-            (overlap_right - overlap_left) * (overlap_bottom - overlap_top)
-        })
-        .map(|m| m.name.clone())
+    (
+        info.rcWork.left - info.rcMonitor.left,
+        info.rcWork.top - info.rcMonitor.top,
+    )
 }
 
-/// Move a window to a specific monitor, scaling appropriately, maximizing, and focusing
-pub fn move_window_to_monitor(hwnd: isize, monitor: &MonitorInfo) -> Result<(), String> {
-    move_window_to_monitor_with_options(hwnd, monitor, None, true, true)
+unsafe fn is_tool_window(hwnd: HWND) -> bool {
+    GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW.0 != 0
 }
 
 /// Move a window to a specific monitor with configurable options
 /// - source_monitor: If provided, window size will be scaled proportionally
-/// - maximize: If true, the window will be maximized after moving
+/// - policy: Where the window lands and whether it is maximized
 /// - auto_focus: If true, the window will be brought to the foreground
 pub fn move_window_to_monitor_with_options(
     hwnd: isize,
     target_monitor: &MonitorInfo,
     source_monitor: Option<&MonitorInfo>,
-    maximize: bool,
+    policy: &PlacementPolicy,
     auto_focus: bool,
 ) -> Result<(), String> {
     unsafe {
         use windows::Win32::UI::WindowsAndMessaging::{
-            IsZoomed, SetForegroundWindow, SetWindowPlacement,
+            SetForegroundWindow, SetWindowPlacement,
         };
 
         let hwnd_handle = HWND(hwnd as *mut std::ffi::c_void);
@@ -460,48 +447,41 @@ pub fn move_window_to_monitor_with_options(
         GetWindowPlacement(hwnd_handle, &mut placement)
             .map_err(|e| format!("Failed to get window placement: {}", e))?;
 
+        // The normal position is in workspace coordinates
+        let (dx, dy) = workspace_offset(hwnd_handle);
         let current_rect = placement.rcNormalPosition;
-        let mut width = current_rect.right - current_rect.left;
-        let mut height = current_rect.bottom - current_rect.top;
-
-        // Scale window size based on monitor resolution if source is provided
-        if let Some(src) = source_monitor {
-            let src_width = src.work_area.width() as f64;
-            let src_height = src.work_area.height() as f64;
-            let tgt_width = target_monitor.work_area.width() as f64;
-            let tgt_height = target_monitor.work_area.height() as f64;
-
-            let scale_x = tgt_width / src_width;
-            let scale_y = tgt_height / src_height;
-            let scale = scale_x.min(scale_y);
-
-            width = (width as f64 * scale) as i32;
-            height = (height as f64 * scale) as i32;
-        }
-
-        // Calculate target work area dimensions
-        let work_width = target_monitor.work_area.width();
-        let work_height = target_monitor.work_area.height();
-
-        // Ensure window doesn't exceed target monitor's work area
-        width = width.min(work_width);
-        height = height.min(work_height);
-
-        // Calculate new position (center of monitor's work area)
-        let (center_x, center_y) = target_monitor.center();
-        let new_x = (center_x - width / 2).max(target_monitor.work_area.left);
-        let new_y = (center_y - height / 2).max(target_monitor.work_area.top);
-
-        // Ensure window fits within work area (right and bottom edges)
-        let new_x = new_x.min(target_monitor.work_area.right - width);
-        let new_y = new_y.min(target_monitor.work_area.bottom - height);
-
-        // Update the placement's normal position
+        let target = place(
+            &WindowRect {
+                left: current_rect.left + dx,
+                top: current_rect.top + dy,
+                right: current_rect.right + dx,
+                bottom: current_rect.bottom + dy,
+            },
+            source_monitor,
+            target_monitor,
+            policy,
+        );
+        let maximize = target.show == ShowState::Maximized;
+        let new_x = target.rect.left;
+        let new_y = target.rect.top;
+        let width = target.rect.width();
+        let height = target.rect.height();
+
+        // Update the placement's normal position, in workspace coordinates
+        // of the target monitor
+        let (dx, dy) = if is_tool_window(hwnd_handle) {
+            (0, 0)
+        } else {
+            (
+                target_monitor.work_area.left - target_monitor.bounds.left,
+                target_monitor.work_area.top - target_monitor.bounds.top,
+            )
+        };
         placement.rcNormalPosition = RECT {
-            left: new_x,
-            top: new_y,
-            right: new_x + width,
-            bottom: new_y + height,
+            left: target.rect.left - dx,
+            top: target.rect.top - dy,
+            right: target.rect.right - dx,
+            bottom: target.rect.bottom - dy,
         };
 
         // For maximized windows: we must first restore to move them, then re-maximize
@@ -590,6 +570,34 @@ pub fn focus_self() {
     }
 }
 
+/// Attach to the console of the process that started us, if it has one
+///
+/// Release builds use the GUI subsystem and start without a console, so
+/// command-line output would otherwise be lost.
+pub fn attach_parent_console() {
+    unsafe {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+        let _ = AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Make the process per-monitor DPI aware
+///
+/// The GUI gets this from winit. Without it, commands run from the command
+/// line see every monitor at 96 DPI and DPI-virtualized rectangles.
+pub fn enable_per_monitor_dpi_awareness() {
+    unsafe {
+        use windows::Win32::UI::HiDpi::{
+            SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+        };
+
+        if let Err(e) = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) {
+            tracing::warn!("Could not make the process per-monitor DPI aware: {}", e);
+        }
+    }
+}
+
 /// Get the currently focused (foreground) window handle
 pub fn get_foreground_window() -> Option<isize> {
     unsafe {
@@ -604,92 +612,53 @@ pub fn get_foreground_window() -> Option<isize> {
     }
 }
 
-/// Center a window on its current monitor
-pub fn center_window(hwnd: isize, monitors: &[MonitorInfo]) -> Result<(), String> {
+/// Set a window's position and size
+pub fn set_window_rect(hwnd: isize, rect: &WindowRect) -> Result<(), String> {
     unsafe {
         let hwnd_handle = HWND(hwnd as *mut std::ffi::c_void);
-        
-        // Get current window rect
-        let mut rect = RECT::default();
-        GetWindowRect(hwnd_handle, &mut rect)
-            .map_err(|e| format!("Failed to get window rect: {}", e))?;
-        
-        let window_rect = WindowRect {
-            left: rect.left,
-            top: rect.top,
-            right: rect.right,
-            bottom: rect.bottom,
-        };
-        
-        // Find which monitor the window is on
-        let monitor = find_window_monitor(&window_rect, monitors)
-            .and_then(|name| monitors.iter().find(|m| m.name == name))
-            .or_else(|| monitors.iter().find(|m| m.is_primary))
-            .ok_or_else(|| "No monitor found".to_string())?;
-        
-        let width = window_rect.width();
-        let height = window_rect.height();
-        
-        // Calculate centered position
-        let (center_x, center_y) = monitor.center();
-        let new_x = center_x - width / 2;
-        let new_y = center_y - height / 2;
-        
-        // Ensure window stays within work area
-        let new_x = new_x.max(monitor.work_area.left).min(monitor.work_area.right - width);
-        let new_y = new_y.max(monitor.work_area.top).min(monitor.work_area.bottom - height);
-        
+
         SetWindowPos(
             hwnd_handle,
             Some(HWND_TOP),
-            new_x,
-            new_y,
-            width,
-            height,
+            rect.left,
+            rect.top,
+            rect.width(),
+            rect.height(),
             SWP_NOZORDER | SWP_SHOWWINDOW,
         )
-        .map_err(|e| format!("Failed to center window: {}", e))?;
-        
+        .map_err(|e| format!("Failed to move window: {}", e))?;
+
         Ok(())
     }
 }
 
-/// Move a window to the next monitor in the list
-pub fn move_to_next_monitor(hwnd: isize, monitors: &[MonitorInfo]) -> Result<(), String> {
-    if monitors.is_empty() {
-        return Err("No monitors available".to_string());
-    }
-    
-    if monitors.len() == 1 {
-        return Ok(()); // Only one monitor, nothing to do
+/// Minimize a window
+pub fn minimize_window(hwnd: isize) -> Result<(), String> {
+    unsafe {
+        let hwnd_handle = HWND(hwnd as *mut std::ffi::c_void);
+
+        // The return value is the previous visibility, not success
+        let _ = ShowWindow(hwnd_handle, SW_MINIMIZE);
+
+        Ok(())
     }
-    
+}
+
+/// Get the current bounds of a window
+pub fn get_window_rect(hwnd: isize) -> Result<WindowRect, String> {
     unsafe {
         let hwnd_handle = HWND(hwnd as *mut std::ffi::c_void);
-        
-        // Get current window rect
+
         let mut rect = RECT::default();
         GetWindowRect(hwnd_handle, &mut rect)
             .map_err(|e| format!("Failed to get window rect: {}", e))?;
-        
-        let window_rect = WindowRect {
+
+        Ok(WindowRect {
             left: rect.left,
             top: rect.top,
             right: rect.right,
             bottom: rect.bottom,
-        };
-        
-        // Find current monitor index
-        let current_idx = find_window_monitor(&window_rect, monitors)
-            .and_then(|name| monitors.iter().position(|m| m.name == name))
-            .unwrap_or(0);
-        
-        // Get next monitor (cycle around)
-        let next_idx = (current_idx + 1) % monitors.len();
-        let next_monitor = &monitors[next_idx];
-        
-        // Move to next monitor
-        move_window_to_monitor_with_options(hwnd, next_monitor, Some(&monitors[current_idx]), false, true)
+        })
     }
 }
 
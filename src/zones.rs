@@ -0,0 +1,386 @@
+//! Snap zones
+//!
+//! Zones are regions of a monitor's work area given as fractions, so the same
+//! zone works on any resolution. Pure code; placement turns them into rects.
+
+use crate::localization::keys;
+use crate::placement::PlacementTarget;
+use crate::types::WindowRect;
+use serde::{Deserialize, Serialize};
+
+/// A region of a work area, as fractions of its width and height
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ZoneRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ZoneRect {
+    pub const fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Convert to pixels within a work area
+    ///
+    /// Edges are rounded independently so adjacent zones share an edge exactly.
+    pub fn resolve(&self, area: &WindowRect) -> WindowRect {
+        let w = area.width() as f64;
+        let h = area.height() as f64;
+
+        WindowRect {
+            left: area.left + (self.x * w).round() as i32,
+            top: area.top + (self.y * h).round() as i32,
+            right: area.left + ((self.x + self.width) * w).round() as i32,
+            bottom: area.top + ((self.y + self.height) * h).round() as i32,
+        }
+    }
+}
+
+/// Built-in snap zones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapZone {
+    Maximize,
+    Center,
+    LeftHalf,
+    RightHalf,
+    LeftThird,
+    CenterThird,
+    RightThird,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl SnapZone {
+    pub fn all() -> &'static [SnapZone] {
+        &[
+            SnapZone::Maximize,
+            SnapZone::Center,
+            SnapZone::LeftHalf,
+            SnapZone::RightHalf,
+            SnapZone::LeftThird,
+            SnapZone::CenterThird,
+            SnapZone::RightThird,
+            SnapZone::TopLeft,
+            SnapZone::TopRight,
+            SnapZone::BottomLeft,
+            SnapZone::BottomRight,
+        ]
+    }
+
+    /// Zone name used in settings and hotkey bindings
+    pub fn id(self) -> &'static str {
+        match self {
+            SnapZone::Maximize => "maximize",
+            SnapZone::Center => "center",
+            SnapZone::LeftHalf => "left-half",
+            SnapZone::RightHalf => "right-half",
+            SnapZone::LeftThird => "left-third",
+            SnapZone::CenterThird => "center-third",
+            SnapZone::RightThird => "right-third",
+            SnapZone::TopLeft => "top-left",
+            SnapZone::TopRight => "top-right",
+            SnapZone::BottomLeft => "bottom-left",
+            SnapZone::BottomRight => "bottom-right",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<SnapZone> {
+        SnapZone::all().iter().copied().find(|zone| zone.id() == id)
+    }
+
+    /// Localization key for the zone's display name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            SnapZone::Maximize => keys::ZONE_MAXIMIZE,
+            SnapZone::Center => keys::ZONE_CENTER,
+            SnapZone::LeftHalf => keys::ZONE_LEFT_HALF,
+            SnapZone::RightHalf => keys::ZONE_RIGHT_HALF,
+            SnapZone::LeftThird => keys::ZONE_LEFT_THIRD,
+            SnapZone::CenterThird => keys::ZONE_CENTER_THIRD,
+            SnapZone::RightThird => keys::ZONE_RIGHT_THIRD,
+            SnapZone::TopLeft => keys::ZONE_TOP_LEFT,
+            SnapZone::TopRight => keys::ZONE_TOP_RIGHT,
+            SnapZone::BottomLeft => keys::ZONE_BOTTOM_LEFT,
+            SnapZone::BottomRight => keys::ZONE_BOTTOM_RIGHT,
+        }
+    }
+
+    /// Where a window snapped to this zone lands
+    pub fn target(self) -> PlacementTarget {
+        const THIRD: f64 = 1.0 / 3.0;
+
+        match self {
+            SnapZone::Maximize => PlacementTarget::Maximize,
+            SnapZone::Center => PlacementTarget::Point { x: 0.5, y: 0.5 },
+            SnapZone::LeftHalf => PlacementTarget::Zone(ZoneRect::new(0.0, 0.0, 0.5, 1.0)),
+            SnapZone::RightHalf => PlacementTarget::Zone(ZoneRect::new(0.5, 0.0, 0.5, 1.0)),
+            SnapZone::LeftThird => PlacementTarget::Zone(ZoneRect::new(0.0, 0.0, THIRD, 1.0)),
+            SnapZone::CenterThird => PlacementTarget::Zone(ZoneRect::new(THIRD, 0.0, THIRD, 1.0)),
+            SnapZone::RightThird => {
+                PlacementTarget::Zone(ZoneRect::new(2.0 * THIRD, 0.0, THIRD, 1.0))
+            }
+            SnapZone::TopLeft => PlacementTarget::Zone(ZoneRect::new(0.0, 0.0, 0.5, 0.5)),
+            SnapZone::TopRight => PlacementTarget::Zone(ZoneRect::new(0.5, 0.0, 0.5, 0.5)),
+            SnapZone::BottomLeft => PlacementTarget::Zone(ZoneRect::new(0.0, 0.5, 0.5, 0.5)),
+            SnapZone::BottomRight => PlacementTarget::Zone(ZoneRect::new(0.5, 0.5, 0.5, 0.5)),
+        }
+    }
+}
+
+/// Share of the monitor along each edge that snaps to a zone
+const SNAP_EDGE: f64 = 0.2;
+
+/// Half-size of the region around the middle that snaps to center
+const SNAP_CENTER: f64 = 0.1;
+
+/// Snap zone under a point, given as fractions of the monitor
+///
+/// Corners snap to quarters, the left and right edges to halves and the top
+/// edge to maximize, like dragging a window to a screen edge. Returns `None`
+/// where the window should be placed freely.
+pub fn snap_zone_at(x: f64, y: f64) -> Option<SnapZone> {
+    let left = x < SNAP_EDGE;
+    let right = x > 1.0 - SNAP_EDGE;
+    let top = y < SNAP_EDGE;
+    let bottom = y > 1.0 - SNAP_EDGE;
+
+    match (left, right, top, bottom) {
+        (true, _, true, _) => Some(SnapZone::TopLeft),
+        (_, true, true, _) => Some(SnapZone::TopRight),
+        (true, _, _, true) => Some(SnapZone::BottomLeft),
+        (_, true, _, true) => Some(SnapZone::BottomRight),
+        (true, ..) => Some(SnapZone::LeftHalf),
+        (_, true, ..) => Some(SnapZone::RightHalf),
+        (_, _, true, _) => Some(SnapZone::Maximize),
+        _ if (x - 0.5).abs() < SNAP_CENTER && (y - 0.5).abs() < SNAP_CENTER => {
+            Some(SnapZone::Center)
+        }
+        _ => None,
+    }
+}
+
+/// A user-defined zone: one or more cells of a grid laid over the work area
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomZone {
+    /// Unique name, also used to bind a hotkey to the zone
+    pub name: String,
+    pub columns: u32,
+    pub rows: u32,
+    /// Zero-based cell where the zone starts
+    pub column: u32,
+    pub row: u32,
+    #[serde(default = "default_span")]
+    pub column_span: u32,
+    #[serde(default = "default_span")]
+    pub row_span: u32,
+}
+
+fn default_span() -> u32 {
+    1
+}
+
+impl CustomZone {
+    /// Region covered by the zone, or `None` if the grid is invalid
+    pub fn rect(&self) -> Option<ZoneRect> {
+        let fits = |start: u32, span: u32, count: u32| {
+            count > 0 && span > 0 && start.checked_add(span).is_some_and(|end| end <= count)
+        };
+        if !fits(self.column, self.column_span, self.columns)
+            || !fits(self.row, self.row_span, self.rows)
+        {
+            return None;
+        }
+
+        let columns = self.columns as f64;
+        let rows = self.rows as f64;
+        Some(ZoneRect::new(
+            self.column as f64 / columns,
+            self.row as f64 / rows,
+            self.column_span as f64 / columns,
+            self.row_span as f64 / rows,
+        ))
+    }
+}
+
+/// Look up a zone by id: a built-in zone name or the name of a custom zone
+///
+/// Built-in names take precedence over custom zones with the same name.
+pub fn resolve_zone(id: &str, custom: &[CustomZone]) -> Option<PlacementTarget> {
+    if let Some(zone) = SnapZone::from_id(id) {
+        return Some(zone.target());
+    }
+
+    custom
+        .iter()
+        .find(|zone| zone.name == id)
+        .and_then(CustomZone::rect)
+        .map(PlacementTarget::Zone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_support::rect;
+
+    fn grid(columns: u32, rows: u32, column: u32, row: u32) -> CustomZone {
+        CustomZone {
+            name: "zone".to_string(),
+            columns,
+            rows,
+            column,
+            row,
+            column_span: 1,
+            row_span: 1,
+        }
+    }
+
+    #[test]
+    fn custom_zones_cover_their_cells() {
+        assert_eq!(
+            grid(4, 2, 1, 1).rect(),
+            Some(ZoneRect::new(0.25, 0.5, 0.25, 0.5))
+        );
+        let wide = CustomZone {
+            column_span: 3,
+            ..grid(4, 1, 1, 0)
+        };
+        assert_eq!(wide.rect(), Some(ZoneRect::new(0.25, 0.0, 0.75, 1.0)));
+    }
+
+    #[test]
+    fn invalid_grids_have_no_rect() {
+        assert_eq!(grid(0, 2, 0, 0).rect(), None);
+        assert_eq!(grid(2, 0, 0, 0).rect(), None);
+        assert_eq!(grid(2, 2, 2, 0).rect(), None);
+        assert_eq!(grid(2, 2, 0, 2).rect(), None);
+
+        let no_span = CustomZone {
+            column_span: 0,
+            ..grid(2, 2, 0, 0)
+        };
+        assert_eq!(no_span.rect(), None);
+        let past_the_grid = CustomZone {
+            row_span: 3,
+            ..grid(2, 2, 0, 0)
+        };
+        assert_eq!(past_the_grid.rect(), None);
+        let overflowing = CustomZone {
+            column_span: u32::MAX,
+            ..grid(u32::MAX, 1, 2, 0)
+        };
+        assert_eq!(overflowing.rect(), None);
+    }
+
+    #[test]
+    fn corners_win_over_edges() {
+        for (x, y, zone) in [
+            (0.05, 0.05, SnapZone::TopLeft),
+            (0.95, 0.05, SnapZone::TopRight),
+            (0.05, 0.95, SnapZone::BottomLeft),
+            (0.95, 0.95, SnapZone::BottomRight),
+            (0.05, 0.5, SnapZone::LeftHalf),
+            (0.95, 0.5, SnapZone::RightHalf),
+            (0.5, 0.05, SnapZone::Maximize),
+            (0.5, 0.5, SnapZone::Center),
+            (0.55, 0.45, SnapZone::Center),
+        ] {
+            assert_eq!(snap_zone_at(x, y), Some(zone), "at {}, {}", x, y);
+        }
+    }
+
+    #[test]
+    fn the_rest_places_freely() {
+        // The bottom edge alone doesn't snap, and the center region is small
+        assert_eq!(snap_zone_at(0.5, 0.95), None);
+        assert_eq!(snap_zone_at(0.3, 0.5), None);
+        assert_eq!(snap_zone_at(0.5, 0.7), None);
+        // Edges are exclusive
+        assert_eq!(snap_zone_at(SNAP_EDGE, 0.5), None);
+        assert_eq!(snap_zone_at(0.5, SNAP_EDGE), None);
+    }
+
+    #[test]
+    fn zones_resolve_by_name() {
+        let custom = [
+            CustomZone {
+                name: "sidebar".to_string(),
+                ..grid(5, 1, 4, 0)
+            },
+            CustomZone {
+                name: "broken".to_string(),
+                ..grid(0, 1, 0, 0)
+            },
+        ];
+        assert_eq!(
+            resolve_zone("sidebar", &custom),
+            Some(PlacementTarget::Zone(ZoneRect::new(0.8, 0.0, 0.2, 1.0)))
+        );
+        assert_eq!(
+            resolve_zone("left-half", &custom),
+            Some(SnapZone::LeftHalf.target())
+        );
+        assert_eq!(resolve_zone("broken", &custom), None);
+        assert_eq!(resolve_zone("missing", &custom), None);
+    }
+
+    #[test]
+    fn built_in_names_win() {
+        let custom = [CustomZone {
+            name: "maximize".to_string(),
+            ..grid(2, 1, 0, 0)
+        }];
+        assert_eq!(
+            resolve_zone("maximize", &custom),
+            Some(PlacementTarget::Maximize)
+        );
+    }
+
+    #[test]
+    fn every_built_in_zone_has_a_unique_id() {
+        for zone in SnapZone::all() {
+            assert_eq!(SnapZone::from_id(zone.id()), Some(*zone));
+        }
+    }
+
+    #[test]
+    fn adjacent_zones_share_an_edge() {
+        // Thirds of 1000 pixels don't divide evenly
+        let area = rect(10, 20, 1010, 1021);
+        let thirds: Vec<WindowRect> = [
+            SnapZone::LeftThird,
+            SnapZone::CenterThird,
+            SnapZone::RightThird,
+        ]
+        .iter()
+        .map(|zone| match zone.target() {
+            PlacementTarget::Zone(rect) => rect.resolve(&area),
+            target => panic!("{:?} isn't a zone", target),
+        })
+        .collect();
+
+        assert_eq!(thirds[0].left, area.left);
+        assert_eq!(thirds[0].right, thirds[1].left);
+        assert_eq!(thirds[1].right, thirds[2].left);
+        assert_eq!(thirds[2].right, area.right);
+        assert!(thirds.iter().all(|r| r.top == 20 && r.bottom == 1021));
+        assert_eq!(thirds[0].right, 343);
+
+        let sevenths: Vec<WindowRect> = (0..7)
+            .map(|column| grid(7, 3, column, 1).rect().unwrap().resolve(&area))
+            .collect();
+        for pair in sevenths.windows(2) {
+            assert_eq!(pair[0].right, pair[1].left);
+        }
+        assert_eq!(sevenths[6].right, area.right);
+        assert_eq!((sevenths[0].top, sevenths[0].bottom), (354, 687));
+    }
+}